@@ -0,0 +1,417 @@
+//! Evaluating many expressions at once.
+//!
+//! Expressions are read one per line. Each expression is evaluated once, or once for each record of variables read
+//! from a CSV or JSON file, and the results are written as text, CSV or JSON.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, Read, Write},
+    str::FromStr,
+};
+
+use equation_eval::{
+    diagnostic::Diagnostic, eval_with_limits, limits::EvalLimits, node::Node, Error, Evaluated,
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde_json::{Map, Number, Value};
+
+use crate::bindings::Bindings;
+
+/// An expression and the line it was read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expression {
+    /// The line number, starting from 1.
+    pub line: usize,
+
+    pub text: String,
+}
+
+/// The variables of a record, in the order of the fields.
+pub type Record = Vec<(String, Evaluated)>;
+
+/// The format results are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// One value per line, errors are written with their source.
+    #[default]
+    Text,
+
+    /// A row for each result.
+    Csv,
+
+    /// An array with an object for each result.
+    Json,
+}
+
+/// The result of an expression for a record.
+#[derive(Debug)]
+pub struct Outcome<'a> {
+    pub expression: &'a Expression,
+
+    /// The number of the record starting from 1, if the expression was evaluated for records.
+    pub record: Option<usize>,
+
+    /// The value of the expression, which is `None` if there are errors or the expression was only checked.
+    pub value: Option<Evaluated>,
+
+    pub errors: Vec<Error>,
+}
+
+/// An error reading records of variables.
+#[derive(Debug)]
+pub enum VarsError {
+    Csv(csv::Error),
+
+    Json(serde_json::Error),
+
+    /// The JSON is not an object or an array of objects.
+    NotRecords,
+
+    /// A field of a JSON object which is not a number, string, bool or array of those.
+    Field {
+        /// The number of the record, starting from 1.
+        record: usize,
+        field: String,
+    },
+}
+
+impl Display for VarsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VarsError::Csv(error) => write!(f, "invalid CSV: {}", error),
+            VarsError::Json(error) => write!(f, "invalid JSON: {}", error),
+            VarsError::NotRecords => f.write_str("expected an object or an array of objects"),
+            VarsError::Field { record, field } => {
+                write!(f, "field `{}` of record {} is not a value", field, record)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VarsError {}
+
+/// Read the non-empty lines of the input as expressions.
+pub fn expressions(input: impl BufRead) -> io::Result<Vec<Expression>> {
+    let mut expressions = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+
+        if !line.trim().is_empty() {
+            expressions.push(Expression {
+                line: i + 1,
+                text: line,
+            });
+        }
+    }
+
+    Ok(expressions)
+}
+
+/// Read records from CSV with a header naming the variables.
+///
+/// Fields which are numbers or `true` or `false` are numbers and bools, other fields are strings.
+pub fn read_csv(input: impl Read) -> Result<Vec<Record>, VarsError> {
+    let mut reader = csv::Reader::from_reader(input);
+    let header = reader.headers().map_err(VarsError::Csv)?.clone();
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(VarsError::Csv)?;
+
+            Ok(header
+                .iter()
+                .zip(record.iter())
+                .map(|(name, field)| (name.trim().to_owned(), parse_field(field)))
+                .collect())
+        })
+        .collect()
+}
+
+/// Read records from a JSON object or an array of objects.
+pub fn read_json(input: impl Read) -> Result<Vec<Record>, VarsError> {
+    let value: Value = serde_json::from_reader(input).map_err(VarsError::Json)?;
+
+    let objects = match value {
+        Value::Array(array) => array,
+        object @ Value::Object(_) => vec![object],
+        _ => return Err(VarsError::NotRecords),
+    };
+
+    objects
+        .into_iter()
+        .enumerate()
+        .map(|(i, object)| {
+            let Value::Object(object) = object else {
+                return Err(VarsError::NotRecords);
+            };
+
+            object
+                .into_iter()
+                .map(|(field, value)| match from_json(&value) {
+                    Some(value) => Ok((field, value)),
+                    None => Err(VarsError::Field {
+                        record: i + 1,
+                        field,
+                    }),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Evaluate each expression, or each expression for each record.
+///
+/// Expressions are evaluated within the default [`EvalLimits`], so one expression can not exhaust the memory or time
+/// of the whole batch.
+pub fn evaluate<'a>(expressions: &'a [Expression], records: Option<&[Record]>) -> Vec<Outcome<'a>> {
+    let Some(records) = records else {
+        let bindings = Bindings::default();
+
+        return expressions
+            .iter()
+            .map(|expression| outcome(&bindings, expression, None))
+            .collect();
+    };
+
+    let mut outcomes = Vec::new();
+
+    for (i, record) in records.iter().enumerate() {
+        let mut bindings = Bindings::default();
+
+        for (name, value) in record {
+            bindings.set(name.clone(), value.clone());
+        }
+
+        outcomes.extend(
+            expressions
+                .iter()
+                .map(|expression| outcome(&bindings, expression, Some(i + 1))),
+        );
+    }
+
+    outcomes
+}
+
+fn outcome<'a>(
+    bindings: &Bindings,
+    expression: &'a Expression,
+    record: Option<usize>,
+) -> Outcome<'a> {
+    let limits = EvalLimits::default();
+
+    let (value, errors) = match eval_with_limits(bindings, &expression.text, &limits) {
+        Ok(value) => (Some(value), Vec::new()),
+        Err(error) => (None, vec![error]),
+    };
+
+    Outcome {
+        expression,
+        record,
+        value,
+        errors,
+    }
+}
+
+/// Parse each expression without evaluating it, reporting every parse error.
+pub fn check(expressions: &[Expression]) -> Vec<Outcome<'_>> {
+    expressions
+        .iter()
+        .map(|expression| Outcome {
+            expression,
+            record: None,
+            value: None,
+            errors: Node::parse_recovering_with_limits(&expression.text, &EvalLimits::default()).1,
+        })
+        .collect()
+}
+
+/// Write the outcomes in a format.
+///
+/// Text is written as one value per line to `out` and the errors are written to `err`. CSV and JSON are written to
+/// `out` and include the errors.
+pub fn write(
+    format: Format,
+    outcomes: &[Outcome<'_>],
+    out: &mut impl Write,
+    err: &mut impl Write,
+) -> io::Result<()> {
+    match format {
+        Format::Text => write_text(outcomes, out, err),
+        Format::Csv => write_csv(outcomes, out),
+        Format::Json => write_json(outcomes, out),
+    }
+}
+
+fn write_text(
+    outcomes: &[Outcome<'_>],
+    out: &mut impl Write,
+    err: &mut impl Write,
+) -> io::Result<()> {
+    for outcome in outcomes {
+        if let Some(value) = &outcome.value {
+            writeln!(out, "{}", text(value))?;
+        }
+
+        for error in &outcome.errors {
+            let mut diagnostic = Diagnostic::new(&outcome.expression.text, error)
+                .with_first_line(outcome.expression.line);
+
+            if let Some(record) = outcome.record {
+                diagnostic = diagnostic.with_note(format!("for record {}", record));
+            }
+
+            write!(err, "{}", diagnostic)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_csv(outcomes: &[Outcome<'_>], out: &mut impl Write) -> io::Result<()> {
+    let records = outcomes.iter().any(|outcome| outcome.record.is_some());
+    let mut writer = csv::Writer::from_writer(out);
+
+    let mut header = vec!["line", "expression", "value", "error"];
+
+    if records {
+        header.insert(0, "record");
+    }
+
+    writer.write_record(&header)?;
+
+    for outcome in outcomes {
+        let mut row = vec![
+            outcome.expression.line.to_string(),
+            outcome.expression.text.clone(),
+            outcome.value.as_ref().map(text).unwrap_or_default(),
+            messages(&outcome.errors),
+        ];
+
+        if let Some(record) = outcome.record {
+            row.insert(0, record.to_string());
+        }
+
+        writer.write_record(&row)?;
+    }
+
+    writer.flush()
+}
+
+fn write_json(outcomes: &[Outcome<'_>], out: &mut impl Write) -> io::Result<()> {
+    let array = outcomes
+        .iter()
+        .map(|outcome| {
+            let mut object = Map::new();
+
+            if let Some(record) = outcome.record {
+                object.insert("record".into(), record.into());
+            }
+
+            object.insert("line".into(), outcome.expression.line.into());
+            object.insert("expression".into(), outcome.expression.text.as_str().into());
+            object.insert(
+                "value".into(),
+                outcome.value.as_ref().map_or(Value::Null, to_json),
+            );
+
+            let errors = outcome
+                .errors
+                .iter()
+                .map(|error| {
+                    serde_json::json!({
+                        "message": error.kind.to_string(),
+                        "start": error.span.start,
+                        "end": error.span.end,
+                    })
+                })
+                .collect();
+            object.insert("errors".into(), Value::Array(errors));
+
+            Value::Object(object)
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut *out, &Value::Array(array))?;
+    writeln!(out)
+}
+
+/// The text of a value, with trailing zeros of numbers removed.
+fn text(value: &Evaluated) -> String {
+    match value {
+        Evaluated::Decimal(decimal) => decimal.normalize().to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn messages(errors: &[Error]) -> String {
+    errors
+        .iter()
+        .map(|error| error.kind.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn parse_field(field: &str) -> Evaluated {
+    let trimmed = field.trim();
+
+    match trimmed {
+        "true" => return Evaluated::Bool(true),
+        "false" => return Evaluated::Bool(false),
+        _ => {}
+    }
+
+    match number(trimmed) {
+        Some(decimal) => Evaluated::Decimal(decimal),
+        None => Evaluated::Str(field.to_owned()),
+    }
+}
+
+fn number(str: &str) -> Option<Decimal> {
+    Decimal::from_str(str)
+        .or_else(|_| Decimal::from_scientific(str))
+        .ok()
+}
+
+fn from_json(value: &Value) -> Option<Evaluated> {
+    let value = match value {
+        Value::Number(number) => Evaluated::Decimal(self::number(&number.to_string())?),
+        Value::Bool(bool) => Evaluated::Bool(*bool),
+        Value::String(str) => Evaluated::Str(str.clone()),
+        Value::Array(array) => Evaluated::List(array.iter().map(from_json).collect::<Option<_>>()?),
+        Value::Null | Value::Object(_) => return None,
+    };
+
+    Some(value)
+}
+
+fn to_json(value: &Evaluated) -> Value {
+    match value {
+        Evaluated::Decimal(decimal) => decimal_to_json(*decimal),
+        Evaluated::Bool(bool) => Value::Bool(*bool),
+        Evaluated::Str(str) => Value::String(str.clone()),
+        Evaluated::List(list) => Value::Array(list.iter().map(to_json).collect()),
+        Evaluated::Matrix(matrix) => Value::Array(
+            (0..matrix.rows())
+                .filter_map(|row| matrix.row(row))
+                .map(|row| Value::Array(row.iter().copied().map(decimal_to_json).collect()))
+                .collect(),
+        ),
+        Evaluated::Lambda(_) => Value::String(value.to_string()),
+    }
+}
+
+/// Integers are written exactly, other numbers are the nearest floating point number.
+fn decimal_to_json(decimal: Decimal) -> Value {
+    if decimal.is_integer() {
+        if let Some(int) = decimal.to_i64() {
+            return Value::Number(int.into());
+        }
+    }
+
+    decimal
+        .to_f64()
+        .and_then(Number::from_f64)
+        .map_or(Value::Null, Value::Number)
+}
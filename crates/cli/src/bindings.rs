@@ -0,0 +1,84 @@
+//! Variables which persist between lines.
+
+use std::collections::BTreeMap;
+
+use equation_eval::{resolve::Resolver, ErrorKind, Evaluated};
+use equation_math::{self as math, Math};
+
+/// A resolver for assigned variables and the previous result, `ans`.
+///
+/// Other names are resolved by [`Math`].
+#[derive(Default)]
+pub struct Bindings {
+    vars: BTreeMap<String, Evaluated>,
+    ans: Option<Evaluated>,
+}
+
+impl Bindings {
+    /// The name of the previous result.
+    pub const ANS: &'static str = "ans";
+
+    /// Assign a value to a variable, replacing any previous value.
+    pub fn set(&mut self, name: impl Into<String>, value: Evaluated) {
+        self.vars.insert(name.into(), value);
+    }
+
+    /// The value of a variable.
+    pub fn get(&self, name: &str) -> Option<&Evaluated> {
+        self.vars.get(name)
+    }
+
+    /// The variables in order of their names.
+    pub fn vars(&self) -> impl Iterator<Item = (&str, &Evaluated)> {
+        self.vars.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// The previous result.
+    pub fn ans(&self) -> Option<&Evaluated> {
+        self.ans.as_ref()
+    }
+
+    /// Set the previous result.
+    pub fn set_ans(&mut self, value: Evaluated) {
+        self.ans = Some(value);
+    }
+}
+
+impl Resolver for Bindings {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        if name == Self::ANS {
+            return self.ans.clone();
+        }
+
+        self.vars
+            .get(name)
+            .cloned()
+            .or_else(|| Math.resolve_const_symbol(name))
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Math.resolve_function(name, args)
+    }
+
+    fn has_function(&self, name: &str) -> bool {
+        Math.has_function(name)
+    }
+
+    fn const_symbols(&self) -> Vec<&str> {
+        let ans = self.ans.as_ref().map(|_| Self::ANS);
+
+        self.vars
+            .keys()
+            .map(String::as_str)
+            .chain(ans)
+            .chain(math::CONSTANTS.iter().map(|constant| constant.name))
+            .collect()
+    }
+
+    fn functions(&self) -> Vec<&str> {
+        math::FUNCTIONS
+            .iter()
+            .map(|function| function.name)
+            .collect()
+    }
+}
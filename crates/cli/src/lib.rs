@@ -0,0 +1,9 @@
+//! An interactive calculator for expressions.
+//!
+//! A [`Repl`](repl::Repl) evaluates one line at a time with [`eval`](equation_eval::eval), resolving names with
+//! [`Bindings`](bindings::Bindings) which remember the variables assigned by previous lines. [`batch`] evaluates
+//! expressions read from files for use in scripts.
+
+pub mod batch;
+pub mod bindings;
+pub mod repl;
@@ -0,0 +1,182 @@
+use std::{
+    env,
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand};
+use equation::{
+    batch::{self, Expression, Format, Record, VarsError},
+    repl::{Output, Repl},
+};
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+/// The file history is kept in, in the home directory.
+const HISTORY: &str = ".equation_history";
+
+/// Evaluate expressions interactively or in batches.
+#[derive(Parser)]
+#[command(name = "equation", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Start an interactive session, which is the default.
+    Repl,
+
+    /// Evaluate expressions from arguments, a file or stdin.
+    Eval(EvalArgs),
+}
+
+#[derive(Args)]
+struct EvalArgs {
+    /// Expressions to evaluate, instead of reading them one per line.
+    expressions: Vec<String>,
+
+    /// Read expressions from a file, one per line, or from stdin if the file is `-`.
+    #[arg(short, long, conflicts_with = "expressions")]
+    file: Option<PathBuf>,
+
+    /// Evaluate every expression for each record of a CSV file, or a JSON file of objects.
+    #[arg(long)]
+    vars: Option<PathBuf>,
+
+    /// The format of the results.
+    #[arg(short, long, value_enum, default_value_t)]
+    output: Format,
+
+    /// Only parse the expressions, reporting every syntax error.
+    #[arg(long, conflicts_with = "vars")]
+    check: bool,
+}
+
+/// The exit code when an expression has an error.
+const FAILED: u8 = 1;
+
+/// The exit code when the input or output could not be read or written.
+const IO_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        None | Some(Command::Repl) => match repl() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::from(IO_ERROR)
+            }
+        },
+
+        Some(Command::Eval(args)) => eval(args),
+    }
+}
+
+fn repl() -> rustyline::Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY));
+
+    if let Some(history) = &history {
+        // There is no history the first time.
+        let _ = editor.load_history(history);
+    }
+
+    let mut repl = Repl::new();
+
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error),
+        };
+
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str())?;
+        }
+
+        match repl.line(&line) {
+            Output::Print(text) => println!("{}", text),
+            Output::Error(error) => eprint!("{}", error),
+            Output::Nothing => {}
+            Output::Quit => break,
+        }
+    }
+
+    if let Some(history) = &history {
+        editor.save_history(history)?;
+    }
+
+    Ok(())
+}
+
+fn eval(args: EvalArgs) -> ExitCode {
+    let expressions = match read_expressions(&args) {
+        Ok(expressions) => expressions,
+        Err(error) => {
+            eprintln!("error: could not read expressions: {}", error);
+            return ExitCode::from(IO_ERROR);
+        }
+    };
+
+    let records = match args.vars.as_deref().map(read_vars).transpose() {
+        Ok(records) => records,
+        Err(error) => {
+            eprintln!("error: could not read variables: {}", error);
+            return ExitCode::from(IO_ERROR);
+        }
+    };
+
+    let outcomes = match args.check {
+        true => batch::check(&expressions),
+        false => batch::evaluate(&expressions, records.as_deref()),
+    };
+
+    if let Err(error) = batch::write(args.output, &outcomes, &mut io::stdout(), &mut io::stderr()) {
+        eprintln!("error: could not write results: {}", error);
+        return ExitCode::from(IO_ERROR);
+    }
+
+    match outcomes.iter().any(|outcome| !outcome.errors.is_empty()) {
+        true => ExitCode::from(FAILED),
+        false => ExitCode::SUCCESS,
+    }
+}
+
+fn read_expressions(args: &EvalArgs) -> io::Result<Vec<Expression>> {
+    if !args.expressions.is_empty() {
+        return Ok(args
+            .expressions
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Expression {
+                line: i + 1,
+                text: text.clone(),
+            })
+            .collect());
+    }
+
+    match args.file.as_deref() {
+        Some(path) if path != Path::new("-") => {
+            batch::expressions(BufReader::new(File::open(path)?))
+        }
+        _ => batch::expressions(io::stdin().lock()),
+    }
+}
+
+/// Read records from a JSON file if the extension is `json`, otherwise from a CSV file.
+fn read_vars(path: &Path) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let file = BufReader::new(File::open(path)?);
+
+    let records = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => batch::read_json(file),
+        _ => batch::read_csv(file),
+    };
+
+    records.map_err(|error: VarsError| error.into())
+}
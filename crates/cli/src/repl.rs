@@ -0,0 +1,267 @@
+//! Evaluating lines of input.
+//!
+//! A line is one of:
+//! - an expression, such as `2 * pi`, whose value becomes `ans`
+//! - an assignment, `name = expression` or `name := expression`, which also sets `ans`
+//! - a command starting with `:`, see [`HELP`]
+
+use std::fmt::Write;
+
+use equation_eval::{diagnostic::Diagnostic, eval, Evaluated};
+use equation_lexer::{Kind, TokenKind, Tokenizer};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::bindings::Bindings;
+
+/// The text printed by `:help`.
+pub const HELP: &str = "\
+expressions are evaluated and the result is stored in `ans`
+name = expression      assign a variable
+:vars                  list the variables
+:mode decimal|float|int  print numbers exactly, as floating point or truncated to integers
+:hex                   print `ans` in hexadecimal
+:help                  print this help
+:quit                  exit";
+
+/// How numbers are printed.
+///
+/// The mode only changes how results are printed, variables and `ans` always hold the exact value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// The exact decimal value.
+    #[default]
+    Decimal,
+
+    /// The nearest floating point value.
+    Float,
+
+    /// The integer part of the value.
+    Int,
+}
+
+impl Mode {
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Decimal => "decimal",
+            Mode::Float => "float",
+            Mode::Int => "int",
+        }
+    }
+}
+
+/// The result of a line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Output {
+    /// Text to print.
+    Print(String),
+
+    /// An error to print, which may span multiple lines.
+    Error(String),
+
+    /// The line was empty.
+    Nothing,
+
+    /// The user asked to exit.
+    Quit,
+}
+
+/// The state of the calculator between lines.
+#[derive(Default)]
+pub struct Repl {
+    bindings: Bindings,
+    mode: Mode,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The variables and previous result.
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// The current [`Mode`].
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Evaluate a line of input.
+    pub fn line(&mut self, line: &str) -> Output {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            return Output::Nothing;
+        }
+
+        if let Some(command) = trimmed.strip_prefix(':') {
+            return self.command(command);
+        }
+
+        let (name, start) = match assignment(line) {
+            Some((name, start)) => (Some(name), start),
+            None => (None, 0),
+        };
+
+        if let Some(name) = name {
+            if matches!(name, Bindings::ANS | "true" | "false") {
+                return Output::Error(format!("error: cannot assign to `{}`\n", name));
+            }
+        }
+
+        // Replace the name of an assignment with spaces, so the spans of errors are offsets into the line.
+        let expr = format!("{:start$}{}", "", &line[start..]);
+
+        let value = match eval(&self.bindings, &expr) {
+            Ok(value) => value,
+            Err(error) => return Output::Error(Diagnostic::new(line, &error).to_string()),
+        };
+
+        let printed = self.format(&value);
+        self.bindings.set_ans(value.clone());
+
+        match name {
+            Some(name) => {
+                self.bindings.set(name, value);
+                Output::Print(format!("{} = {}", name, printed))
+            }
+            None => Output::Print(printed),
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Output {
+        let mut words = command.split_whitespace();
+
+        match (words.next().unwrap_or(""), words.next()) {
+            ("vars", None) => {
+                let mut out = String::new();
+
+                for (name, value) in self.bindings.vars() {
+                    let _ = writeln!(out, "{} = {}", name, self.format(value));
+                }
+
+                match out.is_empty() {
+                    true => Output::Print("no variables".into()),
+                    false => Output::Print(out.trim_end().into()),
+                }
+            }
+
+            ("mode", None) => Output::Print(self.mode.name().into()),
+
+            ("mode", Some(mode)) if words.next().is_none() => {
+                self.mode = match mode {
+                    "decimal" => Mode::Decimal,
+                    "float" => Mode::Float,
+                    "int" => Mode::Int,
+                    _ => {
+                        return Output::Error(format!(
+                            "error: unknown mode `{}`, expected decimal, float or int\n",
+                            mode
+                        ))
+                    }
+                };
+
+                Output::Print(format!("mode: {}", mode))
+            }
+
+            ("hex", None) => match self.bindings.ans() {
+                Some(Evaluated::Decimal(decimal)) => match hex(*decimal) {
+                    Some(hex) => Output::Print(hex),
+                    None => Output::Error("error: `ans` is not an integer\n".into()),
+                },
+                Some(_) => Output::Error("error: `ans` is not a number\n".into()),
+                None => Output::Error("error: there is no previous result\n".into()),
+            },
+
+            ("help", None) => Output::Print(HELP.into()),
+
+            ("quit" | "q" | "exit", None) => Output::Quit,
+
+            (command, _) => Output::Error(format!(
+                "error: unknown command `:{}`, see `:help`\n",
+                command
+            )),
+        }
+    }
+
+    /// Format a value in the current mode.
+    fn format(&self, value: &Evaluated) -> String {
+        match value {
+            Evaluated::Decimal(decimal) => match self.mode {
+                Mode::Decimal => decimal.normalize().to_string(),
+                Mode::Float => decimal.to_f64().unwrap_or(f64::NAN).to_string(),
+                Mode::Int => decimal.trunc().normalize().to_string(),
+            },
+
+            Evaluated::Str(str) => format!("{:?}", str),
+
+            Evaluated::List(list) => {
+                let elements = list
+                    .iter()
+                    .map(|value| self.format(value))
+                    .collect::<Vec<_>>();
+                format!("[{}]", elements.join(", "))
+            }
+
+            Evaluated::Matrix(matrix) => {
+                let rows = (0..matrix.rows())
+                    .filter_map(|row| matrix.row(row))
+                    .map(|row| {
+                        let row = row
+                            .iter()
+                            .map(|decimal| self.format(&Evaluated::Decimal(*decimal)))
+                            .collect::<Vec<_>>();
+                        format!("[{}]", row.join(", "))
+                    })
+                    .collect::<Vec<_>>();
+                format!("[{}]", rows.join(", "))
+            }
+
+            value => value.to_string(),
+        }
+    }
+}
+
+/// The name assigned by a line and the byte offset of the assigned expression.
+fn assignment(line: &str) -> Option<(&str, usize)> {
+    let mut offset = 0;
+    let mut tokens = Tokenizer::from(line)
+        .map(|token| {
+            let start = offset;
+            offset += line[start..]
+                .chars()
+                .take(token.len)
+                .map(char::len_utf8)
+                .sum::<usize>();
+            (token.kind, start, offset)
+        })
+        .filter(|(kind, _, _)| *kind != TokenKind::Ws);
+
+    let (kind, start, end) = tokens.next()?;
+
+    if kind != TokenKind::Chars {
+        return None;
+    }
+
+    match tokens.next()? {
+        (Kind![=], _, expr) => Some((&line[start..end], expr)),
+        (Kind![:], _, _) => match tokens.next()? {
+            (Kind![=], _, expr) => Some((&line[start..end], expr)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// An integer in hexadecimal, such as `0xFF` or `-0x1A`.
+fn hex(decimal: Decimal) -> Option<String> {
+    if !decimal.is_integer() {
+        return None;
+    }
+
+    let int = decimal.to_i128()?;
+    let sign = if int < 0 { "-" } else { "" };
+
+    Some(format!("{}0x{:X}", sign, int.unsigned_abs()))
+}
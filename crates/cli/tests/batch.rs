@@ -0,0 +1,225 @@
+//! Evaluating expressions in batches
+
+use std::process::Command;
+
+use equation::batch::{self, Expression, Format};
+use equation_eval::{Error, Evaluated};
+use rust_decimal::Decimal;
+
+fn expressions(input: &str) -> Vec<Expression> {
+    batch::expressions(input.as_bytes()).unwrap()
+}
+
+/// Write outcomes and return what was written to the output and the errors.
+fn written(format: Format, outcomes: &[batch::Outcome<'_>]) -> (String, String) {
+    let mut out = Vec::new();
+    let mut err = Vec::new();
+
+    batch::write(format, outcomes, &mut out, &mut err).unwrap();
+    (
+        String::from_utf8(out).unwrap(),
+        String::from_utf8(err).unwrap(),
+    )
+}
+
+fn messages(errors: &[Error]) -> Vec<String> {
+    errors.iter().map(|error| error.kind.to_string()).collect()
+}
+
+fn equation(args: &[&str]) -> (Option<i32>, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_equation"))
+        .args(args)
+        .output()
+        .unwrap();
+
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn skip_empty_lines() {
+    let expressions = expressions("1 + 1\n\n   \n2 * 3\n");
+
+    assert_eq!(expressions.len(), 2);
+    assert_eq!(expressions[1].line, 4);
+    assert_eq!(expressions[1].text, "2 * 3");
+}
+
+#[test]
+fn text() {
+    let expressions = expressions("1 + 2\n1 / 0\nsqrt(16)\nsqr(16)");
+    let outcomes = batch::evaluate(&expressions, None);
+    let (out, err) = written(Format::Text, &outcomes);
+
+    assert_eq!(out, "3\n4\n");
+    assert!(err.starts_with("error: division by zero\n"), "{}", err);
+    assert!(err.contains("--> 2:1\n"), "{}", err);
+    assert!(err.contains("2 | 1 / 0\n"), "{}", err);
+    assert!(err.contains("= help: did you mean `sqrt`?\n"), "{}", err);
+}
+
+#[test]
+fn csv_vars() {
+    let records = batch::read_csv("x, name\n1.50,a\ntrue, b \n".as_bytes()).unwrap();
+
+    assert_eq!(
+        records[0],
+        [
+            ("x".into(), Evaluated::Decimal(Decimal::new(150, 2))),
+            ("name".into(), Evaluated::Str("a".into())),
+        ]
+    );
+    assert_eq!(records[1][0].1, Evaluated::Bool(true));
+    assert_eq!(records[1][1].1, Evaluated::Str(" b ".into()));
+}
+
+#[test]
+fn json_vars() {
+    let records =
+        batch::read_json(r#"[{"x": 2, "y": [1, 2.5], "s": "a"}, {"x": 1e2}]"#.as_bytes()).unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0][0], ("s".into(), Evaluated::Str("a".into())));
+    assert_eq!(records[1][0].1, Evaluated::Decimal(Decimal::from(100)));
+
+    let single = batch::read_json(r#"{"x": true}"#.as_bytes()).unwrap();
+    assert_eq!(single, [vec![("x".into(), Evaluated::Bool(true))]]);
+}
+
+#[test]
+fn json_invalid_vars() {
+    assert!(batch::read_json("[1, 2]".as_bytes()).is_err());
+    assert_eq!(
+        batch::read_json(r#"[{"x": 1}, {"x": null}]"#.as_bytes())
+            .unwrap_err()
+            .to_string(),
+        "field `x` of record 2 is not a value"
+    );
+    assert!(batch::read_json("{".as_bytes()).is_err());
+}
+
+#[test]
+fn text_record() {
+    let records = batch::read_csv("y\n1\n0\n".as_bytes()).unwrap();
+    let expressions = expressions("\n1 / y");
+    let outcomes = batch::evaluate(&expressions, Some(&records));
+    let (out, err) = written(Format::Text, &outcomes);
+
+    assert_eq!(out, "1\n");
+    assert!(err.contains("--> 2:1\n"), "{}", err);
+    assert!(err.contains("= note: for record 2\n"), "{}", err);
+}
+
+#[test]
+fn limits() {
+    let nested = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+    let expressions = expressions(&format!(
+        "len(identity(1000))\n{}\ninverse(identity(250))",
+        nested
+    ));
+
+    let outcomes = batch::evaluate(&expressions, None);
+    assert_eq!(messages(&outcomes[0].errors), ["too many elements"]);
+    assert_eq!(
+        messages(&outcomes[1].errors),
+        ["braces are nested too deeply"]
+    );
+    assert_eq!(
+        messages(&outcomes[2].errors),
+        ["evaluation step limit reached"]
+    );
+
+    let outcomes = batch::check(&expressions);
+    assert_eq!(
+        messages(&outcomes[1].errors),
+        ["braces are nested too deeply"]
+    );
+}
+
+#[test]
+fn csv_output() {
+    let records = batch::read_csv("x,y\n1,2\n3,0\n".as_bytes()).unwrap();
+    let expressions = expressions("x + y\nx / y");
+    let outcomes = batch::evaluate(&expressions, Some(&records));
+    let (out, _) = written(Format::Csv, &outcomes);
+
+    assert_eq!(
+        out,
+        "\
+record,line,expression,value,error
+1,1,x + y,3,
+1,2,x / y,0.5,
+2,1,x + y,3,
+2,2,x / y,,division by zero
+"
+    );
+}
+
+#[test]
+fn json_output() {
+    let expressions = expressions("[1, 2.5]\n\"a\" + 1\nconcat(\"a\", 1)");
+    let outcomes = batch::evaluate(&expressions, None);
+    let (out, err) = written(Format::Json, &outcomes);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    assert!(err.is_empty());
+    assert_eq!(json[0]["value"], serde_json::json!([1, 2.5]));
+    assert_eq!(json[1]["value"], serde_json::Value::Null);
+    assert_eq!(
+        json[1]["errors"][0]["message"],
+        "expected decimal, found string"
+    );
+    assert_eq!(json[2]["value"], "a1");
+    assert_eq!(json[2]["line"], 3);
+}
+
+#[test]
+fn check_every_error() {
+    let expressions = expressions("1 + ) * (2 +\nunknown(1)");
+    let outcomes = batch::check(&expressions);
+
+    assert_eq!(outcomes[0].errors.len(), 2);
+    assert!(outcomes[0].value.is_none());
+
+    // Names are not resolved when checking.
+    assert!(outcomes[1].errors.is_empty());
+}
+
+#[test]
+fn exit_success() {
+    let (code, out, err) = equation(&["eval", "1 + 2", "pi > 3"]);
+
+    assert_eq!(code, Some(0));
+    assert_eq!(out, "3\ntrue\n");
+    assert!(err.is_empty());
+}
+
+#[test]
+fn exit_error() {
+    let (code, out, err) = equation(&["eval", "1 + 2", "2 ^"]);
+
+    assert_eq!(code, Some(1));
+    assert_eq!(out, "3\n");
+    assert!(err.contains("unexpected end of expression"), "{}", err);
+}
+
+#[test]
+fn exit_check() {
+    assert_eq!(equation(&["eval", "--check", "unknown(1)"]).0, Some(0));
+    assert_eq!(equation(&["eval", "--check", "(1"]).0, Some(1));
+}
+
+#[test]
+fn exit_missing_file() {
+    let (code, _, err) = equation(&["eval", "--file", "does/not/exist.txt"]);
+
+    assert_eq!(code, Some(2));
+    assert!(
+        err.starts_with("error: could not read expressions"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,129 @@
+//! Lines evaluated by the REPL
+
+use equation::repl::{Mode, Output, Repl};
+
+/// Evaluate each line in a new REPL and return the output of the last line.
+fn run(lines: &[&str]) -> Output {
+    let mut repl = Repl::new();
+    let (last, rest) = lines.split_last().unwrap();
+
+    for line in rest {
+        repl.line(line);
+    }
+
+    repl.line(last)
+}
+
+macro_rules! generate_test {
+    ($name: ident: [$($line: expr),+], $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(run(&[$($line),+]), Output::Print($expected.into()));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: [$($line: expr),+], $expected: expr) => {
+        #[test]
+        fn $name() {
+            match run(&[$($line),+]) {
+                Output::Error(error) => assert_eq!(error, $expected),
+                output => panic!("expected an error, found {:?}", output),
+            }
+        }
+    };
+}
+
+generate_test!(expression: ["1 + 2 * 3"], "7");
+generate_test!(math: ["sqrt(16) + abs(-1)"], "5");
+generate_test!(assign: ["x = 2 + 3"], "x = 5");
+generate_test!(assign_colon: ["x := 4"], "x = 4");
+generate_test!(variable: ["x = 4", "y = x * 2", "x + y"], "12");
+generate_test!(reassign: ["x = 4", "x = x + 1", "x"], "5");
+generate_test!(ans: ["1 + 2", "ans * 10"], "30");
+generate_test!(ans_after_assign: ["x = 7", "ans + 1"], "8");
+generate_test!(ans_after_error: ["2", "1 / 0", "ans"], "2");
+generate_test!(comparison_is_not_assignment: ["x = 1", "x + 1 = 2"], "true");
+generate_test!(string: ["concat(\"a\", 1)"], "\"a1\"");
+generate_test!(list: ["[1, 2.5]"], "[1, 2.5]");
+generate_test!(vars: ["b = 2", "a = [1, 2]", ":vars"], "a = [1, 2]\nb = 2");
+generate_test!(no_vars: [":vars"], "no variables");
+generate_test!(mode_float: [":mode float", "1 / 4"], "0.25");
+generate_test!(mode_int: [":mode int", "7 / 2"], "3");
+generate_test!(mode_int_negative: [":mode int", "-7 / 2"], "-3");
+generate_test!(mode_int_list: [":mode int", "[1.5, 2.5]"], "[1, 2]");
+generate_test!(mode_exact_ans: [":mode int", "7 / 2", ":mode decimal", "ans"], "3.5");
+generate_test!(mode_current: [":mode float", ":mode"], "float");
+generate_test!(hex: ["255"], "255");
+generate_test!(hex_ans: ["255", ":hex"], "0xFF");
+generate_test!(hex_negative: ["-26", ":hex"], "-0x1A");
+
+generate_error!(hex_fraction: ["1.5", ":hex"], "error: `ans` is not an integer\n");
+generate_error!(hex_nothing: [":hex"], "error: there is no previous result\n");
+generate_error!(assign_ans: ["ans = 1"], "error: cannot assign to `ans`\n");
+generate_error!(
+    unknown_mode: [":mode hex"],
+    "error: unknown mode `hex`, expected decimal, float or int\n"
+);
+generate_error!(
+    unknown_command: [":foo"],
+    "error: unknown command `:foo`, see `:help`\n"
+);
+generate_error!(
+    diagnostic: ["1 + sqr(2)"],
+    "\
+error: unknown function
+ --> 1:5
+  |
+1 | 1 + sqr(2)
+  |     ^^^^^^
+  |
+  = help: did you mean `sqrt`?
+"
+);
+generate_error!(
+    diagnostic_in_assignment: ["total = 1 +"],
+    "\
+error: unexpected end of expression
+ --> 1:12
+  |
+1 | total = 1 +
+  |            ^
+"
+);
+generate_error!(
+    suggest_variable: ["total = 1", "totl * 2"],
+    "\
+error: unknown constant
+ --> 1:1
+  |
+1 | totl * 2
+  | ^^^^
+  |
+  = help: did you mean `total`?
+"
+);
+
+#[test]
+fn empty() {
+    assert_eq!(run(&["   "]), Output::Nothing);
+}
+
+#[test]
+fn quit() {
+    assert_eq!(run(&[":quit"]), Output::Quit);
+    assert_eq!(run(&[":q"]), Output::Quit);
+}
+
+#[test]
+fn state() {
+    let mut repl = Repl::new();
+
+    repl.line("x = 2");
+    repl.line(":mode float");
+
+    assert_eq!(repl.mode(), Mode::Float);
+    assert!(repl.bindings().get("x").is_some());
+    assert!(repl.bindings().ans().is_some());
+}
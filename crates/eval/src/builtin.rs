@@ -0,0 +1,399 @@
+//! Functions built into the evaluator.
+//!
+//! Built-in functions are resolved before any function provided by a [`Resolver`](crate::resolve::Resolver).
+
+use alloc::{string::String, vec::Vec};
+use core::{cmp::Ordering, fmt::Write, str::FromStr};
+use rust_decimal::{prelude::ToPrimitive, Decimal, MathematicalOps};
+
+use crate::{limits::EvalLimits, matrix::Matrix, ErrorKind, Evaluated, Type};
+
+/// The names of the built-in functions, excluding those which take a closure.
+pub(crate) const FUNCTIONS: &[&str] = &[
+    "concat",
+    "len",
+    "upper",
+    "lower",
+    "substr",
+    "contains",
+    "to_number",
+    "format",
+    "sum",
+    "avg",
+    "min",
+    "max",
+    "median",
+    "stddev",
+    "transpose",
+    "det",
+    "inverse",
+    "identity",
+];
+
+/// Call a built-in function.
+///
+/// Returns [`None`] if there is no built-in function with the name. Fails if the result is larger than the limits
+/// allow, functions which could build a large result check the limits before or while building it.
+pub(crate) fn call(
+    name: &str,
+    args: &[Evaluated],
+    limits: &EvalLimits,
+) -> Option<Result<Evaluated, ErrorKind>> {
+    let result = match name {
+        "concat" => concat(args, limits),
+        "len" => len(args),
+        "upper" => arity(args).and_then(|[value]| Ok(Evaluated::Str(str(value)?.to_uppercase()))),
+        "lower" => arity(args).and_then(|[value]| Ok(Evaluated::Str(str(value)?.to_lowercase()))),
+        "substr" => substr(args),
+        "contains" => arity(args)
+            .and_then(|[value, pattern]| Ok(Evaluated::Bool(str(value)?.contains(str(pattern)?)))),
+        "to_number" => arity(args).and_then(|[value]| to_number(str(value)?)),
+        "format" => format(args, limits),
+        "sum" => total(args),
+        "avg" => avg(args),
+        "min" => extreme(args, Ordering::Less),
+        "max" => extreme(args, Ordering::Greater),
+        "median" => median(args),
+        "stddev" => stddev(args),
+        "transpose" => {
+            arity(args).and_then(|[value]| Ok(Evaluated::Matrix(matrix(value)?.transpose())))
+        }
+        "det" => arity(args).and_then(|[value]| matrix(value)?.det().map(Evaluated::Decimal)),
+        "inverse" => {
+            arity(args).and_then(|[value]| matrix(value)?.inverse().map(Evaluated::Matrix))
+        }
+        "identity" => arity(args).and_then(|[n]| {
+            let n = index(n)?;
+            limits.check_elements(n.saturating_mul(n))?;
+
+            Matrix::identity(n)
+                .map(Evaluated::Matrix)
+                .ok_or(ErrorKind::InvalidArgument)
+        }),
+        _ => return None,
+    };
+
+    Some(result.and_then(|value| limits.check_size(&value).map(|()| value)))
+}
+
+/// The number of steps of a call of a built-in function, in addition to the step of the call.
+///
+/// The determinant and inverse of an `n` by `n` matrix take `n³` multiplications.
+pub(crate) fn cost(name: &str, args: &[Evaluated]) -> usize {
+    match (name, args) {
+        ("det" | "inverse", [Evaluated::Matrix(matrix)]) => {
+            let n = matrix.rows();
+            n.saturating_mul(n).saturating_mul(n)
+        }
+        _ => 0,
+    }
+}
+
+/// Returns whether there is a built-in function with the name.
+pub(crate) fn exists(name: &str) -> bool {
+    name == "if"
+        || crate::lambda::FUNCTIONS.contains(&name)
+        || call(name, &[], &EvalLimits::UNLIMITED).is_some()
+}
+
+/// Write the textual representation of a value.
+pub(crate) fn write(value: &Evaluated, out: &mut String) {
+    match value {
+        Evaluated::Decimal(decimal) => {
+            let _ = write!(out, "{}", decimal);
+        }
+        Evaluated::Bool(bool) => out.push_str(if *bool { "true" } else { "false" }),
+        Evaluated::Str(str) => out.push_str(str),
+        Evaluated::List(list) => {
+            out.push('[');
+
+            for (i, value) in list.iter().enumerate() {
+                if i != 0 {
+                    out.push_str(", ");
+                }
+
+                write(value, out);
+            }
+
+            out.push(']');
+        }
+        Evaluated::Matrix(matrix) => write(&Evaluated::List(rows(matrix)), out),
+        Evaluated::Lambda(closure) => {
+            out.push('(');
+
+            for (i, param) in closure.params().enumerate() {
+                if i != 0 {
+                    out.push_str(", ");
+                }
+
+                out.push_str(param);
+            }
+
+            out.push_str(") -> ...");
+        }
+    }
+}
+
+/// The rows of a matrix as lists.
+pub(crate) fn rows(matrix: &Matrix) -> Vec<Evaluated> {
+    (0..matrix.rows())
+        .filter_map(|row| matrix.row(row))
+        .map(|row| Evaluated::List(row.iter().copied().map(Evaluated::Decimal).collect()))
+        .collect()
+}
+
+fn arity<const N: usize>(args: &[Evaluated]) -> Result<&[Evaluated; N], ErrorKind> {
+    args.try_into().map_err(|_| ErrorKind::ArgumentCount {
+        expected: N,
+        found: args.len(),
+    })
+}
+
+fn mismatch(expected: Type, value: &Evaluated) -> ErrorKind {
+    ErrorKind::TypeMismatch {
+        expected,
+        found: value.ty(),
+    }
+}
+
+fn str(value: &Evaluated) -> Result<&str, ErrorKind> {
+    match value {
+        Evaluated::Str(str) => Ok(str),
+        value => Err(mismatch(Type::Str, value)),
+    }
+}
+
+/// A matrix, or a list of rows which can be converted to a matrix.
+fn matrix(value: &Evaluated) -> Result<Matrix, ErrorKind> {
+    match value {
+        Evaluated::Matrix(matrix) => Ok(matrix.clone()),
+        Evaluated::List(rows) => Matrix::from_rows(rows).ok_or(mismatch(Type::Matrix, value)),
+        value => Err(mismatch(Type::Matrix, value)),
+    }
+}
+
+/// A non-negative integer used as an index.
+pub(crate) fn index(value: &Evaluated) -> Result<usize, ErrorKind> {
+    match value {
+        Evaluated::Decimal(decimal) if decimal.is_integer() => {
+            decimal.to_usize().ok_or(ErrorKind::InvalidArgument)
+        }
+        Evaluated::Decimal(_) => Err(ErrorKind::InvalidArgument),
+        value => Err(mismatch(Type::Decimal, value)),
+    }
+}
+
+/// `concat(values...)`: join the textual representation of each value.
+fn concat(args: &[Evaluated], limits: &EvalLimits) -> Result<Evaluated, ErrorKind> {
+    let mut out = String::new();
+
+    for value in args {
+        write(value, &mut out);
+        limits.check_string_len(out.len())?;
+    }
+
+    Ok(Evaluated::Str(out))
+}
+
+/// The values an aggregate function operates on.
+///
+/// Aggregates take either a single list or the values as variadic arguments.
+fn values(args: &[Evaluated]) -> &[Evaluated] {
+    match args {
+        [Evaluated::List(list)] => list,
+        args => args,
+    }
+}
+
+/// The values of an aggregate function, which must all be numbers.
+fn numbers(args: &[Evaluated]) -> Result<Vec<Decimal>, ErrorKind> {
+    values(args)
+        .iter()
+        .map(|value| match value {
+            Evaluated::Decimal(decimal) => Ok(*decimal),
+            value => Err(mismatch(Type::Decimal, value)),
+        })
+        .collect()
+}
+
+/// The values of an aggregate function which must have at least one value.
+fn non_empty(args: &[Evaluated]) -> Result<Vec<Decimal>, ErrorKind> {
+    let numbers = numbers(args)?;
+
+    if numbers.is_empty() {
+        return Err(ErrorKind::InvalidArgument);
+    }
+
+    Ok(numbers)
+}
+
+/// `len(str)`, `len(list)` or `len(values...)`: the number of characters in a string, otherwise the number of values.
+fn len(args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    let len = match args {
+        [Evaluated::Str(str)] => str.chars().count(),
+        [Evaluated::List(list)] => list.len(),
+        [Evaluated::Matrix(matrix)] => matrix.rows(),
+        [value] => return Err(mismatch(Type::List, value)),
+        args => args.len(),
+    };
+
+    Ok(Evaluated::Decimal(Decimal::from(len)))
+}
+
+/// `sum(values...)`: the sum of the values.
+pub(crate) fn total(args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    numbers(args)
+        .and_then(|numbers| sum(&numbers))
+        .map(Evaluated::Decimal)
+}
+
+fn sum(numbers: &[Decimal]) -> Result<Decimal, ErrorKind> {
+    numbers.iter().try_fold(Decimal::ZERO, |sum, value| {
+        sum.checked_add(*value).ok_or(ErrorKind::Overflow)
+    })
+}
+
+fn mean(numbers: &[Decimal]) -> Result<Decimal, ErrorKind> {
+    sum(numbers)?
+        .checked_div(Decimal::from(numbers.len()))
+        .ok_or(ErrorKind::Overflow)
+}
+
+/// `avg(values...)`: the arithmetic mean.
+fn avg(args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    mean(&non_empty(args)?).map(Evaluated::Decimal)
+}
+
+/// `min(values...)` and `max(values...)`.
+fn extreme(args: &[Evaluated], ordering: Ordering) -> Result<Evaluated, ErrorKind> {
+    let numbers = non_empty(args)?;
+    let extreme = numbers
+        .into_iter()
+        .reduce(|a, b| if b.cmp(&a) == ordering { b } else { a });
+
+    extreme
+        .map(Evaluated::Decimal)
+        .ok_or(ErrorKind::InvalidArgument)
+}
+
+/// `median(values...)`: the middle value, or the mean of the two middle values.
+fn median(args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    let mut numbers = non_empty(args)?;
+    numbers.sort_unstable();
+
+    let mid = numbers.len() / 2;
+    let median = match numbers.len() % 2 {
+        0 => mean(&numbers[mid - 1..=mid])?,
+        _ => numbers[mid],
+    };
+
+    Ok(Evaluated::Decimal(median))
+}
+
+/// `stddev(values...)`: the population standard deviation.
+fn stddev(args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    let numbers = non_empty(args)?;
+    let mean = mean(&numbers)?;
+
+    let squares = numbers
+        .iter()
+        .map(|value| {
+            value
+                .checked_sub(mean)
+                .and_then(|diff| diff.checked_mul(diff))
+                .ok_or(ErrorKind::Overflow)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let variance = sum(&squares)?
+        .checked_div(Decimal::from(numbers.len()))
+        .ok_or(ErrorKind::Overflow)?;
+
+    variance
+        .sqrt()
+        .map(Evaluated::Decimal)
+        .ok_or(ErrorKind::Undefined)
+}
+
+/// `substr(str, start)` or `substr(str, start, count)`: a range of characters of a string.
+///
+/// The range is clamped to the end of the string.
+fn substr(args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    let (value, start, count) = match args {
+        [value, start] => (value, index(start)?, usize::MAX),
+        [value, start, count] => (value, index(start)?, index(count)?),
+        args => {
+            return Err(ErrorKind::ArgumentCount {
+                expected: if args.len() < 2 { 2 } else { 3 },
+                found: args.len(),
+            })
+        }
+    };
+
+    let substr = str(value)?.chars().skip(start).take(count).collect();
+    Ok(Evaluated::Str(substr))
+}
+
+/// `to_number(str)`: parse a string as a decimal number.
+fn to_number(str: &str) -> Result<Evaluated, ErrorKind> {
+    let str = str.trim();
+
+    let decimal = if str.contains(['e', 'E']) {
+        Decimal::from_scientific(str)
+    } else {
+        Decimal::from_str(str)
+    };
+
+    decimal
+        .map(Evaluated::Decimal)
+        .map_err(|_| ErrorKind::InvalidNumber)
+}
+
+/// `format(template, values...)`: replace each `{}` in the template with the next value.
+///
+/// `{{` and `}}` are used to write literal braces.
+fn format(args: &[Evaluated], limits: &EvalLimits) -> Result<Evaluated, ErrorKind> {
+    let Some((template, values)) = args.split_first() else {
+        return Err(ErrorKind::ArgumentCount {
+            expected: 1,
+            found: 0,
+        });
+    };
+
+    let template = str(template)?;
+    let mut values = values.iter();
+    let mut placeholders = 0;
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                out.push(c);
+            }
+
+            ('{', Some('}')) => {
+                chars.next();
+                placeholders += 1;
+
+                if let Some(value) = values.next() {
+                    write(value, &mut out);
+                    limits.check_string_len(out.len())?;
+                }
+            }
+
+            ('{' | '}', _) => return Err(ErrorKind::InvalidArgument),
+            (c, _) => out.push(c),
+        }
+    }
+
+    if placeholders != args.len() - 1 {
+        return Err(ErrorKind::ArgumentCount {
+            expected: placeholders + 1,
+            found: args.len(),
+        });
+    }
+
+    Ok(Evaluated::Str(out))
+}
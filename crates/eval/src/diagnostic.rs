@@ -0,0 +1,314 @@
+//! Rendering errors with the source they occurred in.
+//!
+//! A [`Diagnostic`] prints the message of an error followed by the lines of the source covered by its span, with the
+//! span underlined:
+//!
+//! ```text
+//! error: unknown function
+//!  --> 1:5
+//!   |
+//! 1 | 1 + sqr(2)
+//!   |     ^^^^^^ not provided by the resolver
+//!   |
+//!   = help: did you mean `sqrt`?
+//! ```
+//!
+//! Columns count characters rather than bytes, tabs are expanded and wide characters are underlined with two carets.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter, Write};
+use unicode_width::UnicodeWidthChar;
+
+use crate::{node::Span, Error, ErrorKind, Type};
+
+/// The number of columns a tab is expanded to.
+const TAB_WIDTH: usize = 4;
+
+/// An error and the source it occurred in, which is rendered by [`Display`].
+pub struct Diagnostic<'a> {
+    source: &'a str,
+    kind: ErrorKind,
+    span: Span,
+    label: Option<String>,
+    notes: Vec<(NoteKind, String)>,
+
+    /// The number of the first line of the source.
+    first_line: usize,
+}
+
+#[derive(Clone, Copy)]
+enum NoteKind {
+    Help,
+    Note,
+}
+
+impl<'a> Diagnostic<'a> {
+    /// Create a diagnostic for an error which occurred in `source`.
+    ///
+    /// The [suggestions](Error::suggestions) of the error are added as a help message, see [`Error::help`].
+    pub fn new(source: &'a str, error: &Error) -> Self {
+        let notes = error
+            .help()
+            .map(|help| (NoteKind::Help, help))
+            .into_iter()
+            .collect();
+
+        Self {
+            source,
+            kind: error.kind,
+            span: error.span,
+            label: None,
+            notes,
+            first_line: 1,
+        }
+    }
+
+    /// Set the number of the first line of the source, when the source was read from a line of a larger file.
+    ///
+    /// The location and the numbers in the gutter count lines from it instead of from 1.
+    pub fn with_first_line(mut self, line: usize) -> Self {
+        self.first_line = line;
+        self
+    }
+
+    /// Set the label printed after the underline.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Add a help message, such as a suggested fix, below the source.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.notes.push((NoteKind::Help, help.into()));
+        self
+    }
+
+    /// Add a note below the source.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push((NoteKind::Note, note.into()));
+        self
+    }
+
+    /// The span clamped to the source and to character boundaries.
+    fn span(&self) -> Span {
+        let floor = |mut offset: usize| {
+            offset = offset.min(self.source.len());
+
+            while !self.source.is_char_boundary(offset) {
+                offset -= 1;
+            }
+
+            offset
+        };
+
+        let start = floor(self.span.start);
+
+        Span {
+            start,
+            end: floor(self.span.end).max(start),
+        }
+    }
+}
+
+impl Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let span = self.span();
+        let lines = lines(self.source).collect::<Vec<_>>();
+
+        // The first and last lines covered by the span.
+        let first = lines
+            .iter()
+            .rposition(|(start, _)| *start <= span.start)
+            .unwrap_or(0);
+        let last = lines
+            .iter()
+            .rposition(|(start, _)| *start < span.end)
+            .unwrap_or(0)
+            .max(first);
+
+        let (line_start, line) = lines[first];
+        let column = line[..(span.start - line_start).min(line.len())]
+            .chars()
+            .count()
+            + 1;
+        let gutter = digits(last + self.first_line);
+
+        writeln!(f, "error: {}", self.kind)?;
+        writeln!(
+            f,
+            "{:gutter$}--> {}:{}",
+            "",
+            first + self.first_line,
+            column
+        )?;
+        writeln!(f, "{:gutter$} |", "")?;
+
+        for (i, &(start, line)) in lines.iter().enumerate().take(last + 1).skip(first) {
+            // The part of the span on this line.
+            let from = (span.start.max(start) - start).min(line.len());
+            let to = (span.end - start).clamp(from, line.len());
+
+            writeln!(f, "{:>gutter$} | {}", i + self.first_line, Expanded(line))?;
+            write!(
+                f,
+                "{:gutter$} | {:offset$}",
+                "",
+                "",
+                offset = width(&line[..from])
+            )?;
+
+            for _ in 0..width(&line[from..to]).max(1) {
+                f.write_char('^')?;
+            }
+
+            match &self.label {
+                Some(label) if i == last => writeln!(f, " {}", label)?,
+                _ => writeln!(f)?,
+            }
+        }
+
+        if !self.notes.is_empty() {
+            writeln!(f, "{:gutter$} |", "")?;
+        }
+
+        for (kind, message) in &self.notes {
+            let kind = match kind {
+                NoteKind::Help => "help",
+                NoteKind::Note => "note",
+            };
+
+            writeln!(f, "{:gutter$} = {}: {}", "", kind, message)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The lines of the source and the byte offset each starts at, excluding line endings.
+fn lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source.split('\n').scan(0, |start, line| {
+        let offset = *start;
+        *start += line.len() + 1;
+        Some((offset, line.strip_suffix('\r').unwrap_or(line)))
+    })
+}
+
+/// The number of columns taken by a string when printed.
+fn width(str: &str) -> usize {
+    str.chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    match c {
+        '\t' => TAB_WIDTH,
+        c => c.width().unwrap_or(0),
+    }
+}
+
+fn digits(mut n: usize) -> usize {
+    let mut digits = 1;
+
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+
+    digits
+}
+
+/// A line with tabs expanded to spaces.
+struct Expanded<'a>(&'a str);
+
+impl Display for Expanded<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\t' => write!(f, "{:TAB_WIDTH$}", "")?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Error {
+    /// A help message listing the suggestions of the error, such as ``did you mean `sqrt`?``.
+    ///
+    /// Returns [`None`] if the error has no suggestions.
+    pub fn help(&self) -> Option<String> {
+        match self.suggestions.is_empty() {
+            true => None,
+            false => Some(format!("did you mean `{}`?", self.suggestions.join("`, `"))),
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::InvalidToken => f.write_str("invalid token"),
+            ErrorKind::UnexpectedToken => f.write_str("unexpected token"),
+            ErrorKind::UnexpectedEnd => f.write_str("unexpected end of expression"),
+            ErrorKind::InvalidNumber => f.write_str("invalid number"),
+            ErrorKind::UnterminatedString => f.write_str("unterminated string"),
+            ErrorKind::InvalidEscape => f.write_str("invalid escape sequence"),
+            ErrorKind::ArgumentCount { expected, found } => write!(
+                f,
+                "expected {} argument{}, found {}",
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found
+            ),
+            ErrorKind::UnknownConstant => f.write_str("unknown constant"),
+            ErrorKind::UnknownFunction => f.write_str("unknown function"),
+            ErrorKind::UnknownOperator => f.write_str("unknown operator"),
+            ErrorKind::Redefinition => f.write_str("name is already defined"),
+            ErrorKind::RecursionLimit => f.write_str("recursion limit reached"),
+            ErrorKind::IterationLimit => f.write_str("iteration limit reached"),
+            ErrorKind::NoConvergence => f.write_str("no solution found"),
+            ErrorKind::InvalidArgument => f.write_str("invalid argument"),
+            ErrorKind::OutOfRange => f.write_str("index out of range"),
+            ErrorKind::LengthMismatch { expected, found } => write!(
+                f,
+                "expected a list of length {}, found length {}",
+                expected, found
+            ),
+            ErrorKind::ShapeMismatch { expected, found } => write!(
+                f,
+                "expected a {}x{} matrix, found a {}x{} matrix",
+                expected.0, expected.1, found.0, found.1
+            ),
+            ErrorKind::SingularMatrix => f.write_str("matrix is singular"),
+            ErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            ErrorKind::MissingOperand => f.write_str("missing operand"),
+            ErrorKind::DivideByZero => f.write_str("division by zero"),
+            ErrorKind::Overflow => f.write_str("overflow"),
+            ErrorKind::Undefined => f.write_str("undefined value"),
+            ErrorKind::InputTooLong => f.write_str("input is too long"),
+            ErrorKind::TooManyTokens => f.write_str("too many tokens"),
+            ErrorKind::NestingTooDeep => f.write_str("braces are nested too deeply"),
+            ErrorKind::StepLimit => f.write_str("evaluation step limit reached"),
+            ErrorKind::StackOverflow => f.write_str("evaluation stack overflow"),
+            ErrorKind::ExponentTooLarge => f.write_str("exponent is too large"),
+            ErrorKind::FactorialTooLarge => f.write_str("factorial argument is too large"),
+            ErrorKind::TooManyElements => f.write_str("too many elements"),
+            ErrorKind::StringTooLong => f.write_str("string is too long"),
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Decimal => "decimal",
+            Type::Bool => "bool",
+            Type::Str => "string",
+            Type::List => "list",
+            Type::Matrix => "matrix",
+            Type::Lambda => "lambda",
+        })
+    }
+}
@@ -0,0 +1,976 @@
+//! Exporting parsed expressions to LaTeX and MathML.
+//!
+//! The nodes of an expression are in postfix order, [`latex`] and [`mathml`] first rebuild the tree of the expression
+//! and then write it out with parentheses only where the precedence of the operators needs them. Division is written
+//! as a fraction, `sqrt` as a root, `^` as a superscript and names of Greek letters such as `pi` as the letter.
+
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    node::{
+        EquationKind, Jump, JumpKind, LogicalKind, Node, OperatorKind, Span, Spanned, UnaryKind,
+    },
+    string, Error, ErrorKind,
+};
+
+/// Names which are written as Greek letters.
+pub(crate) const GREEK: &[(&str, &str, char)] = &[
+    ("alpha", "\\alpha", 'α'),
+    ("beta", "\\beta", 'β'),
+    ("gamma", "\\gamma", 'γ'),
+    ("delta", "\\delta", 'δ'),
+    ("epsilon", "\\epsilon", 'ε'),
+    ("zeta", "\\zeta", 'ζ'),
+    ("eta", "\\eta", 'η'),
+    ("theta", "\\theta", 'θ'),
+    ("iota", "\\iota", 'ι'),
+    ("kappa", "\\kappa", 'κ'),
+    ("lambda", "\\lambda", 'λ'),
+    ("mu", "\\mu", 'μ'),
+    ("nu", "\\nu", 'ν'),
+    ("xi", "\\xi", 'ξ'),
+    ("pi", "\\pi", 'π'),
+    ("rho", "\\rho", 'ρ'),
+    ("sigma", "\\sigma", 'σ'),
+    ("tau", "\\tau", 'τ'),
+    ("upsilon", "\\upsilon", 'υ'),
+    ("phi", "\\phi", 'φ'),
+    ("chi", "\\chi", 'χ'),
+    ("psi", "\\psi", 'ψ'),
+    ("omega", "\\omega", 'ω'),
+    ("Gamma", "\\Gamma", 'Γ'),
+    ("Delta", "\\Delta", 'Δ'),
+    ("Theta", "\\Theta", 'Θ'),
+    ("Lambda", "\\Lambda", 'Λ'),
+    ("Xi", "\\Xi", 'Ξ'),
+    ("Pi", "\\Pi", 'Π'),
+    ("Sigma", "\\Sigma", 'Σ'),
+    ("Phi", "\\Phi", 'Φ'),
+    ("Psi", "\\Psi", 'Ψ'),
+    ("Omega", "\\Omega", 'Ω'),
+];
+
+/// Functions which LaTeX has an operator for, such as `\sin`.
+pub(crate) const OPERATORS: &[&str] =
+    &["sin", "cos", "tan", "exp", "ln", "log", "min", "max", "det"];
+
+/// The precedence of expressions, from loosest to tightest.
+///
+/// An operand with a lower precedence than its operator needs is put in parentheses.
+mod precedence {
+    pub const LAMBDA: u8 = 0;
+    pub const OR: u8 = 1;
+    pub const AND: u8 = 2;
+    pub const EQUATION: u8 = 3;
+
+    /// Sums, products and integrals, which extend as far right as their body.
+    pub const SERIES: u8 = 4;
+    pub const ADD: u8 = 5;
+    pub const MUL: u8 = 6;
+    pub const PREFIX: u8 = 7;
+
+    /// Fractions and conditionals, which are grouped by their layout but not as the base of a power.
+    pub const GROUPED: u8 = 8;
+    pub const POW: u8 = 9;
+    pub const POSTFIX: u8 = 10;
+    pub const ATOM: u8 = 11;
+}
+
+/// A node of the tree of an expression.
+enum Expr<'n> {
+    Unary(&'n UnaryKind<'n>),
+    Neg(Box<Expr<'n>>),
+    Not(Box<Expr<'n>>),
+    Factorial(Box<Expr<'n>>),
+    Operator(OperatorKind, Box<Expr<'n>>, Box<Expr<'n>>),
+    Equation(EquationKind, Box<Expr<'n>>, Box<Expr<'n>>),
+    Logical(LogicalKind, Box<Expr<'n>>, Box<Expr<'n>>),
+
+    /// A custom infix operator, which is written as its name.
+    Custom(&'n str, Box<Expr<'n>>, Box<Expr<'n>>),
+    Index(Box<Expr<'n>>, Box<Expr<'n>>),
+    Function(&'n str, Vec<Expr<'n>>),
+    List(Vec<Expr<'n>>),
+    Lambda(&'n [Cow<'n, str>], Box<Expr<'n>>),
+    If(Box<Expr<'n>>, Box<Expr<'n>>, Box<Expr<'n>>),
+
+    /// `sum` or `prod` of a body from a lower to an upper bound, or `integrate`.
+    Series {
+        kind: Series,
+        var: &'n str,
+        from: Box<Expr<'n>>,
+        to: Box<Expr<'n>>,
+        body: Box<Expr<'n>>,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Series {
+    Sum,
+    Prod,
+    Integral,
+}
+
+impl Expr<'_> {
+    fn precedence(&self) -> u8 {
+        match self {
+            // The precedence of a custom operator is not known, so it is in parentheses wherever it is an operand.
+            Expr::Lambda(..) | Expr::Custom(..) => precedence::LAMBDA,
+            Expr::Logical(LogicalKind::Or, ..) => precedence::OR,
+            Expr::Logical(..) => precedence::AND,
+            Expr::Equation(..) => precedence::EQUATION,
+            Expr::Series { .. } => precedence::SERIES,
+            Expr::Operator(OperatorKind::Add | OperatorKind::Sub, ..) => precedence::ADD,
+            Expr::Operator(OperatorKind::Div | OperatorKind::IntDiv, ..) | Expr::If(..) => {
+                precedence::GROUPED
+            }
+            Expr::Operator(OperatorKind::Pow, ..) => precedence::POW,
+            Expr::Operator(..) => precedence::MUL,
+            Expr::Neg(_) | Expr::Not(_) => precedence::PREFIX,
+            Expr::Factorial(_) | Expr::Index(..) => precedence::POSTFIX,
+            Expr::Unary(_) | Expr::Function(..) | Expr::List(_) => precedence::ATOM,
+        }
+    }
+}
+
+/// The lowest precedence of the left and right operands of an infix operator which are not put in parentheses.
+///
+/// Operators other than `^` are left associative, so an operand on the right with the same precedence needs
+/// parentheses. Comparisons do not chain, so a comparison on either side needs parentheses.
+fn operands(precedence: u8) -> (u8, u8) {
+    match precedence {
+        precedence::EQUATION => (precedence + 1, precedence + 1),
+        _ => (precedence, precedence + 1),
+    }
+}
+
+/// The lowest precedence of the operand of `-` which is not put in parentheses.
+///
+/// A negation is put in parentheses, so `-(-x)` is not written as `--x`.
+fn negated(operand: &Expr<'_>) -> u8 {
+    match operand {
+        Expr::Neg(_) => precedence::ATOM,
+        _ => precedence::PREFIX,
+    }
+}
+
+/// Rebuild the tree of an expression from its nodes.
+///
+/// Fails with the error of a [`Node::Error`], or [`ErrorKind::MissingOperand`] if the nodes were not produced by the
+/// parser.
+fn tree<'n>(nodes: &'n [Node<'n>]) -> Result<Expr<'n>, Error> {
+    let mut stack = Vec::new();
+
+    // The index of the last node of the else branch of each conditional which is not yet complete.
+    let mut conditionals = Vec::new();
+
+    for (i, node) in nodes.iter().enumerate() {
+        let span = node.span();
+        let pop =
+            |stack: &mut Vec<Expr<'n>>| stack.pop().map(Box::new).ok_or_else(|| missing(span));
+
+        let expr = match node {
+            Node::Unary(unary) => Expr::Unary(&unary.kind),
+
+            Node::Operator(operator) => match operator.kind {
+                OperatorKind::Neg => Expr::Neg(pop(&mut stack)?),
+                OperatorKind::Factorial => Expr::Factorial(pop(&mut stack)?),
+                kind => {
+                    let rhs = pop(&mut stack)?;
+                    Expr::Operator(kind, pop(&mut stack)?, rhs)
+                }
+            },
+
+            Node::Equation(equation) => {
+                let rhs = pop(&mut stack)?;
+                Expr::Equation(equation.kind, pop(&mut stack)?, rhs)
+            }
+
+            Node::Logical(logical) => match logical.kind {
+                LogicalKind::Not => Expr::Not(pop(&mut stack)?),
+                kind => {
+                    let rhs = pop(&mut stack)?;
+                    Expr::Logical(kind, pop(&mut stack)?, rhs)
+                }
+            },
+
+            Node::Custom(custom) => {
+                let rhs = pop(&mut stack)?;
+                Expr::Custom(&custom.name, pop(&mut stack)?, rhs)
+            }
+
+            Node::Index(_) => {
+                let index = pop(&mut stack)?;
+                Expr::Index(pop(&mut stack)?, index)
+            }
+
+            Node::List(list) => Expr::List(split(&mut stack, list.len, span)?),
+
+            Node::Function(function) => {
+                let args = split(&mut stack, function.args, span)?;
+                series(&function.name, args)
+            }
+
+            Node::Lambda(lambda) => Expr::Lambda(&lambda.params, Box::new(tree(&lambda.body)?)),
+
+            // The jump over the else branch of a conditional, the other jumps are part of `&&`, `||` and the
+            // condition of a conditional, which are complete without them.
+            Node::Jump(Jump {
+                kind: JumpKind::Always,
+                skip,
+                ..
+            }) => {
+                conditionals.push(i + skip);
+                continue;
+            }
+
+            Node::Jump(_) => continue,
+
+            Node::Error(error) => return Err(error.clone()),
+        };
+
+        stack.push(expr);
+
+        while conditionals.last() == Some(&i) {
+            conditionals.pop();
+
+            let otherwise = pop(&mut stack)?;
+            let then = pop(&mut stack)?;
+            let condition = pop(&mut stack)?;
+            stack.push(Expr::If(condition, then, otherwise));
+        }
+    }
+
+    match stack.pop() {
+        Some(expr) if stack.is_empty() => Ok(expr),
+        _ => Err(missing(
+            nodes
+                .last()
+                .map_or(Span { start: 0, end: 0 }, Spanned::span),
+        )),
+    }
+}
+
+fn missing(span: Span) -> Error {
+    Error {
+        kind: ErrorKind::MissingOperand,
+        span,
+        suggestions: Vec::new(),
+    }
+}
+
+/// Split the last `len` expressions off the stack.
+fn split<'n>(stack: &mut Vec<Expr<'n>>, len: usize, span: Span) -> Result<Vec<Expr<'n>>, Error> {
+    let at = stack.len().checked_sub(len).ok_or_else(|| missing(span))?;
+    Ok(stack.split_off(at))
+}
+
+/// A call of a function, or a series if the function is `sum`, `prod` or `integrate` with a body bound to a name.
+fn series<'n>(name: &'n str, args: Vec<Expr<'n>>) -> Expr<'n> {
+    let args: [Expr<'n>; 3] = match args.try_into() {
+        Ok(args) => args,
+        Err(args) => return Expr::Function(name, args),
+    };
+
+    // The parser moves the body of `sum(k, from, to, body)` after the bounds, and the body of
+    // `integrate(body, x, from, to)` stays before the bounds.
+    let (kind, var, from, to, body) = match (name, args) {
+        ("sum", [from, to, Expr::Lambda([var], body)]) => (Series::Sum, var, from, to, body),
+        ("prod", [from, to, Expr::Lambda([var], body)]) => (Series::Prod, var, from, to, body),
+        ("integrate", [Expr::Lambda([var], body), from, to]) => {
+            (Series::Integral, var, from, to, body)
+        }
+        (_, args) => return Expr::Function(name, args.into()),
+    };
+
+    Expr::Series {
+        kind,
+        var,
+        from: Box::new(from),
+        to: Box::new(to),
+        body,
+    }
+}
+
+/// The contents of a string literal, with escape sequences replaced.
+fn text<'n>(raw: &'n str) -> Cow<'n, str> {
+    match string::unescape(raw) {
+        Ok(text) => Cow::Owned(text),
+        Err(_) => Cow::Borrowed(raw),
+    }
+}
+
+/// Split a name like `x_1` into the name and its subscript.
+fn subscript(name: &str) -> (&str, Option<&str>) {
+    match name.split_once('_') {
+        Some((base, sub)) if !base.is_empty() && !sub.is_empty() => (base, Some(sub)),
+        _ => (name, None),
+    }
+}
+
+/// Export the nodes of an expression to LaTeX.
+///
+/// ```
+/// use equation_eval::{export::latex, node::Node};
+///
+/// let nodes = Node::parse("(1 + x) / 2 * sqrt(pi)").unwrap().collect::<Vec<_>>();
+/// assert_eq!(latex(&nodes).unwrap(), "\\frac{1 + x}{2} \\cdot \\sqrt{\\pi}");
+/// ```
+pub fn latex(nodes: &[Node<'_>]) -> Result<String, Error> {
+    let mut out = String::new();
+    Latex(&mut out).expr(&tree(nodes)?);
+    Ok(out)
+}
+
+struct Latex<'o>(&'o mut String);
+
+impl Latex<'_> {
+    fn expr(&mut self, expr: &Expr<'_>) {
+        let precedence = expr.precedence();
+
+        match expr {
+            Expr::Unary(kind) => self.unary(kind),
+
+            Expr::Neg(operand) => {
+                self.0.push('-');
+                self.operand(operand, negated(operand));
+            }
+
+            Expr::Not(operand) => {
+                self.0.push_str("\\lnot ");
+                self.operand(operand, precedence::PREFIX);
+            }
+
+            Expr::Factorial(operand) => {
+                self.operand(operand, precedence::ATOM);
+                self.0.push('!');
+            }
+
+            Expr::Operator(OperatorKind::Div, lhs, rhs) => {
+                self.0.push_str("\\frac{");
+                self.expr(lhs);
+                self.0.push_str("}{");
+                self.expr(rhs);
+                self.0.push('}');
+            }
+
+            Expr::Operator(OperatorKind::IntDiv, lhs, rhs) => {
+                self.0.push_str("\\left\\lfloor \\frac{");
+                self.expr(lhs);
+                self.0.push_str("}{");
+                self.expr(rhs);
+                self.0.push_str("} \\right\\rfloor");
+            }
+
+            Expr::Operator(OperatorKind::Pow, lhs, rhs) => {
+                self.operand(lhs, precedence::POSTFIX);
+                self.0.push_str("^{");
+                self.expr(rhs);
+                self.0.push('}');
+            }
+
+            Expr::Operator(kind, lhs, rhs) => {
+                let operator = match kind {
+                    OperatorKind::Add => " + ",
+                    OperatorKind::Sub => " - ",
+                    OperatorKind::Mod => " \\bmod ",
+                    _ => " \\cdot ",
+                };
+
+                self.infix(lhs, operator, rhs, precedence);
+            }
+
+            Expr::Custom(name, lhs, rhs) => {
+                self.operand(lhs, precedence::POSTFIX);
+                self.0.push_str(" \\mathbin{\\mathrm{");
+                self.escape(name);
+                self.0.push_str("}} ");
+                self.operand(rhs, precedence::POSTFIX);
+            }
+
+            Expr::Equation(kind, lhs, rhs) => {
+                let operator = match kind {
+                    EquationKind::Eq => " = ",
+                    EquationKind::Neq => " \\neq ",
+                    EquationKind::Gt => " > ",
+                    EquationKind::Lt => " < ",
+                    EquationKind::Ge => " \\geq ",
+                    EquationKind::Le => " \\leq ",
+                };
+
+                self.infix(lhs, operator, rhs, precedence);
+            }
+
+            Expr::Logical(kind, lhs, rhs) => {
+                let operator = match kind {
+                    LogicalKind::Or => " \\lor ",
+                    _ => " \\land ",
+                };
+
+                self.infix(lhs, operator, rhs, precedence);
+            }
+
+            Expr::Index(list, index) => {
+                self.operand(list, precedence::POSTFIX);
+                self.0.push_str("\\left[");
+                self.expr(index);
+                self.0.push_str("\\right]");
+            }
+
+            Expr::Function(name, args) => self.function(name, args),
+
+            Expr::List(values) => {
+                self.0.push_str("\\left[");
+                self.list(values);
+                self.0.push_str("\\right]");
+            }
+
+            Expr::Lambda(params, body) => {
+                match params {
+                    [param] => self.name(param),
+                    params => {
+                        self.0.push_str("\\left(");
+
+                        for (i, param) in params.iter().enumerate() {
+                            if i != 0 {
+                                self.0.push_str(", ");
+                            }
+
+                            self.name(param);
+                        }
+
+                        self.0.push_str("\\right)");
+                    }
+                }
+
+                self.0.push_str(" \\mapsto ");
+                self.expr(body);
+            }
+
+            Expr::If(condition, then, otherwise) => {
+                self.0.push_str("\\begin{cases} ");
+                self.expr(then);
+                self.0.push_str(" & \\text{if } ");
+                self.expr(condition);
+                self.0.push_str(" \\\\ ");
+                self.expr(otherwise);
+                self.0.push_str(" & \\text{otherwise} \\end{cases}");
+            }
+
+            Expr::Series {
+                kind,
+                var,
+                from,
+                to,
+                body,
+            } => {
+                let (symbol, lower) = match kind {
+                    Series::Sum => ("\\sum", true),
+                    Series::Prod => ("\\prod", true),
+                    Series::Integral => ("\\int", false),
+                };
+
+                self.0.push_str(symbol);
+                self.0.push_str("_{");
+
+                if lower {
+                    self.name(var);
+                    self.0.push('=');
+                }
+
+                self.expr(from);
+                self.0.push_str("}^{");
+                self.expr(to);
+                self.0.push_str("} ");
+                self.operand(body, precedence::MUL);
+
+                if let Series::Integral = kind {
+                    self.0.push_str(" \\, d");
+                    self.name(var);
+                }
+            }
+        }
+    }
+
+    /// Write an infix operator, putting the operands in parentheses if they bind looser than the operator.
+    fn infix(&mut self, lhs: &Expr<'_>, operator: &str, rhs: &Expr<'_>, precedence: u8) {
+        let (left, right) = operands(precedence);
+
+        self.operand(lhs, left);
+        self.0.push_str(operator);
+        self.operand(rhs, right);
+    }
+
+    /// Write an operand, in parentheses if its precedence is lower than `min`.
+    fn operand(&mut self, expr: &Expr<'_>, min: u8) {
+        if expr.precedence() >= min {
+            return self.expr(expr);
+        }
+
+        self.0.push_str("\\left(");
+        self.expr(expr);
+        self.0.push_str("\\right)");
+    }
+
+    fn list(&mut self, values: &[Expr<'_>]) {
+        for (i, value) in values.iter().enumerate() {
+            if i != 0 {
+                self.0.push_str(", ");
+            }
+
+            self.expr(value);
+        }
+    }
+
+    fn unary(&mut self, kind: &UnaryKind<'_>) {
+        match kind {
+            UnaryKind::Int(int) => {
+                let _ = write!(self.0, "{}", int);
+            }
+
+            UnaryKind::Decimal(decimal) => {
+                let _ = write!(self.0, "{}", decimal);
+            }
+
+            UnaryKind::Bool(bool) => {
+                let _ = write!(self.0, "\\mathrm{{{}}}", bool);
+            }
+
+            UnaryKind::Str(raw) => {
+                self.0.push_str("\\text{``");
+
+                for c in text(raw).chars() {
+                    match c {
+                        '\\' => self.0.push_str("\\textbackslash{}"),
+                        '^' => self.0.push_str("\\^{}"),
+                        '~' => self.0.push_str("\\~{}"),
+                        '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                            self.0.push('\\');
+                            self.0.push(c);
+                        }
+                        c => self.0.push(c),
+                    }
+                }
+
+                self.0.push_str("''}");
+            }
+
+            UnaryKind::Constant(name) => self.name(name),
+        }
+    }
+
+    /// Write a name, as a Greek letter if it is the name of one and with any subscript after a `_`.
+    fn name(&mut self, name: &str) {
+        let (base, sub) = subscript(name);
+
+        match GREEK.iter().find(|(greek, ..)| *greek == base) {
+            Some((_, latex, _)) => self.0.push_str(latex),
+            None if base.chars().count() == 1 => self.0.push_str(base),
+            None => {
+                self.0.push_str("\\mathrm{");
+                self.escape(base);
+                self.0.push('}');
+            }
+        }
+
+        if let Some(sub) = sub {
+            self.0.push_str("_{");
+            self.escape(sub);
+            self.0.push('}');
+        }
+    }
+
+    fn escape(&mut self, name: &str) {
+        for c in name.chars() {
+            if c == '_' {
+                self.0.push('\\');
+            }
+
+            self.0.push(c);
+        }
+    }
+
+    fn function(&mut self, name: &str, args: &[Expr<'_>]) {
+        let delimiters = match (name, args) {
+            ("sqrt", [arg]) => {
+                self.0.push_str("\\sqrt{");
+                self.expr(arg);
+                self.0.push('}');
+                return;
+            }
+
+            ("abs", [_]) => ("\\left|", "\\right|"),
+            ("floor", [_]) => ("\\left\\lfloor ", "\\right\\rfloor"),
+            ("ceil", [_]) => ("\\left\\lceil ", "\\right\\rceil"),
+
+            _ => {
+                if OPERATORS.contains(&name) {
+                    self.0.push('\\');
+                    self.0.push_str(name);
+                } else if name.chars().count() == 1 {
+                    self.0.push_str(name);
+                } else {
+                    self.0.push_str("\\operatorname{");
+                    self.escape(name);
+                    self.0.push('}');
+                }
+
+                ("\\left(", "\\right)")
+            }
+        };
+
+        self.0.push_str(delimiters.0);
+        self.list(args);
+        self.0.push_str(delimiters.1);
+    }
+}
+
+/// Export the nodes of an expression to presentation MathML.
+///
+/// The result is a `<math>` element. Text is escaped, and operators which are not ASCII are written as the
+/// characters themselves.
+///
+/// ```
+/// use equation_eval::{export::mathml, node::Node};
+///
+/// let nodes = Node::parse("x^2").unwrap().collect::<Vec<_>>();
+/// assert_eq!(
+///     mathml(&nodes).unwrap(),
+///     "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><msup><mrow><mi>x</mi></mrow><mrow><mn>2</mn></mrow></msup></math>"
+/// );
+/// ```
+pub fn mathml(nodes: &[Node<'_>]) -> Result<String, Error> {
+    let expr = tree(nodes)?;
+    let mut out = String::from("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">");
+
+    MathMl(&mut out).expr(&expr);
+    out.push_str("</math>");
+    Ok(out)
+}
+
+struct MathMl<'o>(&'o mut String);
+
+impl MathMl<'_> {
+    fn expr(&mut self, expr: &Expr<'_>) {
+        let precedence = expr.precedence();
+
+        match expr {
+            Expr::Unary(kind) => self.unary(kind),
+
+            Expr::Neg(operand) => {
+                self.mo("−");
+                self.operand(operand, negated(operand));
+            }
+
+            Expr::Not(operand) => {
+                self.mo("¬");
+                self.operand(operand, precedence::PREFIX);
+            }
+
+            Expr::Factorial(operand) => {
+                self.operand(operand, precedence::ATOM);
+                self.mo("!");
+            }
+
+            Expr::Operator(OperatorKind::Div, lhs, rhs) => {
+                self.0.push_str("<mfrac>");
+                self.row(lhs);
+                self.row(rhs);
+                self.0.push_str("</mfrac>");
+            }
+
+            Expr::Operator(OperatorKind::IntDiv, lhs, rhs) => {
+                self.0.push_str("<mrow>");
+                self.mo("⌊");
+                self.0.push_str("<mfrac>");
+                self.row(lhs);
+                self.row(rhs);
+                self.0.push_str("</mfrac>");
+                self.mo("⌋");
+                self.0.push_str("</mrow>");
+            }
+
+            Expr::Operator(OperatorKind::Pow, lhs, rhs) => {
+                self.0.push_str("<msup><mrow>");
+                self.operand(lhs, precedence::POSTFIX);
+                self.0.push_str("</mrow>");
+                self.row(rhs);
+                self.0.push_str("</msup>");
+            }
+
+            Expr::Operator(kind, lhs, rhs) => {
+                let operator = match kind {
+                    OperatorKind::Add => "+",
+                    OperatorKind::Sub => "−",
+                    OperatorKind::Mod => "mod",
+                    _ => "⋅",
+                };
+
+                self.infix(lhs, operator, rhs, precedence);
+            }
+
+            Expr::Custom(name, lhs, rhs) => {
+                self.operand(lhs, precedence::POSTFIX);
+                self.0.push_str("<mo>");
+                self.escape(name);
+                self.0.push_str("</mo>");
+                self.operand(rhs, precedence::POSTFIX);
+            }
+
+            Expr::Equation(kind, lhs, rhs) => {
+                let operator = match kind {
+                    EquationKind::Eq => "=",
+                    EquationKind::Neq => "≠",
+                    EquationKind::Gt => "&gt;",
+                    EquationKind::Lt => "&lt;",
+                    EquationKind::Ge => "≥",
+                    EquationKind::Le => "≤",
+                };
+
+                self.infix(lhs, operator, rhs, precedence);
+            }
+
+            Expr::Logical(kind, lhs, rhs) => {
+                let operator = match kind {
+                    LogicalKind::Or => "∨",
+                    _ => "∧",
+                };
+
+                self.infix(lhs, operator, rhs, precedence);
+            }
+
+            Expr::Index(list, index) => {
+                self.operand(list, precedence::POSTFIX);
+                self.mo("[");
+                self.expr(index);
+                self.mo("]");
+            }
+
+            Expr::Function(name, args) => self.function(name, args),
+
+            Expr::List(values) => {
+                self.0.push_str("<mrow>");
+                self.mo("[");
+                self.list(values);
+                self.mo("]");
+                self.0.push_str("</mrow>");
+            }
+
+            Expr::Lambda(params, body) => {
+                match params {
+                    [param] => self.name(param),
+                    params => {
+                        self.0.push_str("<mrow>");
+                        self.mo("(");
+
+                        for (i, param) in params.iter().enumerate() {
+                            if i != 0 {
+                                self.mo(",");
+                            }
+
+                            self.name(param);
+                        }
+
+                        self.mo(")");
+                        self.0.push_str("</mrow>");
+                    }
+                }
+
+                self.mo("↦");
+                self.expr(body);
+            }
+
+            Expr::If(condition, then, otherwise) => {
+                self.0.push_str("<mrow>");
+                self.mo("{");
+                self.0.push_str("<mtable><mtr><mtd>");
+                self.expr(then);
+                self.0.push_str("</mtd><mtd><mtext>if&#xa0;</mtext>");
+                self.expr(condition);
+                self.0.push_str("</mtd></mtr><mtr><mtd>");
+                self.expr(otherwise);
+                self.0
+                    .push_str("</mtd><mtd><mtext>otherwise</mtext></mtd></mtr></mtable></mrow>");
+            }
+
+            Expr::Series {
+                kind,
+                var,
+                from,
+                to,
+                body,
+            } => {
+                match kind {
+                    Series::Sum | Series::Prod => {
+                        self.0.push_str("<munderover>");
+                        self.mo(match kind {
+                            Series::Sum => "∑",
+                            _ => "∏",
+                        });
+                        self.0.push_str("<mrow>");
+                        self.name(var);
+                        self.mo("=");
+                        self.expr(from);
+                        self.0.push_str("</mrow>");
+                        self.row(to);
+                        self.0.push_str("</munderover>");
+                    }
+
+                    Series::Integral => {
+                        self.0.push_str("<msubsup>");
+                        self.mo("∫");
+                        self.row(from);
+                        self.row(to);
+                        self.0.push_str("</msubsup>");
+                    }
+                }
+
+                self.operand(body, precedence::MUL);
+
+                if let Series::Integral = kind {
+                    self.0.push_str("<mspace width=\"0.167em\"/><mi>d</mi>");
+                    self.name(var);
+                }
+            }
+        }
+    }
+
+    fn mo(&mut self, operator: &str) {
+        self.0.push_str("<mo>");
+        self.0.push_str(operator);
+        self.0.push_str("</mo>");
+    }
+
+    /// Write an expression as a single element.
+    fn row(&mut self, expr: &Expr<'_>) {
+        self.0.push_str("<mrow>");
+        self.expr(expr);
+        self.0.push_str("</mrow>");
+    }
+
+    fn infix(&mut self, lhs: &Expr<'_>, operator: &str, rhs: &Expr<'_>, precedence: u8) {
+        let (left, right) = operands(precedence);
+
+        self.operand(lhs, left);
+        self.mo(operator);
+        self.operand(rhs, right);
+    }
+
+    /// Write an operand, in parentheses if its precedence is lower than `min`.
+    fn operand(&mut self, expr: &Expr<'_>, min: u8) {
+        if expr.precedence() >= min {
+            return self.expr(expr);
+        }
+
+        self.0.push_str("<mrow>");
+        self.mo("(");
+        self.expr(expr);
+        self.mo(")");
+        self.0.push_str("</mrow>");
+    }
+
+    fn list(&mut self, values: &[Expr<'_>]) {
+        for (i, value) in values.iter().enumerate() {
+            if i != 0 {
+                self.mo(",");
+            }
+
+            self.expr(value);
+        }
+    }
+
+    fn unary(&mut self, kind: &UnaryKind<'_>) {
+        match kind {
+            UnaryKind::Int(int) => {
+                let _ = write!(self.0, "<mn>{}</mn>", int);
+            }
+
+            UnaryKind::Decimal(decimal) => {
+                let _ = write!(self.0, "<mn>{}</mn>", decimal);
+            }
+
+            UnaryKind::Bool(bool) => {
+                let _ = write!(self.0, "<mi>{}</mi>", bool);
+            }
+
+            UnaryKind::Str(raw) => {
+                self.0.push_str("<ms>");
+                self.escape(&text(raw));
+                self.0.push_str("</ms>");
+            }
+
+            UnaryKind::Constant(name) => self.name(name),
+        }
+    }
+
+    /// Write a name, as a Greek letter if it is the name of one and with any subscript after a `_`.
+    fn name(&mut self, name: &str) {
+        let (base, sub) = subscript(name);
+
+        if sub.is_some() {
+            self.0.push_str("<msub>");
+        }
+
+        self.0.push_str("<mi>");
+
+        match GREEK.iter().find(|(greek, ..)| *greek == base) {
+            Some(&(_, _, letter)) => self.0.push(letter),
+            None => self.escape(base),
+        }
+
+        self.0.push_str("</mi>");
+
+        if let Some(sub) = sub {
+            let element = match sub.chars().all(|c| c.is_ascii_digit()) {
+                true => "mn",
+                false => "mi",
+            };
+
+            let _ = write!(self.0, "<{}>", element);
+            self.escape(sub);
+            let _ = write!(self.0, "</{}></msub>", element);
+        }
+    }
+
+    fn escape(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '&' => self.0.push_str("&amp;"),
+                '<' => self.0.push_str("&lt;"),
+                '>' => self.0.push_str("&gt;"),
+                '"' => self.0.push_str("&quot;"),
+                c => self.0.push(c),
+            }
+        }
+    }
+
+    fn function(&mut self, name: &str, args: &[Expr<'_>]) {
+        let delimiters = match (name, args) {
+            ("sqrt", [arg]) => {
+                self.0.push_str("<msqrt>");
+                self.expr(arg);
+                self.0.push_str("</msqrt>");
+                return;
+            }
+
+            ("abs", [_]) => ("|", "|"),
+            ("floor", [_]) => ("⌊", "⌋"),
+            ("ceil", [_]) => ("⌈", "⌉"),
+
+            _ => {
+                self.0.push_str("<mi>");
+                self.escape(name);
+                self.0.push_str("</mi><mo>&#x2061;</mo>");
+
+                ("(", ")")
+            }
+        };
+
+        self.0.push_str("<mrow>");
+        self.mo(delimiters.0);
+        self.list(args);
+        self.mo(delimiters.1);
+        self.0.push_str("</mrow>");
+    }
+}
@@ -0,0 +1,452 @@
+//! Syntax highlighting of expressions.
+//!
+//! [`lexical`] classifies each token of an expression on its own, [`semantic`] also parses the expression to tell
+//! constants provided by a resolver from the parameters of lambdas. The highlights can be rendered with [`ansi`]
+//! colours or [`html`] spans, and [`Braces`] pairs up the braces of an expression so matching braces can be shown
+//! together.
+//!
+//! Expressions are lexed with the default [`LexerConfig`], the `_with_config` variants highlight other dialects such
+//! as one with `#` comments.
+
+use alloc::{string::String, vec::Vec};
+use equation_lexer::{LexerConfig, Token, TokenKind, Tokenizer};
+
+use crate::{
+    limits::EvalLimits,
+    node::{Lambda, Node, Span, Spanned, Unary, UnaryKind},
+    parse::{self, Parser},
+    resolve::Resolver,
+};
+
+/// The style class of a part of an expression.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Class {
+    Whitespace,
+
+    /// A number literal.
+    Number,
+
+    /// A string literal, including the quotes.
+    String,
+
+    /// An operator such as `+`, `&&` or the arrow of a lambda.
+    Operator,
+
+    /// `(`, `)`, `[`, `]`, `{` or `}`.
+    Brace,
+
+    /// `,`, `;` or `:`.
+    Separator,
+
+    /// `true`, `false` or `if`.
+    Keyword,
+
+    /// The name of a called function.
+    Function,
+
+    /// A name which is resolved as a constant.
+    Constant,
+
+    /// A name bound by a lambda.
+    Variable,
+
+    /// A name which is not known to be a constant or variable.
+    ///
+    /// Before the expression is parsed every name which is not a function or keyword is a name.
+    Name,
+
+    /// A comment.
+    Comment,
+
+    /// A token which is not valid in any expression.
+    Invalid,
+}
+
+impl Class {
+    /// The name of the class, which is used as the class of [`html`] spans.
+    pub fn name(self) -> &'static str {
+        match self {
+            Class::Whitespace => "whitespace",
+            Class::Number => "number",
+            Class::String => "string",
+            Class::Operator => "operator",
+            Class::Brace => "brace",
+            Class::Separator => "separator",
+            Class::Keyword => "keyword",
+            Class::Function => "function",
+            Class::Constant => "constant",
+            Class::Variable => "variable",
+            Class::Name => "name",
+            Class::Comment => "comment",
+            Class::Invalid => "invalid",
+        }
+    }
+
+    /// The ANSI escape sequence which starts the style of the class, if the class is styled.
+    pub fn ansi(self) -> Option<&'static str> {
+        let style = match self {
+            Class::Number => "\x1b[36m",
+            Class::String => "\x1b[32m",
+            Class::Operator => "\x1b[33m",
+            Class::Keyword => "\x1b[35;1m",
+            Class::Function => "\x1b[34m",
+            Class::Constant => "\x1b[35m",
+            Class::Variable => "\x1b[3m",
+            Class::Comment => "\x1b[90m",
+            Class::Invalid => "\x1b[31;4m",
+            Class::Whitespace | Class::Brace | Class::Separator | Class::Name => return None,
+        };
+
+        Some(style)
+    }
+}
+
+/// A part of an expression and its class.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    pub class: Class,
+    pub span: Span,
+}
+
+/// The ANSI escape sequence which resets the style.
+const RESET: &str = "\x1b[0m";
+
+/// Classify each token of an expression.
+///
+/// Names are keywords, functions if they are followed by `(` and otherwise [`Class::Name`]. The highlights cover the
+/// whole expression, in order.
+pub fn lexical(str: &str) -> Vec<Highlight> {
+    lexical_with_config(str, LexerConfig::default())
+}
+
+/// Classify each token of an expression in a dialect, such as one with `#` comments.
+pub fn lexical_with_config(str: &str, config: LexerConfig) -> Vec<Highlight> {
+    let tokens = tokens(str, config);
+
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(i, &(token, span))| {
+            let class = match token.kind {
+                TokenKind::Ws => Class::Whitespace,
+                TokenKind::Number(_) | TokenKind::Superscript => Class::Number,
+                TokenKind::Str { .. } => Class::String,
+                TokenKind::Brace { .. } => Class::Brace,
+                TokenKind::Comma | TokenKind::Semicolon | TokenKind::Colon => Class::Separator,
+                TokenKind::Comment => Class::Comment,
+                TokenKind::Invalid => Class::Invalid,
+                TokenKind::Chars => match &str[span.start..span.end] {
+                    "true" | "false" | "if" => Class::Keyword,
+                    _ if is_call(&tokens[i + 1..]) => Class::Function,
+                    _ => Class::Name,
+                },
+                TokenKind::Plus
+                | TokenKind::Minus
+                | TokenKind::Multiply
+                | TokenKind::Divide
+                | TokenKind::Modulo
+                | TokenKind::Eq
+                | TokenKind::Ge
+                | TokenKind::Le
+                | TokenKind::Amp
+                | TokenKind::Pipe
+                | TokenKind::Caret
+                | TokenKind::Exclamation
+                | TokenKind::LessEqual
+                | TokenKind::GreaterEqual
+                | TokenKind::NotEqual
+                | TokenKind::Root
+                | TokenKind::DoubleSlash => Class::Operator,
+            };
+
+            Highlight { class, span }
+        })
+        .collect()
+}
+
+/// Classify each token of an expression, using the parsed expression to classify names.
+///
+/// Names which the resolver provides are constants and the parameters of lambdas are variables, including where
+/// they are used in the body. Other names stay [`Class::Name`]. Parts of the expression which do not parse are
+/// classified as by [`lexical`].
+///
+/// The expression is parsed within the [`Default`] limits, an expression with too many tokens or braces nested too
+/// deeply is classified as by [`lexical`].
+pub fn semantic(resolver: &dyn Resolver, str: &str) -> Vec<Highlight> {
+    semantic_with_config(
+        resolver,
+        str,
+        LexerConfig::default(),
+        &EvalLimits::default(),
+    )
+}
+
+/// Classify each token of an expression in a dialect, using the expression parsed within the limits to classify
+/// names.
+pub fn semantic_with_config(
+    resolver: &dyn Resolver,
+    str: &str,
+    config: LexerConfig,
+    limits: &EvalLimits,
+) -> Vec<Highlight> {
+    let mut highlights = lexical_with_config(str, config);
+    let tokens = Tokenizer::with_config(str, config);
+    let nodes = match Parser::new(str, tokens, limits) {
+        Ok(parser) => parser.recovering().0,
+        Err(_) => Vec::new(),
+    };
+    let mut roles = Vec::new();
+
+    self::roles(resolver, str, config, &nodes, &mut Vec::new(), &mut roles);
+
+    for highlight in &mut highlights {
+        if highlight.class != Class::Name {
+            continue;
+        }
+
+        if let Some(role) = roles.iter().find(|role| role.span == highlight.span) {
+            highlight.class = role.class;
+        }
+    }
+
+    highlights
+}
+
+/// Collect the class of each name used in the nodes, and of the parameters of lambdas.
+fn roles<'a>(
+    resolver: &dyn Resolver,
+    str: &str,
+    config: LexerConfig,
+    nodes: &'a [Node<'a>],
+    params: &mut Vec<&'a str>,
+    roles: &mut Vec<Highlight>,
+) {
+    for node in nodes {
+        match node {
+            Node::Unary(Unary {
+                kind: UnaryKind::Constant(name),
+                ..
+            }) => {
+                let class = if params.contains(&&**name) {
+                    Class::Variable
+                } else if resolver.resolve_const_symbol(name).is_some() {
+                    Class::Constant
+                } else {
+                    continue;
+                };
+
+                roles.push(Highlight {
+                    class,
+                    span: node.span(),
+                });
+            }
+
+            Node::Lambda(Lambda {
+                params: names,
+                body,
+                span,
+                ..
+            }) => {
+                // The parameters are the names before the body.
+                let body_start = body.iter().map(|node| node.span().start).min();
+                let end = body_start.unwrap_or(span.end);
+
+                for (token, name) in tokens(&str[span.start..end], config) {
+                    let name = Span {
+                        start: span.start + name.start,
+                        end: span.start + name.end,
+                    };
+
+                    if token.kind == TokenKind::Chars
+                        && names
+                            .iter()
+                            .any(|param| **param == str[name.start..name.end])
+                    {
+                        roles.push(Highlight {
+                            class: Class::Variable,
+                            span: name,
+                        });
+                    }
+                }
+
+                let len = params.len();
+                params.extend(names.iter().map(|name| &**name));
+                self::roles(resolver, str, config, body, params, roles);
+                params.truncate(len);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+/// Render an expression with ANSI escape sequences styling each highlight.
+pub fn ansi(str: &str, highlights: &[Highlight]) -> String {
+    let mut out = String::with_capacity(str.len());
+
+    render(str, highlights, &mut out, |out, class, text| {
+        match class.ansi() {
+            Some(style) => {
+                out.push_str(style);
+                out.push_str(text);
+                out.push_str(RESET);
+            }
+            None => out.push_str(text),
+        };
+    });
+
+    out
+}
+
+/// Render an expression as HTML, with each highlight in a `<span>` with the [`name`](Class::name) of its class.
+///
+/// Whitespace is not wrapped in a span and the text is escaped.
+pub fn html(str: &str, highlights: &[Highlight]) -> String {
+    let mut out = String::with_capacity(str.len());
+
+    render(str, highlights, &mut out, |out, class, text| {
+        if class == Class::Whitespace {
+            escape(out, text);
+            return;
+        }
+
+        out.push_str("<span class=\"");
+        out.push_str(class.name());
+        out.push_str("\">");
+        escape(out, text);
+        out.push_str("</span>");
+    });
+
+    out
+}
+
+/// Write each highlight, and any text between the highlights unstyled.
+fn render(
+    str: &str,
+    highlights: &[Highlight],
+    out: &mut String,
+    mut write: impl FnMut(&mut String, Class, &str),
+) {
+    let mut pos = 0;
+
+    for highlight in highlights {
+        let Some(text) = str.get(highlight.span.start..highlight.span.end) else {
+            continue;
+        };
+
+        if highlight.span.start < pos {
+            continue;
+        }
+
+        out.push_str(&str[pos..highlight.span.start]);
+        write(out, highlight.class, text);
+        pos = highlight.span.end;
+    }
+
+    out.push_str(&str[pos..]);
+}
+
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// A pair of matching braces.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct BracePair {
+    pub open: Span,
+    pub close: Span,
+
+    /// The number of braces the pair is inside of, starting from 0.
+    pub depth: usize,
+}
+
+/// The braces of an expression, paired up.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Braces {
+    /// The pairs of braces, in the order of their closing braces.
+    pub pairs: Vec<BracePair>,
+
+    /// Braces without a matching brace, such as the `]` of `(1]` or an opening brace which is never closed.
+    pub unmatched: Vec<Span>,
+}
+
+impl Braces {
+    /// Pair up the braces of an expression.
+    ///
+    /// A closing brace matches the last opening brace which is not yet closed if they are the same kind of brace,
+    /// otherwise the closing brace is unmatched.
+    pub fn new(str: &str) -> Self {
+        Braces::with_config(str, LexerConfig::default())
+    }
+
+    /// Pair up the braces of an expression in a dialect, so braces in comments are not paired.
+    pub fn with_config(str: &str, config: LexerConfig) -> Self {
+        let mut open = Vec::new();
+        let mut pairs = Vec::new();
+        let mut unmatched = Vec::new();
+
+        for (token, span) in tokens(str, config) {
+            match token.kind {
+                TokenKind::Brace { kind, open: true } => open.push((kind, span)),
+                TokenKind::Brace { kind, open: false } => match open.last() {
+                    Some(&(last, start)) if last == kind => {
+                        open.pop();
+                        pairs.push(BracePair {
+                            open: start,
+                            close: span,
+                            depth: open.len(),
+                        });
+                    }
+                    _ => unmatched.push(span),
+                },
+                _ => {}
+            }
+        }
+
+        unmatched.extend(open.into_iter().map(|(_, span)| span));
+        unmatched.sort_by_key(|span| span.start);
+
+        Self { pairs, unmatched }
+    }
+
+    /// The brace matching the brace at a byte offset, if there is a brace at the offset and it has a match.
+    pub fn matching(&self, offset: usize) -> Option<Span> {
+        let contains = |span: &Span| span.start <= offset && offset < span.end;
+
+        self.pairs.iter().find_map(|pair| {
+            if contains(&pair.open) {
+                Some(pair.close)
+            } else if contains(&pair.close) {
+                Some(pair.open)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Whether the next token which is not whitespace opens a call.
+fn is_call(rest: &[(Token, Span)]) -> bool {
+    rest.iter()
+        .map(|(token, _)| token.kind)
+        .find(|kind| *kind != TokenKind::Ws)
+        == Some(equation_lexer::Brace![Round, Open])
+}
+
+/// The tokens of the expression with their byte spans.
+fn tokens(str: &str, config: LexerConfig) -> Vec<(Token, Span)> {
+    parse::spans(str, Tokenizer::with_config(str, config)).collect()
+}
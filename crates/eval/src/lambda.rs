@@ -0,0 +1,418 @@
+//! Closures and the built-in functions which call them.
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::{
+    error,
+    limits::{Context, EvalLimits, Usage},
+    list,
+    node::{Lambda, Node, Span, UnaryKind},
+    numeric,
+    resolve::Resolver,
+    run, Error, ErrorKind, Evaluated, Type,
+};
+
+/// A lambda and the values of the names it uses from where it was created.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Closure {
+    params: Arc<[String]>,
+    body: Arc<[Node<'static>]>,
+    captured: Vec<(String, Evaluated)>,
+    pub(crate) span: Span,
+}
+
+impl Closure {
+    /// Create a closure, capturing the values of the names used by the lambda which the resolver can resolve.
+    pub(crate) fn new(lambda: &Lambda<'_>, resolver: &dyn Resolver) -> Self {
+        let params = lambda
+            .params
+            .iter()
+            .map(|param| String::from(&**param))
+            .collect::<Arc<[_]>>();
+        let body = lambda
+            .body
+            .iter()
+            .cloned()
+            .map(Node::into_owned)
+            .collect::<Arc<[_]>>();
+
+        let mut captured = Vec::new();
+        capture(&body, &params, resolver, &mut captured);
+
+        Self {
+            params,
+            body,
+            captured,
+            span: lambda.span,
+        }
+    }
+
+    /// The names of the parameters, in order.
+    pub fn params(&self) -> impl Iterator<Item = &str> {
+        self.params.iter().map(String::as_str)
+    }
+
+    /// Call the closure.
+    ///
+    /// Resolvers may use this to evaluate a lambda passed as an argument any number of times. Names which are not
+    /// parameters or captured values are resolved by `resolver`.
+    ///
+    /// Errors raised by the body are reported at their span in the body.
+    pub fn call(&self, resolver: &dyn Resolver, args: &[Evaluated]) -> Result<Evaluated, Error> {
+        let usage = Usage::default();
+        let ctx = Context::new(resolver, &EvalLimits::UNLIMITED, &usage);
+        self.call_at(&ctx, args, self.span)
+    }
+
+    /// Call the closure, reporting the wrong number of arguments at `span`.
+    pub(crate) fn call_at(
+        &self,
+        ctx: &Context<'_>,
+        args: &[Evaluated],
+        span: Span,
+    ) -> Result<Evaluated, Error> {
+        if args.len() != self.params.len() {
+            return Err(error(
+                ErrorKind::ArgumentCount {
+                    expected: self.params.len(),
+                    found: args.len(),
+                },
+                span,
+            ));
+        }
+
+        let scope = Scope {
+            closure: self,
+            args,
+            parent: ctx.resolver,
+        };
+
+        run(&ctx.with(&scope), self.body.iter())
+    }
+
+    /// Call the closure, expecting a boolean result.
+    fn predicate(&self, ctx: &Context<'_>, args: &[Evaluated], span: Span) -> Result<bool, Error> {
+        match self.call_at(ctx, args, span)? {
+            Evaluated::Bool(bool) => Ok(bool),
+            value => Err(error(
+                ErrorKind::TypeMismatch {
+                    expected: Type::Bool,
+                    found: value.ty(),
+                },
+                self.span,
+            )),
+        }
+    }
+}
+
+/// Closures are only equal to clones of themselves.
+impl PartialEq for Closure {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.body, &other.body) && self.captured == other.captured
+    }
+}
+
+impl Eq for Closure {}
+
+/// Capture the constants used by `body`, including the bodies of nested lambdas.
+fn capture(
+    body: &[Node<'_>],
+    params: &[String],
+    resolver: &dyn Resolver,
+    captured: &mut Vec<(String, Evaluated)>,
+) {
+    for node in body {
+        match node {
+            Node::Unary(unary) => {
+                let UnaryKind::Constant(name) = &unary.kind else {
+                    continue;
+                };
+
+                if params.iter().any(|param| param == name)
+                    || captured.iter().any(|(captured, _)| captured == name)
+                {
+                    continue;
+                }
+
+                if let Some(value) = resolver.resolve_const_symbol(name) {
+                    captured.push((name.clone().into_owned(), value));
+                }
+            }
+
+            Node::Lambda(lambda) => capture(&lambda.body, params, resolver, captured),
+            _ => (),
+        }
+    }
+}
+
+/// The names visible in the body of a closure.
+///
+/// Parameters shadow captured values, which shadow names provided by the resolver of the caller.
+struct Scope<'c> {
+    closure: &'c Closure,
+    args: &'c [Evaluated],
+    parent: &'c dyn Resolver,
+}
+
+impl Resolver for Scope<'_> {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        if let Some(i) = self.closure.params.iter().position(|param| param == name) {
+            return self.args.get(i).cloned();
+        }
+
+        match self
+            .closure
+            .captured
+            .iter()
+            .find(|(captured, _)| captured == name)
+        {
+            Some((_, value)) => Some(value.clone()),
+            None => self.parent.resolve_const_symbol(name),
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        self.parent.resolve_function(name, args)
+    }
+
+    fn resolve_operator(
+        &self,
+        name: &str,
+        lhs: &Evaluated,
+        rhs: &Evaluated,
+    ) -> Result<Evaluated, ErrorKind> {
+        self.parent.resolve_operator(name, lhs, rhs)
+    }
+
+    fn has_function(&self, name: &str) -> bool {
+        self.parent.has_function(name)
+    }
+
+    fn max_iterations(&self) -> usize {
+        self.parent.max_iterations()
+    }
+
+    fn const_symbols(&self) -> Vec<&str> {
+        let params = self.closure.params();
+        let captured = self.closure.captured.iter().map(|(name, _)| name.as_str());
+
+        params
+            .chain(captured)
+            .chain(self.parent.const_symbols())
+            .collect()
+    }
+
+    fn functions(&self) -> Vec<&str> {
+        self.parent.functions()
+    }
+}
+
+/// The names of the built-in functions which call closures.
+pub(crate) const FUNCTIONS: &[&str] = &["map", "filter", "reduce", "prod", "integrate", "root"];
+
+/// Call a built-in function which takes a closure.
+///
+/// Returns [`None`] if there is no such built-in function with the name.
+pub(crate) fn call(
+    ctx: &Context<'_>,
+    name: &str,
+    args: &[Evaluated],
+    span: Span,
+) -> Option<Result<Evaluated, Error>> {
+    let result = match (name, args) {
+        ("sum", [from, to, Evaluated::Lambda(f)]) => series(ctx, false, from, to, f, span),
+        ("prod", [from, to, Evaluated::Lambda(f)]) => series(ctx, true, from, to, f, span),
+
+        // The parser turns the bound variable and body into a lambda, so only 3 arguments remain.
+        ("prod", [_, _, body]) => Err(mismatch(Type::Lambda, body, span)),
+        ("prod", args) => Err(error(
+            ErrorKind::ArgumentCount {
+                expected: 4,
+                found: args.len(),
+            },
+            span,
+        )),
+
+        ("integrate", [Evaluated::Lambda(f), from, to]) => {
+            numeric::integrate(ctx, f, from, to, span)
+        }
+        ("root", [Evaluated::Lambda(f), guess]) => numeric::root(ctx, f, guess, span),
+
+        ("integrate", [body, _, _]) | ("root", [body, _]) => {
+            Err(mismatch(Type::Lambda, body, span))
+        }
+        ("integrate" | "root", args) => Err(error(
+            ErrorKind::ArgumentCount {
+                expected: if name == "root" { 3 } else { 4 },
+                found: args.len(),
+            },
+            span,
+        )),
+
+        ("map", [values, f]) => map(ctx, values, f, span),
+        ("filter", [values, f]) => filter(ctx, values, f, span),
+        ("reduce", [values, f]) => reduce(ctx, values, f, None, span),
+        ("reduce", [values, f, initial]) => reduce(ctx, values, f, Some(initial), span),
+
+        ("map" | "filter", args) => Err(error(
+            ErrorKind::ArgumentCount {
+                expected: 2,
+                found: args.len(),
+            },
+            span,
+        )),
+
+        ("reduce", args) => Err(error(
+            ErrorKind::ArgumentCount {
+                expected: if args.len() < 2 { 2 } else { 3 },
+                found: args.len(),
+            },
+            span,
+        )),
+
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+pub(crate) fn mismatch(expected: Type, value: &Evaluated, span: Span) -> Error {
+    error(
+        ErrorKind::TypeMismatch {
+            expected,
+            found: value.ty(),
+        },
+        span,
+    )
+}
+
+fn elements(value: &Evaluated, span: Span) -> Result<Vec<Evaluated>, Error> {
+    match value {
+        Evaluated::List(list) => Ok(list.clone()),
+        Evaluated::Matrix(matrix) => Ok(crate::builtin::rows(matrix)),
+        value => Err(mismatch(Type::List, value, span)),
+    }
+}
+
+fn closure(value: &Evaluated, span: Span) -> Result<&Closure, Error> {
+    match value {
+        Evaluated::Lambda(closure) => Ok(closure),
+        value => Err(mismatch(Type::Lambda, value, span)),
+    }
+}
+
+/// `map(list, x -> value)`: the value of the closure for each element.
+fn map(
+    ctx: &Context<'_>,
+    values: &Evaluated,
+    f: &Evaluated,
+    span: Span,
+) -> Result<Evaluated, Error> {
+    let f = closure(f, span)?;
+    let value = elements(values, span)?
+        .into_iter()
+        .map(|value| f.call_at(ctx, &[value], span))
+        .collect::<Result<_, _>>()
+        .map(list)?;
+
+    ctx.limits
+        .check_size(&value)
+        .map_err(|kind| error(kind, span))?;
+    Ok(value)
+}
+
+/// `filter(list, x -> condition)`: the elements for which the closure is `true`.
+fn filter(
+    ctx: &Context<'_>,
+    values: &Evaluated,
+    f: &Evaluated,
+    span: Span,
+) -> Result<Evaluated, Error> {
+    let f = closure(f, span)?;
+    let mut filtered = Vec::new();
+
+    for value in elements(values, span)? {
+        if f.predicate(ctx, core::slice::from_ref(&value), span)? {
+            filtered.push(value);
+        }
+    }
+
+    Ok(list(filtered))
+}
+
+/// `reduce(list, (acc, x) -> value)` or `reduce(list, (acc, x) -> value, initial)`: combine the elements from
+/// first to last.
+///
+/// Without an initial value the first element is used, which requires the list to be non-empty.
+fn reduce(
+    ctx: &Context<'_>,
+    values: &Evaluated,
+    f: &Evaluated,
+    initial: Option<&Evaluated>,
+    span: Span,
+) -> Result<Evaluated, Error> {
+    let f = closure(f, span)?;
+    let mut values = elements(values, span)?.into_iter();
+
+    let initial = match initial {
+        Some(initial) => initial.clone(),
+        None => values
+            .next()
+            .ok_or_else(|| error(ErrorKind::InvalidArgument, span))?,
+    };
+
+    values.try_fold(initial, |acc, value| f.call_at(ctx, &[acc, value], span))
+}
+
+/// `sum(k, from, to, body)` and `prod(k, from, to, body)`: the sum or product of the body for each integer `k` from
+/// `from` to `to`, inclusive.
+///
+/// An empty range has a sum of 0 and a product of 1.
+fn series(
+    ctx: &Context<'_>,
+    product: bool,
+    from: &Evaluated,
+    to: &Evaluated,
+    f: &Closure,
+    span: Span,
+) -> Result<Evaluated, Error> {
+    let bound = |value: &Evaluated| match value {
+        Evaluated::Decimal(decimal) if decimal.is_integer() => Ok(*decimal),
+        Evaluated::Decimal(_) => Err(error(ErrorKind::InvalidArgument, span)),
+        value => Err(mismatch(Type::Decimal, value, span)),
+    };
+
+    let (from, to) = (bound(from)?, bound(to)?);
+    let count = to
+        .checked_sub(from)
+        .and_then(|count| count.checked_add(Decimal::ONE))
+        .ok_or_else(|| error(ErrorKind::Overflow, span))?;
+
+    // Reserve every iteration before the body is evaluated, so a range which is too large fails immediately.
+    let count = count.max(Decimal::ZERO).to_usize().unwrap_or(usize::MAX);
+    ctx.iterate(count, span)?;
+
+    let mut result = if product { Decimal::ONE } else { Decimal::ZERO };
+    let mut k = from;
+
+    while k <= to {
+        let value = match f.call_at(ctx, &[Evaluated::Decimal(k)], span)? {
+            Evaluated::Decimal(value) => value,
+            value => return Err(mismatch(Type::Decimal, &value, f.span)),
+        };
+
+        result = if product {
+            result.checked_mul(value)
+        } else {
+            result.checked_add(value)
+        }
+        .ok_or_else(|| error(ErrorKind::Overflow, span))?;
+
+        k += Decimal::ONE;
+    }
+
+    Ok(Evaluated::Decimal(result))
+}
@@ -0,0 +1,731 @@
+//! Parsing a subset of LaTeX math as an alternative input format.
+//!
+//! LaTeX is translated to an expression in the native syntax, which is parsed by the native parser so the nodes are
+//! the same as if the expression had been written natively. The spans of the nodes and of errors are in the LaTeX
+//! source.
+//!
+//! The supported LaTeX is:
+//! - Numbers, `+`, `-`, `=`, `<`, `>`, `!` and parentheses or brackets for grouping.
+//! - `\frac{a}{b}`, `\sqrt{x}` and `\sqrt[n]{x}`.
+//! - `\cdot`, `\times` and `\div`, `\leq`, `\geq`, `\neq`, `\land`, `\lor`, `\lnot` and `\bmod`.
+//! - `^{...}` for powers, `^2` with a single character exponent.
+//! - Single letters and `\mathrm{name}` as names, with subscripts such as `x_1` or `x_{max}` which become `x_1` and
+//!   `x_max`.
+//! - Greek letters such as `\pi` and `\theta`, which become `pi` and `theta`.
+//! - `\sin`, `\cos`, `\ln` and the other LaTeX operators, and `\operatorname{name}`, applied to a group in
+//!   parentheses or braces or to the next factor, like `\sin 2\pi` or `\sin^2 x`.
+//! - `\left( \right)` and `\left[ \right]` for grouping and `\left| \right|` for `abs`.
+//! - Spacing such as `\,` and `\quad`, and `%` comments.
+//!
+//! Operands next to each other are multiplied, so `2\pi r` is `2 * pi * r` and `xy` is `x * y`. A letter followed by
+//! parentheses is also a multiplication, use `\operatorname{f}(x)` to call a function `f`.
+//!
+//! Groups, command arguments and function applications may be nested at most [`MAX_NESTING`] deep, and with
+//! [`parse_with_limits`] and [`eval_with_limits`] no deeper than [`EvalLimits::max_nesting`]. Deeper LaTeX fails with
+//! [`ErrorKind::NestingTooDeep`].
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    eval_nodes, eval_nodes_with_limits,
+    export::{GREEK, OPERATORS},
+    limits::EvalLimits,
+    node::{Node, Span},
+    parse::Parser,
+    resolve::Resolver,
+    Error, ErrorKind, Evaluated,
+};
+
+/// The maximum nesting of groups, command arguments and function applications.
+///
+/// Each level is translated recursively, so this bounds the stack used by deeply nested LaTeX.
+pub const MAX_NESTING: usize = 64;
+
+/// Commands which are an operator of the native syntax.
+const SYMBOLS: &[(&str, &str)] = &[
+    ("cdot", "*"),
+    ("times", "*"),
+    ("div", "/"),
+    ("le", "<="),
+    ("leq", "<="),
+    ("ge", ">="),
+    ("geq", ">="),
+    ("ne", "!="),
+    ("neq", "!="),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("land", "&&"),
+    ("wedge", "&&"),
+    ("lor", "||"),
+    ("vee", "||"),
+    ("lnot", "!"),
+    ("neg", "!"),
+    ("bmod", "%"),
+    ("mod", "%"),
+];
+
+/// Commands which only add space.
+const SPACING: &[&str] = &[",", ";", ":", "!", " ", "quad", "qquad"];
+
+/// Commands whose argument is the text of a name.
+const NAMES: &[&str] = &["mathrm", "mathit", "text"];
+
+/// Translate LaTeX to an expression in the native syntax.
+///
+/// ```
+/// use equation_eval::latex::translate;
+///
+/// assert_eq!(translate("\\frac{1}{2} m v^2").unwrap(), "((1)/(2))*m*v^(2)");
+/// ```
+pub fn translate(src: &str) -> Result<String, Error> {
+    Translator::new(src, MAX_NESTING)
+        .translate()
+        .map(|translated| translated.out)
+}
+
+/// Parse LaTeX into the nodes of an expression, with spans in `src`.
+pub fn parse(src: &str) -> Result<Vec<Node<'static>>, Error> {
+    parse_with_limits(src, &EvalLimits::UNLIMITED)
+}
+
+/// Parse untrusted LaTeX within the limits which apply to parsing.
+///
+/// The input length is checked against the LaTeX source and the token count against the translation.
+pub fn parse_with_limits(src: &str, limits: &EvalLimits) -> Result<Vec<Node<'static>>, Error> {
+    limits.check_input(src)?;
+
+    let translated = Translator::new(src, limits.max_nesting.min(MAX_NESTING)).translate()?;
+    let source = |span: Span| translated.source(span);
+
+    let tokens = equation_lexer::Tokenizer::from(translated.out.as_str());
+    let mut nodes = Parser::new(&translated.out, tokens, limits)
+        .and_then(Parser::parse)
+        .map_err(|error| remap_error(error, &source))?
+        .into_iter()
+        .map(Node::into_owned)
+        .collect::<Vec<_>>();
+
+    for node in &mut nodes {
+        remap(node, &source);
+    }
+
+    Ok(nodes)
+}
+
+/// Evaluate LaTeX.
+pub fn eval(resolver: &dyn Resolver, src: &str) -> Result<Evaluated, Error> {
+    eval_nodes(resolver, parse(src)?.into_iter())
+}
+
+/// Evaluate untrusted LaTeX within resource limits.
+pub fn eval_with_limits(
+    resolver: &dyn Resolver,
+    src: &str,
+    limits: &EvalLimits,
+) -> Result<Evaluated, Error> {
+    eval_nodes_with_limits(
+        resolver,
+        parse_with_limits(src, limits)?.into_iter(),
+        limits,
+    )
+}
+
+fn remap_error(error: Error, source: &impl Fn(Span) -> Span) -> Error {
+    Error {
+        span: source(error.span),
+        ..error
+    }
+}
+
+/// Replace the spans of a node and the nodes it contains with spans in the LaTeX source.
+fn remap(node: &mut Node<'_>, source: &impl Fn(Span) -> Span) {
+    let span = match node {
+        Node::Unary(node) => &mut node.span,
+        Node::Function(node) => &mut node.span,
+        Node::Operator(node) => &mut node.span,
+        Node::Equation(node) => &mut node.span,
+        Node::Logical(node) => &mut node.span,
+        Node::Custom(node) => &mut node.span,
+        Node::List(node) => &mut node.span,
+        Node::Index(node) => &mut node.span,
+        Node::Jump(node) => &mut node.span,
+        Node::Error(error) => &mut error.span,
+        Node::Lambda(lambda) => {
+            for node in &mut lambda.body {
+                remap(node, source);
+            }
+
+            &mut lambda.span
+        }
+    };
+
+    *span = source(*span);
+}
+
+/// A LaTeX token.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tex<'s> {
+    /// A command such as `\frac`, without the backslash.
+    Command(&'s str),
+
+    /// Digits, with at most one decimal point.
+    Number(&'s str),
+
+    Char(char),
+}
+
+/// The closing token of a group.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Close {
+    End,
+    Char(char),
+
+    /// `\right` followed by a delimiter.
+    Right(char),
+}
+
+/// LaTeX translated to the native syntax.
+struct Translated<'s> {
+    src: &'s str,
+    out: String,
+
+    /// The span in the LaTeX source each byte of the translation was produced from.
+    map: Vec<Span>,
+}
+
+impl Translated<'_> {
+    /// The span in the LaTeX source a span of the translation was produced from.
+    fn source(&self, span: Span) -> Span {
+        let end = Span {
+            start: self.src.len(),
+            end: self.src.len(),
+        };
+
+        if span.start >= span.end {
+            let start = self
+                .map
+                .get(span.start)
+                .map_or(end.start, |span| span.start);
+            return Span { start, end: start };
+        }
+
+        self.map
+            .get(span.start..span.end.min(self.map.len()))
+            .and_then(|spans| spans.iter().copied().reduce(Span::to))
+            .unwrap_or(end)
+    }
+}
+
+struct Translator<'s> {
+    src: &'s str,
+    pos: usize,
+    out: String,
+    map: Vec<Span>,
+
+    /// Whether the translation so far ends with an operand, so another operand is multiplied with it.
+    operand: bool,
+
+    /// The number of items being translated which contain the current item.
+    depth: usize,
+    max_nesting: usize,
+}
+
+impl<'s> Translator<'s> {
+    fn new(src: &'s str, max_nesting: usize) -> Self {
+        Self {
+            src,
+            pos: 0,
+            out: String::with_capacity(src.len()),
+            map: Vec::with_capacity(src.len()),
+            operand: false,
+            depth: 0,
+            max_nesting,
+        }
+    }
+
+    fn translate(mut self) -> Result<Translated<'s>, Error> {
+        self.sequence(Close::End)?;
+
+        Ok(Translated {
+            src: self.src,
+            out: self.out,
+            map: self.map,
+        })
+    }
+
+    /// The next token and its span, skipping whitespace and comments.
+    fn peek(&self) -> Option<(Tex<'s>, Span)> {
+        let mut pos = self.pos;
+
+        loop {
+            let rest = &self.src[pos..];
+            let c = rest.chars().next()?;
+
+            if c.is_whitespace() {
+                pos += c.len_utf8();
+            } else if c == '%' {
+                pos += rest.find('\n').unwrap_or(rest.len());
+            } else {
+                break;
+            }
+        }
+
+        let rest = &self.src[pos..];
+        let mut chars = rest.chars();
+        let c = chars.next()?;
+
+        let (token, len) = match c {
+            '\\' => {
+                let letters = chars.clone().take_while(char::is_ascii_alphabetic).count();
+
+                match (letters, chars.next()) {
+                    (0, Some(c)) => (Tex::Command(&rest[1..1 + c.len_utf8()]), 1 + c.len_utf8()),
+                    (0, None) => (Tex::Char('\\'), 1),
+                    (letters, _) => (Tex::Command(&rest[1..1 + letters]), 1 + letters),
+                }
+            }
+
+            '0'..='9' | '.' => {
+                let mut point = c == '.';
+                let len = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(_, c)| match c {
+                        '.' if !point => {
+                            point = true;
+                            false
+                        }
+                        c => !c.is_ascii_digit(),
+                    })
+                    .map_or(rest.len(), |(i, _)| i);
+
+                (Tex::Number(&rest[..len]), len)
+            }
+
+            c => (Tex::Char(c), c.len_utf8()),
+        };
+
+        Some((
+            token,
+            Span {
+                start: pos,
+                end: pos + len,
+            },
+        ))
+    }
+
+    fn next(&mut self) -> Result<(Tex<'s>, Span), Error> {
+        let (token, span) = self.peek().ok_or_else(|| self.end())?;
+        self.pos = span.end;
+        Ok((token, span))
+    }
+
+    fn end(&self) -> Error {
+        Error {
+            kind: ErrorKind::UnexpectedEnd,
+            span: Span {
+                start: self.src.len(),
+                end: self.src.len(),
+            },
+            suggestions: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, text: &str, span: Span) {
+        self.out.push_str(text);
+        self.map.extend(core::iter::repeat_n(span, text.len()));
+    }
+
+    /// Start an operand, multiplying it with the previous operand if there is one.
+    fn operand(&mut self, span: Span) {
+        if self.operand {
+            self.emit("*", span);
+        }
+    }
+
+    /// Translate tokens until the closing token, returning the span of the closing token.
+    fn sequence(&mut self, close: Close) -> Result<Span, Error> {
+        self.operand = false;
+
+        loop {
+            let Some((token, span)) = self.peek() else {
+                return match close {
+                    Close::End => Ok(Span {
+                        start: self.src.len(),
+                        end: self.src.len(),
+                    }),
+                    _ => Err(self.end()),
+                };
+            };
+
+            let closing = match (token, close) {
+                (Tex::Char(c), Close::Char(expected)) => c == expected,
+                (Tex::Command("right"), Close::Right(expected)) => {
+                    self.pos = span.end;
+                    let (delimiter, span) = self.next()?;
+
+                    if delimiter != Tex::Char(expected) {
+                        return Err(unexpected(span));
+                    }
+
+                    self.operand = true;
+                    return Ok(span);
+                }
+                _ => false,
+            };
+
+            if closing {
+                self.pos = span.end;
+                self.operand = true;
+                return Ok(span);
+            }
+
+            self.item()?;
+        }
+    }
+
+    /// Translate the next item: an operand, an operator or a command.
+    ///
+    /// Every recursive translation goes through an item, so the nesting is limited here.
+    fn item(&mut self) -> Result<(), Error> {
+        let (token, span) = self.next()?;
+
+        if self.depth >= self.max_nesting {
+            return Err(Error {
+                kind: ErrorKind::NestingTooDeep,
+                span,
+                suggestions: Vec::new(),
+            });
+        }
+
+        self.depth += 1;
+        let result = self.token(token, span);
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Translate a token which starts an item.
+    fn token(&mut self, token: Tex<'s>, span: Span) -> Result<(), Error> {
+        match token {
+            Tex::Number(number) => {
+                self.operand(span);
+                self.emit(number, span);
+                self.operand = true;
+            }
+
+            Tex::Char(c) if c.is_ascii_alphabetic() => {
+                self.operand(span);
+                self.emit(&self.src[span.start..span.end], span);
+                self.subscript()?;
+                self.operand = true;
+            }
+
+            Tex::Char(c @ ('+' | '-' | '*' | '/' | '=' | '<' | '>' | ',')) => {
+                self.emit(c.encode_utf8(&mut [0; 4]), span);
+                self.operand = false;
+            }
+
+            // Factorial.
+            Tex::Char('!') => self.emit("!", span),
+
+            Tex::Char('^') => {
+                self.emit("^", span);
+                self.argument()?;
+            }
+
+            Tex::Char(open @ ('(' | '[' | '{')) => {
+                let close = match open {
+                    '(' => ')',
+                    '[' => ']',
+                    _ => '}',
+                };
+
+                self.operand(span);
+                self.emit("(", span);
+                let end = self.sequence(Close::Char(close))?;
+                self.emit(")", end);
+            }
+
+            Tex::Command(command) => self.command(command, span)?,
+
+            Tex::Char(_) => return Err(unexpected(span)),
+        }
+
+        Ok(())
+    }
+
+    fn command(&mut self, command: &str, span: Span) -> Result<(), Error> {
+        if let Some((_, symbol)) = SYMBOLS.iter().find(|(name, _)| *name == command) {
+            self.emit(symbol, span);
+            self.operand = false;
+            return Ok(());
+        }
+
+        if SPACING.contains(&command) {
+            return Ok(());
+        }
+
+        if let Some((name, ..)) = GREEK.iter().find(|(name, ..)| *name == command) {
+            self.operand(span);
+            self.emit(name, span);
+            self.subscript()?;
+            self.operand = true;
+            return Ok(());
+        }
+
+        if OPERATORS.contains(&command) {
+            return self.function(command, span);
+        }
+
+        match command {
+            "frac" | "dfrac" | "tfrac" => {
+                self.operand(span);
+                self.emit("(", span);
+                self.argument()?;
+                self.emit("/", span);
+                self.argument()?;
+                self.emit(")", span);
+            }
+
+            "sqrt" => {
+                self.operand(span);
+
+                if let Some((Tex::Char('['), open)) = self.peek() {
+                    self.pos = open.end;
+
+                    let degree = self.capture(|this| {
+                        let end = this.sequence(Close::Char(']'))?;
+                        this.emit(")", end);
+                        Ok(())
+                    })?;
+
+                    self.emit("(", span);
+                    self.argument()?;
+                    self.emit("^(1/(", span);
+                    self.append(degree);
+                    self.emit("))", span);
+                } else {
+                    self.emit("sqrt", span);
+                    self.argument()?;
+                }
+            }
+
+            "operatorname" => {
+                let (name, name_span) = self.text()?;
+                self.function(name, span.to(name_span))?;
+            }
+
+            "left" => {
+                let (delimiter, delimiter_span) = self.next()?;
+                let (open, close) = match delimiter {
+                    Tex::Char('(') => ("(", ')'),
+                    Tex::Char('[') => ("(", ']'),
+                    Tex::Char('|') => ("abs(", '|'),
+                    _ => return Err(unexpected(delimiter_span)),
+                };
+
+                let span = span.to(delimiter_span);
+                self.operand(span);
+                self.emit(open, span);
+                let end = self.sequence(Close::Right(close))?;
+                self.emit(")", end);
+            }
+
+            command if NAMES.contains(&command) => {
+                let (name, name_span) = self.text()?;
+                let span = span.to(name_span);
+
+                self.operand(span);
+                self.emit(name, span);
+                self.subscript()?;
+                self.operand = true;
+            }
+
+            _ => {
+                return Err(Error {
+                    kind: ErrorKind::InvalidToken,
+                    span,
+                    suggestions: Vec::new(),
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Translate a call of a function, such as `\sin x`, `\sin(x)` or `\sin^2 x`.
+    fn function(&mut self, name: &str, span: Span) -> Result<(), Error> {
+        self.operand(span);
+        self.emit(name, span);
+        self.operand = false;
+
+        // An exponent before the argument, which is written after the call.
+        let exponent = match self.peek() {
+            Some((Tex::Char('^'), caret)) => {
+                self.pos = caret.end;
+                Some(self.capture(|this| {
+                    this.emit("^", caret);
+                    this.argument()
+                })?)
+            }
+            _ => None,
+        };
+
+        self.operand = false;
+
+        match self.peek() {
+            Some((Tex::Char('('), open)) => {
+                self.pos = open.end;
+                self.emit("(", open);
+                let end = self.sequence(Close::Char(')'))?;
+                self.emit(")", end);
+            }
+
+            Some((Tex::Command("left"), _)) => {
+                self.emit("(", span);
+                self.item()?;
+                self.emit(")", span);
+            }
+
+            Some((Tex::Char('{'), _)) => self.argument()?,
+
+            _ => {
+                self.emit("(", span);
+                self.item()?;
+
+                while let Some((Tex::Char('^' | '!'), _)) = self.peek() {
+                    self.item()?;
+                }
+
+                self.emit(")", span);
+            }
+        }
+
+        if let Some(exponent) = exponent {
+            self.append(exponent);
+        }
+
+        self.operand = true;
+        Ok(())
+    }
+
+    /// Translate the argument of a command, a group in braces or a single token, in parentheses.
+    fn argument(&mut self) -> Result<(), Error> {
+        let (token, span) = self.peek().ok_or_else(|| self.end())?;
+
+        match token {
+            Tex::Char('{') => {
+                self.pos = span.end;
+                self.emit("(", span);
+                let end = self.sequence(Close::Char('}'))?;
+                self.emit(")", end);
+            }
+
+            // Only the first digit of a number is the argument, so `x^23` is `x^2 * 3`.
+            Tex::Number(number) => {
+                let digit = Span {
+                    start: span.start,
+                    end: span.start + 1,
+                };
+
+                self.pos = digit.end;
+                self.emit("(", digit);
+                self.emit(&number[..1], digit);
+                self.emit(")", digit);
+                self.operand = true;
+            }
+
+            _ => {
+                self.emit("(", span);
+                self.operand = false;
+                self.item()?;
+                self.emit(")", span);
+                self.operand = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Translate the subscript of a name, if there is one, as part of the name.
+    fn subscript(&mut self) -> Result<(), Error> {
+        let Some((Tex::Char('_'), underscore)) = self.peek() else {
+            return Ok(());
+        };
+
+        self.pos = underscore.end;
+
+        let (sub, span) = match self.next()? {
+            (Tex::Char('{'), _) => {
+                self.pos = underscore.end;
+                self.text()?
+            }
+
+            (Tex::Char(c), span) if c.is_ascii_alphanumeric() => {
+                (&self.src[span.start..span.end], span)
+            }
+
+            // Only the first digit of a number is the subscript.
+            (Tex::Number(number), span) if number.as_bytes()[0].is_ascii_digit() => {
+                self.pos = span.start + 1;
+                (&number[..1], span)
+            }
+
+            (_, span) => return Err(unexpected(span)),
+        };
+
+        let span = underscore.to(span);
+        self.emit("_", span);
+        self.emit(sub, span);
+        Ok(())
+    }
+
+    /// The text of a name in braces, such as the argument of `\mathrm`.
+    fn text(&mut self) -> Result<(&'s str, Span), Error> {
+        let (open, span) = self.next()?;
+
+        if open != Tex::Char('{') {
+            return Err(unexpected(span));
+        }
+
+        let rest = &self.src[span.end..];
+        let len = rest.find('}').ok_or_else(|| self.end())?;
+        let name = rest[..len].trim();
+        let span = span.to(Span {
+            start: span.end + len,
+            end: span.end + len + 1,
+        });
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(unexpected(span));
+        }
+
+        self.pos = span.end;
+        Ok((name, span))
+    }
+
+    /// Translate something into a separate buffer, to be appended later.
+    fn capture(
+        &mut self,
+        translate: impl FnOnce(&mut Self) -> Result<(), Error>,
+    ) -> Result<(String, Vec<Span>), Error> {
+        let start = self.out.len();
+        translate(self)?;
+
+        Ok((self.out.split_off(start), self.map.split_off(start)))
+    }
+
+    fn append(&mut self, (out, map): (String, Vec<Span>)) {
+        self.out.push_str(&out);
+        self.map.extend(map);
+    }
+}
+
+fn unexpected(span: Span) -> Error {
+    Error {
+        kind: ErrorKind::UnexpectedToken,
+        span,
+        suggestions: Vec::new(),
+    }
+}
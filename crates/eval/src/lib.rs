@@ -2,83 +2,823 @@
 
 extern crate alloc;
 
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
+use core::borrow::Borrow;
 use equation_lexer::Token;
-use node::{Function, Node, Span};
+use lambda::Closure;
+use limits::{Context, EvalLimits, Usage};
+use matrix::Matrix;
+use node::{Function, Node, Span, Spanned};
+use operators::OperatorTable;
 use resolve::Resolver;
-use rust_decimal::Decimal;
+use rust_decimal::{prelude::ToPrimitive, Decimal, MathematicalOps};
 
-use crate::node::{Equation, Operator, OperatorKind};
+use crate::node::{
+    CustomOperator, Equation, EquationKind, Index, Jump, JumpKind, List, Logical, LogicalKind,
+    Operator, OperatorKind, Unary, UnaryKind,
+};
 
+mod builtin;
+#[cfg(feature = "fmt")]
+pub mod diagnostic;
+pub mod export;
+pub mod highlight;
+pub mod lambda;
+pub mod latex;
+pub mod limits;
+pub mod matrix;
 pub mod node;
+mod numeric;
+pub mod operators;
+mod parse;
 pub mod resolve;
+pub mod session;
+mod string;
+mod suggest;
 
-/// Returned value from evaluation.
+/// A value produced by evaluation.
+///
+/// Values are used for the operands on the evaluation stack, by [`Resolver`]s and as the result of evaluation.
+///
+/// With the `serde` feature a value is externally tagged by its snake case variant name. Numbers are strings so no
+/// precision is lost, a matrix is described by [`Matrix`] and a lambda keeps its parameters, body and captured
+/// values:
+///
+/// ```json
+/// { "decimal": "1.5" }
+/// { "list": [{ "bool": true }, { "str": "a" }] }
+/// { "matrix": { "rows": 1, "cols": 2, "data": ["1", "2"] } }
+/// ```
 #[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Evaluated {
-    /// Numeric output
+    /// Numeric value
     Decimal(Decimal),
 
-    /// Equability
-    Eq(bool),
+    /// Boolean value
+    ///
+    /// This is the outcome of an equality, a comparison, a logical operator or a boolean literal.
+    Bool(bool),
+
+    /// String value
+    Str(String),
 
-    /// Comparison
+    /// List of values
+    List(Vec<Evaluated>),
+
+    /// Matrix of numbers
     ///
-    /// If true then the comparison was true.
-    Cmp(bool),
+    /// A list literal where every element is a list of numbers with the same length is a matrix.
+    Matrix(Matrix),
 
-    /// The value is undefined.
-    Undefined,
+    /// Anonymous function
+    ///
+    /// This is the value of a lambda such as `x -> x * 2`, which is called by functions like `map`.
+    Lambda(Closure),
+}
+
+impl Evaluated {
+    /// Returns the type of the value.
+    pub fn ty(&self) -> Type {
+        match self {
+            Evaluated::Decimal(_) => Type::Decimal,
+            Evaluated::Bool(_) => Type::Bool,
+            Evaluated::Str(_) => Type::Str,
+            Evaluated::List(_) => Type::List,
+            Evaluated::Matrix(_) => Type::Matrix,
+            Evaluated::Lambda(_) => Type::Lambda,
+        }
+    }
+}
+
+/// Formats the value the same way as `concat`: strings without quotes, lists and matrix rows in brackets.
+impl core::fmt::Display for Evaluated {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut out = String::new();
+        builtin::write(self, &mut out);
+        f.write_str(&out)
+    }
 }
 
+/// The type of an [`Evaluated`] value.
 #[cfg_attr(feature = "fmt", derive(Debug))]
-pub enum ErrorKind {}
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Type {
+    Decimal,
+
+    Bool,
+
+    Str,
+
+    List,
+
+    Matrix,
+
+    Lambda,
+}
+
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ErrorKind {
+    /// A token which is not valid in any expression.
+    InvalidToken,
+
+    /// A token which is not valid at this position in the expression.
+    UnexpectedToken,
+
+    /// The expression ended before it was complete.
+    UnexpectedEnd,
+
+    /// A number which could not be parsed.
+    InvalidNumber,
+
+    /// A string literal without a closing quote.
+    UnterminatedString,
+
+    /// An invalid escape sequence in a string literal.
+    InvalidEscape,
+
+    /// The wrong number of arguments were passed to a function.
+    ArgumentCount {
+        expected: usize,
+        found: usize,
+    },
+
+    /// A constant could not be resolved.
+    UnknownConstant,
+
+    /// A function could not be resolved.
+    UnknownFunction,
+
+    /// A custom operator could not be resolved.
+    UnknownOperator,
+
+    /// A definition uses a name which is already provided by the resolver or built in, or a parameter name is
+    /// used more than once.
+    Redefinition,
+
+    /// User-defined functions called each other too many times without returning.
+    RecursionLimit,
+
+    /// Ranges and numeric methods need more evaluations in total than [`Resolver::max_iterations`] allows.
+    IterationLimit,
+
+    /// A numeric method such as `root` did not find a solution.
+    NoConvergence,
+
+    /// An argument has the right type but an invalid value.
+    InvalidArgument,
+
+    /// An index is outside of the list.
+    OutOfRange,
+
+    /// Lists used in an element-wise operation have different lengths.
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+
+    /// A matrix has the wrong number of rows and columns for the operation.
+    ShapeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+
+    /// A matrix with a determinant of zero has no inverse.
+    SingularMatrix,
+
+    /// A value has the wrong type for the operation.
+    TypeMismatch {
+        expected: Type,
+        found: Type,
+    },
+
+    /// An operand was missing from the stack.
+    ///
+    /// This only occurs if the nodes were not produced by the parser.
+    MissingOperand,
+
+    DivideByZero,
+
+    Overflow,
+
+    /// The value is undefined.
+    Undefined,
+
+    /// The input is longer than [`EvalLimits::max_input_len`].
+    InputTooLong,
+
+    /// The input has more tokens than [`EvalLimits::max_tokens`].
+    TooManyTokens,
+
+    /// More braces are open at once than [`EvalLimits::max_nesting`].
+    NestingTooDeep,
 
+    /// More nodes were evaluated than [`EvalLimits::max_steps`].
+    StepLimit,
+
+    /// The evaluation stack has more values than [`EvalLimits::max_stack`].
+    StackOverflow,
+
+    /// The exponent of `^` is larger than [`EvalLimits::max_exponent`].
+    ExponentTooLarge,
+
+    /// The argument of a factorial is larger than [`EvalLimits::max_factorial`].
+    FactorialTooLarge,
+
+    /// A list or matrix has more values than [`EvalLimits::max_elements`].
+    TooManyElements,
+
+    /// A string is longer than [`EvalLimits::max_string_len`].
+    StringTooLong,
+}
+
+/// An error and the part of the expression it occurred in.
+///
+/// With the `serde` feature an error is an object with a `kind` and a `span`. Kinds are snake case and kinds with
+/// data are externally tagged, such as `{ "argument_count": { "expected": 2, "found": 1 } }`. Suggestions are only
+/// present when there are any.
 #[non_exhaustive]
 #[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Error {
     pub kind: ErrorKind,
     pub span: Span,
+
+    /// Names similar to the name of an unknown constant or function, closest first.
+    ///
+    /// Names are suggested from the names visible where the unknown name was used: lambda parameters, captured values,
+    /// the parameters and variables of a [`Session`](session::Session), the names of the resolver and the built-in
+    /// functions. Other errors have no suggestions.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub suggestions: Vec<String>,
 }
 
 pub fn eval(resolver: &dyn Resolver, str: &str) -> Result<Evaluated, Error> {
-    eval_tokens(resolver, equation_lexer::Tokenizer::from(str))
+    eval_tokens(resolver, str, equation_lexer::Tokenizer::from(str))
 }
 
+/// Evaluate tokens which were produced from `str`.
+///
+/// The tokens may be produced by [`Tokenizer::with_config`](equation_lexer::Tokenizer::with_config) to evaluate a
+/// different dialect of expressions, such as one with `**` for powers or `#` comments.
 pub fn eval_tokens(
     resolver: &dyn Resolver,
+    str: &str,
     tokens: impl Iterator<Item = Token>,
 ) -> Result<Evaluated, Error> {
-    eval_nodes(resolver, Node::from_tokens(tokens).expect("TODO: Err"))
+    eval_nodes(resolver, Node::from_tokens(str, tokens)?)
 }
 
 pub fn eval_nodes<'a>(
     resolver: &dyn Resolver,
     nodes: impl Iterator<Item = Node<'a>>,
 ) -> Result<Evaluated, Error> {
+    eval_nodes_with_limits(resolver, nodes, &EvalLimits::UNLIMITED)
+}
+
+/// Evaluate an untrusted expression within resource limits.
+pub fn eval_with_limits(
+    resolver: &dyn Resolver,
+    str: &str,
+    limits: &EvalLimits,
+) -> Result<Evaluated, Error> {
+    eval_nodes_with_limits(resolver, Node::parse_with_limits(str, limits)?, limits)
+}
+
+/// Evaluate an expression parsed with the operators of a table instead of the standard operators.
+///
+/// See [`operators`] for how to change the precedence of operators or add custom operators.
+pub fn eval_with_operators(
+    resolver: &dyn Resolver,
+    str: &str,
+    operators: &OperatorTable,
+) -> Result<Evaluated, Error> {
+    eval_nodes(resolver, Node::parse_with_operators(str, operators)?)
+}
+
+/// Evaluate an untrusted expression parsed with the operators of a table, within resource limits.
+pub fn eval_with_operators_and_limits(
+    resolver: &dyn Resolver,
+    str: &str,
+    operators: &OperatorTable,
+    limits: &EvalLimits,
+) -> Result<Evaluated, Error> {
+    let nodes = Node::parse_with_operators_and_limits(str, operators, limits)?;
+    eval_nodes_with_limits(resolver, nodes, limits)
+}
+
+/// Evaluate nodes within resource limits.
+///
+/// Only the limits which apply to evaluation are checked, the nodes must already be parsed.
+pub fn eval_nodes_with_limits<'a>(
+    resolver: &dyn Resolver,
+    nodes: impl Iterator<Item = Node<'a>>,
+    limits: &EvalLimits,
+) -> Result<Evaluated, Error> {
+    run(&Context::new(resolver, limits, &Usage::default()), nodes)
+}
+
+/// Evaluate nodes in a context which may be shared with other evaluations.
+///
+/// The nodes may be borrowed, so the body of a lambda or user-defined function is evaluated without copying it.
+pub(crate) fn run<'a, N: Borrow<Node<'a>>>(
+    ctx: &Context<'_>,
+    mut nodes: impl Iterator<Item = N>,
+) -> Result<Evaluated, Error> {
+    let resolver = ctx.resolver;
+
     // Most of the time operations do not go deeper than 4, let's preallocate for 4 entries.
-    let mut stack = Vec::<Node>::with_capacity(4);
+    let mut stack = Vec::<Operand>::with_capacity(4);
+
+    while let Some(node) = nodes.next() {
+        let node = node.borrow();
+        let node_span = node.span();
+        ctx.step(node_span)?;
+
+        match *node {
+            Node::Unary(Unary { ref kind, span }) => {
+                let value = match *kind {
+                    UnaryKind::Int(int) => Evaluated::Decimal(Decimal::from(int)),
+                    UnaryKind::Decimal(decimal) => Evaluated::Decimal(decimal),
+                    UnaryKind::Bool(bool) => Evaluated::Bool(bool),
+                    UnaryKind::Str(ref raw) => string::unescape(raw)
+                        .map(Evaluated::Str)
+                        .map_err(|_| error(ErrorKind::InvalidEscape, span))?,
+                    UnaryKind::Constant(ref name) => {
+                        resolver.resolve_const_symbol(name).ok_or_else(|| Error {
+                            kind: ErrorKind::UnknownConstant,
+                            span,
+                            suggestions: suggest::constants(resolver, name),
+                        })?
+                    }
+                };
+
+                stack.push(Operand { value, span });
+            }
+
+            Node::Function(Function {
+                ref name,
+                args,
+                span,
+            }) => {
+                let start = stack
+                    .len()
+                    .checked_sub(args)
+                    .ok_or_else(|| error(ErrorKind::MissingOperand, span))?;
+
+                let args = stack
+                    .drain(start..)
+                    .map(|operand| operand.value)
+                    .collect::<Vec<_>>();
+
+                ctx.steps(builtin::cost(name, &args), span)?;
+
+                let value = match lambda::call(ctx, name, &args, span) {
+                    Some(result) => result?,
+                    None => match builtin::call(name, &args, ctx.limits) {
+                        Some(result) => result.map_err(|kind| error(kind, span))?,
+                        None => resolver
+                            .resolve_function(name, &args)
+                            .map_err(|kind| unknown_function(resolver, kind, name, span))?,
+                    },
+                };
+
+                stack.push(Operand { value, span });
+            }
+
+            Node::Operator(Operator { kind, span }) => {
+                let value = match kind {
+                    OperatorKind::Neg => pop(&mut stack, span)?.map(&mut |value| Ok(-value))?,
+                    OperatorKind::Factorial => pop(&mut stack, span)?
+                        .map(&mut |value| factorial(value, span, ctx.limits))?,
+
+                    kind => {
+                        let rhs = pop(&mut stack, span)?;
+                        let lhs = pop(&mut stack, span)?;
+
+                        match (&lhs.value, &rhs.value, kind) {
+                            (Evaluated::Matrix(a), Evaluated::Matrix(b), OperatorKind::Mul) => {
+                                let cost =
+                                    a.rows().saturating_mul(a.cols()).saturating_mul(b.cols());
+                                ctx.steps(cost, span)?;
+                                ctx.limits
+                                    .check_elements(a.rows().saturating_mul(b.cols()))
+                                    .map_err(|kind| error(kind, span))?;
+
+                                a.mul(b)
+                                    .map(Evaluated::Matrix)
+                                    .map_err(|kind| error(kind, rhs.span))?
+                            }
+
+                            _ => lhs.zip(rhs, span, &mut |lhs, rhs| {
+                                arithmetic(kind, lhs, rhs, span, ctx.limits)
+                            })?,
+                        }
+                    }
+                };
+
+                stack.push(Operand { value, span });
+            }
+
+            Node::Equation(Equation { kind, span }) => {
+                let rhs = pop(&mut stack, span)?;
+                let lhs = pop(&mut stack, span)?;
+
+                let value = match kind {
+                    EquationKind::Eq => lhs.equals(rhs)?,
+                    EquationKind::Neq => !lhs.equals(rhs)?,
 
-    for node in nodes {
-        match node {
-            Node::Unary(_) => stack.push(node),
+                    kind => {
+                        let (lhs, rhs) = (lhs.decimal()?, rhs.decimal()?);
 
-            Node::Function(Function { name, .. }) => {
-                resolver.resolve_function(name, &mut stack)?;
+                        match kind {
+                            EquationKind::Gt => lhs > rhs,
+                            EquationKind::Lt => lhs < rhs,
+                            EquationKind::Ge => lhs >= rhs,
+                            _ => lhs <= rhs,
+                        }
+                    }
+                };
+
+                stack.push(Operand {
+                    value: Evaluated::Bool(value),
+                    span,
+                });
+            }
+
+            Node::Logical(Logical { kind, span }) => {
+                let value = match kind {
+                    LogicalKind::Not => !pop(&mut stack, span)?.bool()?,
+
+                    kind => {
+                        let rhs = pop(&mut stack, span)?.bool()?;
+                        let lhs = pop(&mut stack, span)?.bool()?;
+
+                        match kind {
+                            LogicalKind::And => lhs && rhs,
+                            _ => lhs || rhs,
+                        }
+                    }
+                };
+
+                stack.push(Operand {
+                    value: Evaluated::Bool(value),
+                    span,
+                });
+            }
+
+            Node::List(List { len, span }) => {
+                let start = stack
+                    .len()
+                    .checked_sub(len)
+                    .ok_or_else(|| error(ErrorKind::MissingOperand, span))?;
+
+                let value = list(stack.drain(start..).map(|operand| operand.value).collect());
+                ctx.limits
+                    .check_size(&value)
+                    .map_err(|kind| error(kind, span))?;
+
+                stack.push(Operand { value, span });
+            }
+
+            Node::Custom(CustomOperator { ref name, span }) => {
+                let rhs = pop(&mut stack, span)?;
+                let lhs = pop(&mut stack, span)?;
+                let value = resolver
+                    .resolve_operator(name, &lhs.value, &rhs.value)
+                    .map_err(|kind| error(kind, span))?;
+
+                stack.push(Operand { value, span });
             }
 
-            Node::Operator(Operator { kind, .. }) => match kind {
-                OperatorKind::Add => todo!(),
-                OperatorKind::Sub => todo!(),
-                OperatorKind::Mul => todo!(),
-                OperatorKind::Div => todo!(),
-                OperatorKind::Mod => todo!(),
-                OperatorKind::Pow => todo!(),
+            Node::Index(Index { span }) => {
+                let index = pop(&mut stack, span)?;
+                let list = pop(&mut stack, span)?.list()?;
+                let value = index
+                    .index()
+                    .and_then(|i| list.into_iter().nth(i).ok_or(ErrorKind::OutOfRange))
+                    .map_err(|kind| error(kind, index.span))?;
+
+                stack.push(Operand { value, span });
+            }
+
+            Node::Lambda(ref lambda) => {
+                let span = lambda.span;
+                let value = Evaluated::Lambda(Closure::new(lambda, resolver));
+                stack.push(Operand { value, span });
+            }
+
+            Node::Error(ref error) => return Err(error.clone()),
+
+            Node::Jump(Jump { kind, skip, span }) => {
+                let taken = match kind {
+                    JumpKind::Always => true,
+                    JumpKind::Unless => !pop(&mut stack, span)?.bool()?,
+
+                    kind => {
+                        let top = stack
+                            .last()
+                            .cloned()
+                            .ok_or_else(|| error(ErrorKind::MissingOperand, span))?;
+                        top.bool()? == (kind == JumpKind::IfTrue)
+                    }
+                };
+
+                if taken && skip != 0 {
+                    nodes.nth(skip - 1);
+                }
+            }
+        }
+
+        if stack.len() > ctx.limits.max_stack {
+            return Err(error(ErrorKind::StackOverflow, node_span));
+        }
+    }
+
+    match stack.pop() {
+        Some(operand) if stack.is_empty() => Ok(operand.value),
+
+        result => Err(error(
+            ErrorKind::MissingOperand,
+            result.map_or(Span { start: 0, end: 0 }, |operand| operand.span),
+        )),
+    }
+}
+
+/// A value on the evaluation stack and the span of the expression which produced it.
+#[derive(Clone)]
+struct Operand {
+    value: Evaluated,
+    span: Span,
+}
+
+impl Operand {
+    fn mismatch(&self, expected: Type) -> Error {
+        error(
+            ErrorKind::TypeMismatch {
+                expected,
+                found: self.value.ty(),
             },
+            self.span,
+        )
+    }
+
+    fn decimal(self) -> Result<Decimal, Error> {
+        match self.value {
+            Evaluated::Decimal(decimal) => Ok(decimal),
+            _ => Err(self.mismatch(Type::Decimal)),
+        }
+    }
 
-            Node::Equation(Equation { kind: _, .. }) => todo!(),
+    fn bool(self) -> Result<bool, Error> {
+        match self.value {
+            Evaluated::Bool(bool) => Ok(bool),
+            _ => Err(self.mismatch(Type::Bool)),
         }
     }
 
-    todo!()
+    /// A list, or the rows of a matrix.
+    fn list(self) -> Result<Vec<Evaluated>, Error> {
+        match self.value {
+            Evaluated::List(list) => Ok(list),
+            Evaluated::Matrix(matrix) => Ok(builtin::rows(&matrix)),
+            _ => Err(self.mismatch(Type::List)),
+        }
+    }
+
+    /// A non-negative integer used as an index.
+    fn index(&self) -> Result<usize, ErrorKind> {
+        builtin::index(&self.value)
+    }
+
+    /// Apply a numeric operation to a number, or to each element of a list.
+    fn map(
+        self,
+        f: &mut impl FnMut(Decimal) -> Result<Decimal, Error>,
+    ) -> Result<Evaluated, Error> {
+        let Operand { value, span } = self;
+
+        match value {
+            Evaluated::Decimal(decimal) => f(decimal).map(Evaluated::Decimal),
+            Evaluated::List(list) => list
+                .into_iter()
+                .map(|value| Operand { value, span }.map(f))
+                .collect::<Result<_, _>>()
+                .map(Evaluated::List),
+            Evaluated::Matrix(matrix) => matrix.map(f).map(Evaluated::Matrix),
+            value => Err(Operand { value, span }.mismatch(Type::Decimal)),
+        }
+    }
+
+    /// Apply a numeric operation element-wise to two lists of the same length or two matrices of the same shape.
+    ///
+    /// If only one operand is a list or matrix, the other operand is applied to each element.
+    fn zip(
+        self,
+        rhs: Operand,
+        span: Span,
+        f: &mut impl FnMut(Decimal, Decimal) -> Result<Decimal, Error>,
+    ) -> Result<Evaluated, Error> {
+        let (lhs_span, rhs_span) = (self.span, rhs.span);
+        let lhs = |value| Operand {
+            value,
+            span: lhs_span,
+        };
+        let rhs_operand = |value| Operand {
+            value,
+            span: rhs_span,
+        };
+
+        let list: Result<Vec<_>, _> = match (self.value, rhs.value) {
+            (Evaluated::Decimal(lhs), Evaluated::Decimal(rhs)) => {
+                return f(lhs, rhs).map(Evaluated::Decimal)
+            }
+
+            (Evaluated::Matrix(lhs), Evaluated::Matrix(rhs)) => {
+                return match lhs.zip(&rhs, f) {
+                    Some(result) => result.map(Evaluated::Matrix),
+                    None => Err(error(
+                        ErrorKind::ShapeMismatch {
+                            expected: lhs.shape(),
+                            found: rhs.shape(),
+                        },
+                        rhs_span,
+                    )),
+                };
+            }
+
+            (Evaluated::Matrix(lhs), Evaluated::Decimal(rhs)) => {
+                return lhs.map(|l| f(l, rhs)).map(Evaluated::Matrix)
+            }
+
+            (Evaluated::Decimal(lhs), Evaluated::Matrix(rhs)) => {
+                return rhs.map(|r| f(lhs, r)).map(Evaluated::Matrix)
+            }
+
+            (Evaluated::List(lhs_list), Evaluated::List(rhs_list)) => {
+                if lhs_list.len() != rhs_list.len() {
+                    return Err(error(
+                        ErrorKind::LengthMismatch {
+                            expected: lhs_list.len(),
+                            found: rhs_list.len(),
+                        },
+                        span,
+                    ));
+                }
+
+                lhs_list
+                    .into_iter()
+                    .zip(rhs_list)
+                    .map(|(l, r)| lhs(l).zip(rhs_operand(r), span, f))
+                    .collect()
+            }
+
+            (Evaluated::List(lhs_list), r @ Evaluated::Decimal(_)) => lhs_list
+                .into_iter()
+                .map(|l| lhs(l).zip(rhs_operand(r.clone()), span, f))
+                .collect(),
+
+            (l @ Evaluated::Decimal(_), Evaluated::List(rhs_list)) => rhs_list
+                .into_iter()
+                .map(|r| lhs(l.clone()).zip(rhs_operand(r), span, f))
+                .collect(),
+
+            (Evaluated::Decimal(_) | Evaluated::List(_) | Evaluated::Matrix(_), r) => {
+                return Err(rhs_operand(r).mismatch(Type::Decimal))
+            }
+
+            (l, _) => return Err(lhs(l).mismatch(Type::Decimal)),
+        };
+
+        list.map(Evaluated::List)
+    }
+
+    /// Compare two values of the same type for equality.
+    fn equals(self, rhs: Operand) -> Result<bool, Error> {
+        if self.value.ty() != rhs.value.ty() {
+            return Err(rhs.mismatch(self.value.ty()));
+        }
+
+        Ok(self.value == rhs.value)
+    }
+}
+
+/// A list of values, which is a matrix if every value is a list of numbers with the same length.
+fn list(values: Vec<Evaluated>) -> Evaluated {
+    match Matrix::from_rows(&values) {
+        Some(matrix) => Evaluated::Matrix(matrix),
+        None => Evaluated::List(values),
+    }
+}
+
+fn error(kind: ErrorKind, span: Span) -> Error {
+    Error {
+        kind,
+        span,
+        suggestions: Vec::new(),
+    }
+}
+
+/// An error from calling a function of the resolver.
+///
+/// The error of a function which the resolver does have comes from within the function, so no names are suggested.
+fn unknown_function(resolver: &dyn Resolver, kind: ErrorKind, name: &str, span: Span) -> Error {
+    let suggestions = match kind {
+        ErrorKind::UnknownFunction if !resolver.has_function(name) => {
+            suggest::functions(resolver, name)
+        }
+        _ => Vec::new(),
+    };
+
+    Error {
+        kind,
+        span,
+        suggestions,
+    }
+}
+
+/// Pop an evaluated operand from the stack.
+fn pop(stack: &mut Vec<Operand>, span: Span) -> Result<Operand, Error> {
+    stack
+        .pop()
+        .ok_or_else(|| error(ErrorKind::MissingOperand, span))
+}
+
+fn arithmetic(
+    kind: OperatorKind,
+    lhs: Decimal,
+    rhs: Decimal,
+    span: Span,
+    limits: &EvalLimits,
+) -> Result<Decimal, Error> {
+    if matches!(
+        kind,
+        OperatorKind::Div | OperatorKind::IntDiv | OperatorKind::Mod
+    ) && rhs.is_zero()
+    {
+        return Err(error(ErrorKind::DivideByZero, span));
+    }
+
+    if kind == OperatorKind::Pow && rhs.abs() > Decimal::from(limits.max_exponent) {
+        return Err(error(ErrorKind::ExponentTooLarge, span));
+    }
+
+    let value = match kind {
+        OperatorKind::Add => lhs.checked_add(rhs),
+        OperatorKind::Sub => lhs.checked_sub(rhs),
+        OperatorKind::Mul => lhs.checked_mul(rhs),
+        OperatorKind::Div => lhs.checked_div(rhs),
+        OperatorKind::Mod => lhs.checked_rem(rhs),
+        OperatorKind::IntDiv => lhs.checked_div(rhs).map(|value| value.floor()),
+
+        // Integer powers are exact, use them where possible.
+        _ => match rhs.is_integer().then(|| rhs.to_i64()).flatten() {
+            Some(exp) => lhs.checked_powi(exp),
+            None => lhs.checked_powd(rhs),
+        },
+    };
+
+    value.ok_or_else(|| error(ErrorKind::Overflow, span))
+}
+
+fn factorial(value: Decimal, span: Span, limits: &EvalLimits) -> Result<Decimal, Error> {
+    if !value.is_integer() || value.is_sign_negative() {
+        return Err(error(ErrorKind::Undefined, span));
+    }
+
+    if value > Decimal::from(limits.max_factorial) {
+        return Err(error(ErrorKind::FactorialTooLarge, span));
+    }
+
+    let mut result = Decimal::ONE;
+    let mut n = Decimal::ONE;
+
+    while n <= value {
+        result = result
+            .checked_mul(n)
+            .ok_or_else(|| error(ErrorKind::Overflow, span))?;
+        n += Decimal::ONE;
+    }
+
+    Ok(result)
 }
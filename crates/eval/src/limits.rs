@@ -0,0 +1,229 @@
+//! Resource limits for evaluating untrusted expressions.
+
+use core::cell::Cell;
+
+use crate::{error, node::Span, resolve::Resolver, Error, ErrorKind, Evaluated};
+
+/// Limits on the resources used to parse and evaluate an expression.
+///
+/// Exceeding a limit fails with a distinct [`ErrorKind`] instead of hanging or allocating without bound. The
+/// [`Default`] limits are suitable for expressions entered by users, [`EvalLimits::UNLIMITED`] is used by functions
+/// such as [`eval`](crate::eval) which take no limits.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct EvalLimits {
+    /// The maximum length of the input in bytes.
+    ///
+    /// Exceeding this fails with [`ErrorKind::InputTooLong`].
+    pub max_input_len: usize,
+
+    /// The maximum number of tokens, excluding whitespace.
+    ///
+    /// Exceeding this fails with [`ErrorKind::TooManyTokens`].
+    pub max_tokens: usize,
+
+    /// The maximum number of braces which may be open at once.
+    ///
+    /// Exceeding this fails with [`ErrorKind::NestingTooDeep`].
+    pub max_nesting: usize,
+
+    /// The maximum number of nodes which are evaluated, including the bodies of lambdas and user-defined functions.
+    ///
+    /// Built-in functions and operators which do more work than a node count a step for each multiplication, such
+    /// as the `n³` steps of `inverse` of an `n` by `n` matrix.
+    ///
+    /// Exceeding this fails with [`ErrorKind::StepLimit`].
+    pub max_steps: usize,
+
+    /// The maximum number of values on the evaluation stack.
+    ///
+    /// Exceeding this fails with [`ErrorKind::StackOverflow`].
+    pub max_stack: usize,
+
+    /// The maximum magnitude of the exponent of `^`.
+    ///
+    /// Exceeding this fails with [`ErrorKind::ExponentTooLarge`].
+    pub max_exponent: u32,
+
+    /// The maximum argument of the factorial operator.
+    ///
+    /// Exceeding this fails with [`ErrorKind::FactorialTooLarge`].
+    pub max_factorial: u32,
+
+    /// The maximum number of values in a list or matrix, including the values in nested lists.
+    ///
+    /// Exceeding this fails with [`ErrorKind::TooManyElements`].
+    pub max_elements: usize,
+
+    /// The maximum length of a string in bytes, or of all the strings in a list together.
+    ///
+    /// Exceeding this fails with [`ErrorKind::StringTooLong`].
+    pub max_string_len: usize,
+}
+
+impl EvalLimits {
+    /// No limits at all.
+    pub const UNLIMITED: EvalLimits = EvalLimits {
+        max_input_len: usize::MAX,
+        max_tokens: usize::MAX,
+        max_nesting: usize::MAX,
+        max_steps: usize::MAX,
+        max_stack: usize::MAX,
+        max_exponent: u32::MAX,
+        max_factorial: u32::MAX,
+        max_elements: usize::MAX,
+        max_string_len: usize::MAX,
+    };
+
+    /// Check the length of the input.
+    pub(crate) fn check_input(&self, str: &str) -> Result<(), Error> {
+        if str.len() > self.max_input_len {
+            return Err(error(
+                ErrorKind::InputTooLong,
+                Span {
+                    start: self.max_input_len,
+                    end: str.len(),
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check the number of values of a list or matrix, before it is created where possible.
+    pub(crate) fn check_elements(&self, elements: usize) -> Result<(), ErrorKind> {
+        match elements > self.max_elements {
+            true => Err(ErrorKind::TooManyElements),
+            false => Ok(()),
+        }
+    }
+
+    /// Check the length of a string, while it is built where possible.
+    pub(crate) fn check_string_len(&self, len: usize) -> Result<(), ErrorKind> {
+        match len > self.max_string_len {
+            true => Err(ErrorKind::StringTooLong),
+            false => Ok(()),
+        }
+    }
+
+    /// Check the number of values and the length of the strings of a value, including nested lists.
+    pub(crate) fn check_size(&self, value: &Evaluated) -> Result<(), ErrorKind> {
+        if self.max_elements == usize::MAX && self.max_string_len == usize::MAX {
+            return Ok(());
+        }
+
+        let (mut elements, mut len) = (0, 0);
+        size(value, &mut elements, &mut len);
+
+        self.check_elements(elements)?;
+        self.check_string_len(len)
+    }
+}
+
+/// Add the number of values and the length of the strings of a value.
+fn size(value: &Evaluated, elements: &mut usize, len: &mut usize) {
+    match value {
+        Evaluated::Str(str) => *len = len.saturating_add(str.len()),
+        Evaluated::List(list) => {
+            *elements = elements.saturating_add(list.len());
+
+            for value in list {
+                size(value, elements, len);
+            }
+        }
+        Evaluated::Matrix(matrix) => {
+            *elements = elements.saturating_add(matrix.rows().saturating_mul(matrix.cols()));
+        }
+        _ => (),
+    }
+}
+
+impl Default for EvalLimits {
+    fn default() -> Self {
+        Self {
+            max_input_len: 16 * 1024,
+            max_tokens: 4096,
+            max_nesting: 64,
+            max_steps: 1_000_000,
+            max_stack: 1024,
+            max_exponent: 1024,
+            max_factorial: 100,
+            max_elements: 65_536,
+            max_string_len: 64 * 1024,
+        }
+    }
+}
+
+/// The resources used by an evaluation and every nested evaluation so far.
+#[derive(Default)]
+pub(crate) struct Usage {
+    /// The number of nodes evaluated.
+    steps: Cell<usize>,
+
+    /// The number of evaluations of the bodies of `sum` and `prod` and the expressions of `integrate` and `root`.
+    iterations: Cell<usize>,
+}
+
+/// The state shared by an evaluation and every nested evaluation, such as the body of a lambda.
+pub(crate) struct Context<'a> {
+    pub(crate) resolver: &'a dyn Resolver,
+    pub(crate) limits: &'a EvalLimits,
+    usage: &'a Usage,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(
+        resolver: &'a dyn Resolver,
+        limits: &'a EvalLimits,
+        usage: &'a Usage,
+    ) -> Self {
+        Self {
+            resolver,
+            limits,
+            usage,
+        }
+    }
+
+    /// A context for a nested evaluation, which shares the limits and usage but resolves names differently.
+    pub(crate) fn with<'b>(&'b self, resolver: &'b dyn Resolver) -> Context<'b> {
+        Context {
+            resolver,
+            limits: self.limits,
+            usage: self.usage,
+        }
+    }
+
+    /// Count the evaluation of a node.
+    pub(crate) fn step(&self, span: Span) -> Result<(), Error> {
+        self.steps(1, span)
+    }
+
+    /// Count `count` steps of the work done by a node, before the work is done.
+    pub(crate) fn steps(&self, count: usize, span: Span) -> Result<(), Error> {
+        let steps = self.usage.steps.get().saturating_add(count);
+
+        if steps > self.limits.max_steps {
+            return Err(error(ErrorKind::StepLimit, span));
+        }
+
+        self.usage.steps.set(steps);
+        Ok(())
+    }
+
+    /// Count `count` iterations of a series or numeric method.
+    ///
+    /// Iterations are counted across nested evaluations, so the bodies of nested series are evaluated at most
+    /// [`Resolver::max_iterations`] times in total.
+    pub(crate) fn iterate(&self, count: usize, span: Span) -> Result<(), Error> {
+        let iterations = self
+            .usage
+            .iterations
+            .get()
+            .checked_add(count)
+            .filter(|&iterations| iterations <= self.resolver.max_iterations())
+            .ok_or_else(|| error(ErrorKind::IterationLimit, span))?;
+
+        self.usage.iterations.set(iterations);
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+//! Matrices of decimal numbers.
+
+use alloc::vec::Vec;
+use rust_decimal::Decimal;
+
+use crate::{ErrorKind, Evaluated};
+
+/// A matrix of decimal numbers, stored in row-major order.
+///
+/// A matrix always has at least one row and one column.
+///
+/// With the `serde` feature a matrix is an object with `rows`, `cols` and the `data` in row-major order. A matrix with
+/// a zero dimension or the wrong number of values fails to deserialize.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Shape")
+)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Decimal>,
+}
+
+/// The fields of a matrix before the dimensions are checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Shape {
+    rows: usize,
+    cols: usize,
+    data: Vec<Decimal>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Shape> for Matrix {
+    type Error = &'static str;
+
+    fn try_from(shape: Shape) -> Result<Self, Self::Error> {
+        Matrix::new(shape.rows, shape.cols, shape.data)
+            .ok_or("the number of values does not match the dimensions of the matrix")
+    }
+}
+
+impl Matrix {
+    /// The largest number of rows and columns of an [identity](Matrix::identity) matrix.
+    pub const MAX_DIMENSION: usize = 1024;
+
+    /// Creates a matrix from values in row-major order.
+    ///
+    /// Returns [`None`] if either dimension is zero or the number of values does not match the dimensions.
+    pub fn new(rows: usize, cols: usize, data: Vec<Decimal>) -> Option<Self> {
+        (rows != 0 && cols != 0 && rows.checked_mul(cols) == Some(data.len())).then_some(Self {
+            rows,
+            cols,
+            data,
+        })
+    }
+
+    /// Creates an `n` by `n` identity matrix.
+    ///
+    /// Returns [`None`] if `n` is zero or larger than [`Matrix::MAX_DIMENSION`].
+    pub fn identity(n: usize) -> Option<Self> {
+        if n > Self::MAX_DIMENSION {
+            return None;
+        }
+
+        let data = (0..n.checked_mul(n)?)
+            .map(|i| {
+                if i / n == i % n {
+                    Decimal::ONE
+                } else {
+                    Decimal::ZERO
+                }
+            })
+            .collect();
+
+        Self::new(n, n, data)
+    }
+
+    /// Creates a matrix from a list of rows.
+    ///
+    /// Every row must be a non-empty list of numbers and all rows must have the same length.
+    pub fn from_rows(rows: &[Evaluated]) -> Option<Self> {
+        let cols = match rows.first()? {
+            Evaluated::List(row) => row.len(),
+            _ => return None,
+        };
+
+        let mut data = Vec::with_capacity(rows.len() * cols);
+
+        for row in rows {
+            match row {
+                Evaluated::List(row) if row.len() == cols => {
+                    for value in row {
+                        match value {
+                            Evaluated::Decimal(decimal) => data.push(*decimal),
+                            _ => return None,
+                        }
+                    }
+                }
+
+                _ => return None,
+            }
+        }
+
+        Self::new(rows.len(), cols, data)
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The element at a row and column.
+    pub fn get(&self, row: usize, col: usize) -> Option<Decimal> {
+        if col >= self.cols {
+            return None;
+        }
+
+        self.data.get(row * self.cols + col).copied()
+    }
+
+    /// The elements of a row.
+    pub fn row(&self, row: usize) -> Option<&[Decimal]> {
+        let start = row.checked_mul(self.cols)?;
+        self.data.get(start..start + self.cols)
+    }
+
+    /// Swap the rows and columns.
+    pub fn transpose(&self) -> Self {
+        let data = (0..self.data.len())
+            .map(|i| self.data[(i % self.rows) * self.cols + i / self.rows])
+            .collect();
+
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            data,
+        }
+    }
+
+    fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    fn square(&self) -> Result<usize, ErrorKind> {
+        if !self.is_square() {
+            return Err(ErrorKind::ShapeMismatch {
+                expected: (self.rows, self.rows),
+                found: self.shape(),
+            });
+        }
+
+        Ok(self.rows)
+    }
+
+    /// Apply a function to each element.
+    pub(crate) fn map<E>(&self, f: impl FnMut(Decimal) -> Result<Decimal, E>) -> Result<Self, E> {
+        Ok(Self {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().copied().map(f).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Apply a function to each pair of elements of two matrices with the same shape.
+    ///
+    /// Returns [`None`] if the shapes are different.
+    pub(crate) fn zip<E>(
+        &self,
+        rhs: &Self,
+        mut f: impl FnMut(Decimal, Decimal) -> Result<Decimal, E>,
+    ) -> Option<Result<Self, E>> {
+        if self.shape() != rhs.shape() {
+            return None;
+        }
+
+        let data = self
+            .data
+            .iter()
+            .zip(&rhs.data)
+            .map(|(lhs, rhs)| f(*lhs, *rhs))
+            .collect::<Result<_, _>>();
+
+        Some(data.map(|data| Self {
+            rows: self.rows,
+            cols: self.cols,
+            data,
+        }))
+    }
+
+    /// Matrix multiplication.
+    pub(crate) fn mul(&self, rhs: &Self) -> Result<Self, ErrorKind> {
+        if self.cols != rhs.rows {
+            return Err(ErrorKind::ShapeMismatch {
+                expected: (self.cols, rhs.cols),
+                found: rhs.shape(),
+            });
+        }
+
+        let mut data = Vec::with_capacity(self.rows * rhs.cols);
+
+        for row in 0..self.rows {
+            for col in 0..rhs.cols {
+                let mut sum = Decimal::ZERO;
+
+                for i in 0..self.cols {
+                    sum = self.data[row * self.cols + i]
+                        .checked_mul(rhs.data[i * rhs.cols + col])
+                        .and_then(|product| sum.checked_add(product))
+                        .ok_or(ErrorKind::Overflow)?;
+                }
+
+                data.push(sum);
+            }
+        }
+
+        Ok(Self {
+            rows: self.rows,
+            cols: rhs.cols,
+            data,
+        })
+    }
+
+    /// The determinant of a square matrix.
+    pub(crate) fn det(&self) -> Result<Decimal, ErrorKind> {
+        let n = self.square()?;
+        determinant(self.data.clone(), n)
+    }
+
+    /// The inverse of a square matrix.
+    ///
+    /// This uses fraction-free Gauss-Jordan elimination on the matrix next to the identity, which turns the matrix
+    /// into the determinant times the identity and the identity into the adjugate. Every division during the
+    /// elimination is exact, so the only rounding happens when the adjugate is divided by the determinant.
+    pub(crate) fn inverse(&self) -> Result<Self, ErrorKind> {
+        let n = self.square()?;
+        let width = 2 * n;
+
+        let mut data = Vec::with_capacity(n * width);
+        for row in 0..n {
+            data.extend_from_slice(&self.data[row * n..(row + 1) * n]);
+            data.extend((0..n).map(|col| {
+                if col == row {
+                    Decimal::ONE
+                } else {
+                    Decimal::ZERO
+                }
+            }));
+        }
+
+        let mut previous = Decimal::ONE;
+
+        for k in 0..n {
+            if data[k * width + k].is_zero() {
+                // Swap with a row below which has a non-zero pivot, otherwise the matrix is singular.
+                let swap = (k + 1..n)
+                    .find(|&row| !data[row * width + k].is_zero())
+                    .ok_or(ErrorKind::SingularMatrix)?;
+
+                for col in 0..width {
+                    data.swap(k * width + col, swap * width + col);
+                }
+            }
+
+            let pivot = data[k * width + k];
+
+            for row in (0..n).filter(|&row| row != k) {
+                let factor = data[row * width + k];
+
+                for col in (0..width).filter(|&col| col != k) {
+                    let value = data[row * width + col]
+                        .checked_mul(pivot)
+                        .zip(factor.checked_mul(data[k * width + col]))
+                        .and_then(|(a, b)| a.checked_sub(b))
+                        .and_then(|value| value.checked_div(previous))
+                        .ok_or(ErrorKind::Overflow)?;
+
+                    data[row * width + col] = value;
+                }
+
+                data[row * width + k] = Decimal::ZERO;
+            }
+
+            previous = pivot;
+        }
+
+        // Every element of the diagonal is now the determinant.
+        let det = previous;
+        let data = (0..n)
+            .flat_map(|row| data[row * width + n..(row + 1) * width].iter())
+            .map(|value| value.checked_div(det).ok_or(ErrorKind::Overflow))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            rows: n,
+            cols: n,
+            data,
+        })
+    }
+}
+
+/// The determinant of an `n` by `n` matrix in row-major order.
+///
+/// This uses the fraction-free Bareiss algorithm, where every division is exact.
+fn determinant(mut data: Vec<Decimal>, n: usize) -> Result<Decimal, ErrorKind> {
+    if n == 0 {
+        return Ok(Decimal::ONE);
+    }
+
+    let mut negate = false;
+    let mut previous = Decimal::ONE;
+
+    for k in 0..n - 1 {
+        if data[k * n + k].is_zero() {
+            // Swap with a row below which has a non-zero pivot, otherwise the determinant is zero.
+            let Some(swap) = (k + 1..n).find(|&row| !data[row * n + k].is_zero()) else {
+                return Ok(Decimal::ZERO);
+            };
+
+            for col in 0..n {
+                data.swap(k * n + col, swap * n + col);
+            }
+
+            negate = !negate;
+        }
+
+        let pivot = data[k * n + k];
+
+        for row in k + 1..n {
+            for col in k + 1..n {
+                let value = data[row * n + col]
+                    .checked_mul(pivot)
+                    .zip(data[row * n + k].checked_mul(data[k * n + col]))
+                    .and_then(|(a, b)| a.checked_sub(b))
+                    .and_then(|value| value.checked_div(previous))
+                    .ok_or(ErrorKind::Overflow)?;
+
+                data[row * n + col] = value;
+            }
+        }
+
+        previous = pivot;
+    }
+
+    let det = data[n * n - 1];
+    Ok(if negate { -det } else { det })
+}
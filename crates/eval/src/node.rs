@@ -1,21 +1,43 @@
+use alloc::{
+    borrow::Cow,
+    vec::{IntoIter, Vec},
+};
 use equation_lexer::Token;
+use rust_decimal::Decimal;
 
-#[derive(Clone, Copy)]
+use crate::{limits::EvalLimits, operators::OperatorTable, parse::Parser, Error};
+
+/// A region of the source expression.
+///
+/// The start and end of a span are byte offsets into the source string.
+#[derive(Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "fmt", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     pub start: usize,
     pub end: usize,
 }
 
+impl Span {
+    /// Returns a span covering both this span and the other span.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
 pub trait Spanned {
     fn span(&self) -> Span;
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
-#[derive(Clone, Copy)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unary<'a> {
     pub kind: UnaryKind<'a>,
-    span: Span,
+    pub(crate) span: Span,
 }
 
 impl Spanned for Unary<'_> {
@@ -25,25 +47,42 @@ impl Spanned for Unary<'_> {
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
-#[derive(Clone, Copy)]
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum UnaryKind<'a> {
     /// Integer value
     Int(i64),
 
     /// Decimal value
-    Decimal(f64),
+    Decimal(Decimal),
+
+    /// Boolean value
+    Bool(bool),
+
+    /// String value
+    ///
+    /// This is the contents of the string literal without the quotes, escape sequences are not yet processed.
+    Str(Cow<'a, str>),
 
     /// Constant symbol value
     ///
     /// This needs to be resolved.
-    Constant(&'a str),
+    Constant(Cow<'a, str>),
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
-#[derive(Clone, Copy)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function<'a> {
-    pub name: &'a str,
-    span: Span,
+    pub name: Cow<'a, str>,
+
+    /// The number of arguments passed to the function.
+    pub args: usize,
+    pub(crate) span: Span,
 }
 
 impl Spanned for Function<'_> {
@@ -53,7 +92,12 @@ impl Spanned for Function<'_> {
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum OperatorKind {
     Add,
 
@@ -65,14 +109,24 @@ pub enum OperatorKind {
 
     Mod,
 
+    /// Integer division, the quotient rounded down.
+    IntDiv,
+
     Pow,
+
+    /// Prefix negation, takes a single operand.
+    Neg,
+
+    /// Postfix factorial, takes a single operand.
+    Factorial,
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Operator {
     pub kind: OperatorKind,
-    span: Span,
+    pub(crate) span: Span,
 }
 
 impl Spanned for Operator {
@@ -82,22 +136,38 @@ impl Spanned for Operator {
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum EquationKind {
+    /// `=`
     Eq,
 
+    /// `!=`
     Neq,
 
+    /// `>`
+    Gt,
+
+    /// `<`
+    Lt,
+
+    /// `>=`
     Ge,
 
+    /// `<=`
     Le,
 }
 
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Equation {
     pub kind: EquationKind,
-    span: Span,
+    pub(crate) span: Span,
 }
 
 impl Spanned for Equation {
@@ -106,8 +176,167 @@ impl Spanned for Equation {
     }
 }
 
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LogicalKind {
+    /// `&&`
+    And,
+
+    /// `||`
+    Or,
+
+    /// Prefix `!`, takes a single operand.
+    Not,
+}
+
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Logical {
+    pub kind: LogicalKind,
+    pub(crate) span: Span,
+}
+
+impl Spanned for Logical {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum JumpKind {
+    /// Always skip.
+    Always,
+
+    /// Skip if the value on top of the stack is `false`, leaving the value on the stack.
+    IfFalse,
+
+    /// Skip if the value on top of the stack is `true`, leaving the value on the stack.
+    IfTrue,
+
+    /// Pop the value on top of the stack and skip if the value is `false`.
+    Unless,
+}
+
+/// A list literal, collecting the values of the previous `len` nodes.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct List {
+    /// The number of elements in the list.
+    pub len: usize,
+    pub(crate) span: Span,
+}
+
+impl Spanned for List {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// Indexing into a list, `list[index]`.
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Index {
+    pub(crate) span: Span,
+}
+
+impl Spanned for Index {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// An anonymous function, `x -> body` or `(x, y) -> body`.
+///
+/// Evaluating a lambda produces a closure, the body is only evaluated when the closure is called.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lambda<'a> {
+    /// The names of the parameters, in order.
+    pub params: Vec<Cow<'a, str>>,
+
+    /// The nodes of the body.
+    pub body: Vec<Node<'a>>,
+    pub(crate) span: Span,
+}
+
+impl Spanned for Lambda<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// A custom infix operator from an [`OperatorTable`], which takes two operands.
+///
+/// The operator is evaluated by [`Resolver::resolve_operator`](crate::resolve::Resolver::resolve_operator).
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomOperator<'a> {
+    /// The text of the operator, such as `xor`.
+    pub name: Cow<'a, str>,
+    pub(crate) span: Span,
+}
+
+impl Spanned for CustomOperator<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// A forward jump over a number of nodes.
+///
+/// Jumps implement short-circuiting for logical operators and conditionals.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Jump {
+    pub kind: JumpKind,
+
+    /// The number of nodes to skip if the jump is taken.
+    pub skip: usize,
+    pub(crate) span: Span,
+}
+
+impl Spanned for Jump {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// A node of a parsed expression.
+///
+/// Nodes are produced in postfix order, operands always come before the node which consumes them.
+///
+/// With the `serde` feature a node is externally tagged by its snake case variant name, and contains the fields of
+/// the part including its [`Span`]. Literals keep their kind, numbers are strings so no precision is lost:
+///
+/// ```json
+/// { "unary": { "kind": { "decimal": "1.5" }, "span": { "start": 0, "end": 3 } } }
+/// { "operator": { "kind": "add", "span": { "start": 0, "end": 13 } } }
+/// { "function": { "name": "sqrt", "args": 1, "span": { "start": 6, "end": 13 } } }
+/// ```
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Node<'a> {
     Unary(Unary<'a>),
 
@@ -116,6 +345,23 @@ pub enum Node<'a> {
     Operator(Operator),
 
     Equation(Equation),
+
+    Logical(Logical),
+
+    Custom(CustomOperator<'a>),
+
+    List(List),
+
+    Index(Index),
+
+    Lambda(Lambda<'a>),
+
+    Jump(Jump),
+
+    /// A part of the expression which could not be parsed, which is only produced when recovering from errors.
+    ///
+    /// Evaluating the node fails with the error.
+    Error(Error),
 }
 
 impl Spanned for Node<'_> {
@@ -125,19 +371,244 @@ impl Spanned for Node<'_> {
             Node::Function(token) => token.span(),
             Node::Operator(token) => token.span(),
             Node::Equation(token) => token.span(),
+            Node::Logical(token) => token.span(),
+            Node::Custom(token) => token.span(),
+            Node::List(token) => token.span(),
+            Node::Index(token) => token.span(),
+            Node::Lambda(token) => token.span(),
+            Node::Jump(token) => token.span(),
+            Node::Error(error) => error.span,
         }
     }
 }
 
 impl Node<'_> {
-    pub fn parse(str: &str) -> Result<impl Iterator<Item = Node> + '_, ()> {
+    /// Returns a node which does not borrow from the source string.
+    pub fn into_owned(self) -> Node<'static> {
+        let owned = |str: Cow<'_, str>| Cow::Owned(str.into_owned());
+
+        match self {
+            Node::Unary(Unary { kind, span }) => {
+                let kind = match kind {
+                    UnaryKind::Int(int) => UnaryKind::Int(int),
+                    UnaryKind::Decimal(decimal) => UnaryKind::Decimal(decimal),
+                    UnaryKind::Bool(bool) => UnaryKind::Bool(bool),
+                    UnaryKind::Str(str) => UnaryKind::Str(owned(str)),
+                    UnaryKind::Constant(name) => UnaryKind::Constant(owned(name)),
+                };
+
+                Node::Unary(Unary { kind, span })
+            }
+
+            Node::Function(Function { name, args, span }) => Node::Function(Function {
+                name: owned(name),
+                args,
+                span,
+            }),
+
+            Node::Operator(node) => Node::Operator(node),
+            Node::Equation(node) => Node::Equation(node),
+            Node::Logical(node) => Node::Logical(node),
+            Node::Custom(CustomOperator { name, span }) => Node::Custom(CustomOperator {
+                name: owned(name),
+                span,
+            }),
+            Node::List(node) => Node::List(node),
+            Node::Index(node) => Node::Index(node),
+            Node::Lambda(Lambda { params, body, span }) => Node::Lambda(Lambda {
+                params: params.into_iter().map(owned).collect(),
+                body: body.into_iter().map(Node::into_owned).collect(),
+                span,
+            }),
+            Node::Jump(node) => Node::Jump(node),
+            Node::Error(error) => Node::Error(error),
+        }
+    }
+
+    pub fn parse(str: &str) -> Result<IntoIter<Node<'_>>, Error> {
         let tokens = equation_lexer::Tokenizer::from(str);
-        Self::from_tokens(tokens)
+        Node::from_tokens(str, tokens)
+    }
+
+    /// Parse tokens into nodes.
+    ///
+    /// The tokens must have been produced from `str`.
+    pub fn from_tokens(
+        str: &str,
+        iter: impl Iterator<Item = Token>,
+    ) -> Result<IntoIter<Node<'_>>, Error> {
+        Parser::new(str, iter, &EvalLimits::UNLIMITED)?
+            .parse()
+            .map(IntoIterator::into_iter)
     }
 
-    pub fn from_tokens<'a>(
-        _iter: impl Iterator<Item = Token>,
-    ) -> Result<impl Iterator<Item = Node<'a>>, ()> {
-        Ok(core::iter::empty())
+    /// Parse an untrusted expression within the limits which apply to parsing.
+    pub fn parse_with_limits<'a>(
+        str: &'a str,
+        limits: &EvalLimits,
+    ) -> Result<IntoIter<Node<'a>>, Error> {
+        limits.check_input(str)?;
+
+        let tokens = equation_lexer::Tokenizer::from(str);
+        Parser::new(str, tokens, limits)?
+            .parse()
+            .map(IntoIterator::into_iter)
+    }
+
+    /// Parse an expression with the operators of a table instead of the standard operators.
+    pub fn parse_with_operators<'a>(
+        str: &'a str,
+        operators: &OperatorTable,
+    ) -> Result<IntoIter<Node<'a>>, Error> {
+        let tokens = equation_lexer::Tokenizer::from(str);
+        Node::from_tokens_with_operators(str, tokens, operators)
+    }
+
+    /// Parse an untrusted expression with the operators of a table, within the limits which apply to parsing.
+    pub fn parse_with_operators_and_limits<'a>(
+        str: &'a str,
+        operators: &OperatorTable,
+        limits: &EvalLimits,
+    ) -> Result<IntoIter<Node<'a>>, Error> {
+        limits.check_input(str)?;
+
+        let tokens = equation_lexer::Tokenizer::from(str);
+        Parser::new(str, tokens, limits)?
+            .with_operators(operators)
+            .parse()
+            .map(IntoIterator::into_iter)
+    }
+
+    /// Parse tokens into nodes with the operators of a table.
+    ///
+    /// The tokens must have been produced from `str`.
+    pub fn from_tokens_with_operators<'a>(
+        str: &'a str,
+        iter: impl Iterator<Item = Token>,
+        operators: &OperatorTable,
+    ) -> Result<IntoIter<Node<'a>>, Error> {
+        Parser::new(str, iter, &EvalLimits::UNLIMITED)?
+            .with_operators(operators)
+            .parse()
+            .map(IntoIterator::into_iter)
+    }
+
+    /// Parse an expression, recovering from errors to report every error instead of only the first.
+    ///
+    /// Parts of the expression which could not be parsed are replaced by [`Node::Error`] and parsing continues from
+    /// the next `,`, closing brace or operator. The errors are in the order they occur in the expression, if there
+    /// are none the nodes are the same as those returned by [`Node::parse`].
+    pub fn parse_recovering(str: &str) -> (Vec<Node<'_>>, Vec<Error>) {
+        Node::parse_recovering_with_limits(str, &EvalLimits::UNLIMITED)
+    }
+
+    /// Parse an untrusted expression within limits, recovering from errors.
+    ///
+    /// An input which is too long or has too many tokens has no nodes and only that error.
+    pub fn parse_recovering_with_limits<'a>(
+        str: &'a str,
+        limits: &EvalLimits,
+    ) -> (Vec<Node<'a>>, Vec<Error>) {
+        if let Err(error) = limits.check_input(str) {
+            return (Vec::new(), alloc::vec![error]);
+        }
+
+        let tokens = equation_lexer::Tokenizer::from(str);
+
+        match Parser::new(str, tokens, limits) {
+            Ok(parser) => parser.recovering(),
+            Err(error) => (Vec::new(), alloc::vec![error]),
+        }
+    }
+}
+
+/// A statement of a program.
+///
+/// Statements are separated by `;` or a newline outside of braces.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+pub enum Statement<'a> {
+    /// A function definition, `name(params...) = body`.
+    Function(Definition<'a>),
+
+    /// An assignment to a variable, `name = value` or `name := value`.
+    Assign(Assignment<'a>),
+
+    /// An expression which is evaluated.
+    Expr(Vec<Node<'a>>),
+}
+
+impl Statement<'_> {
+    /// Parse a program into statements.
+    pub fn parse(str: &str) -> Result<Vec<Statement<'_>>, Error> {
+        let tokens = equation_lexer::Tokenizer::from(str);
+        Statement::from_tokens(str, tokens)
+    }
+
+    /// Parse tokens into statements.
+    ///
+    /// The tokens must have been produced from `str`.
+    pub fn from_tokens(
+        str: &str,
+        iter: impl Iterator<Item = Token>,
+    ) -> Result<Vec<Statement<'_>>, Error> {
+        Parser::new(str, iter, &EvalLimits::UNLIMITED)?.program()
+    }
+}
+
+/// A user-defined function.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+pub struct Definition<'a> {
+    pub name: Cow<'a, str>,
+
+    /// The names of the parameters, in order.
+    pub params: Vec<Cow<'a, str>>,
+
+    /// The nodes of the function body.
+    pub body: Vec<Node<'a>>,
+
+    /// The span of the name and parameters.
+    pub(crate) span: Span,
+}
+
+impl Definition<'_> {
+    /// Returns a definition which does not borrow from the source string.
+    pub fn into_owned(self) -> Definition<'static> {
+        Definition {
+            name: Cow::Owned(self.name.into_owned()),
+            params: self
+                .params
+                .into_iter()
+                .map(|param| Cow::Owned(param.into_owned()))
+                .collect(),
+            body: self.body.into_iter().map(Node::into_owned).collect(),
+            span: self.span,
+        }
+    }
+}
+
+impl Spanned for Definition<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// An assignment to a variable.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+pub struct Assignment<'a> {
+    pub name: Cow<'a, str>,
+
+    /// The nodes of the assigned expression.
+    pub value: Vec<Node<'a>>,
+
+    /// The span of the name.
+    pub(crate) span: Span,
+}
+
+impl Spanned for Assignment<'_> {
+    fn span(&self) -> Span {
+        self.span
     }
 }
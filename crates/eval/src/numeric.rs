@@ -0,0 +1,163 @@
+//! Numeric integration and root-finding.
+//!
+//! Both work on an expression with a bound variable, such as `integrate(x^2, x, 0, 1)`, which the parser turns into
+//! a lambda. The expression is evaluated with [`f64`] precision and the result is rounded to [`DIGITS`] decimal
+//! places.
+
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+
+use crate::{
+    error, lambda::mismatch, lambda::Closure, limits::Context, node::Span, Error, ErrorKind,
+    Evaluated, Type,
+};
+
+/// The number of decimal places of a result.
+const DIGITS: u32 = 10;
+
+/// The absolute error which integration and root-finding aim for.
+const TOLERANCE: f64 = 1e-12;
+
+/// The maximum depth of interval subdivision when integrating.
+const MAX_DEPTH: u32 = 40;
+
+/// The maximum number of steps when finding a root.
+const MAX_STEPS: usize = 100;
+
+/// An expression evaluated as a function of its bound variable.
+///
+/// Every evaluation counts towards [`Resolver::max_iterations`](crate::resolve::Resolver::max_iterations).
+struct Function<'a> {
+    ctx: &'a Context<'a>,
+    closure: &'a Closure,
+    span: Span,
+}
+
+impl Function<'_> {
+    fn at(&mut self, x: f64) -> Result<f64, Error> {
+        self.ctx.iterate(1, self.span)?;
+
+        let x = Decimal::from_f64(x).ok_or_else(|| error(ErrorKind::Overflow, self.span))?;
+
+        match self
+            .closure
+            .call_at(self.ctx, &[Evaluated::Decimal(x)], self.span)?
+        {
+            Evaluated::Decimal(y) => y
+                .to_f64()
+                .ok_or_else(|| error(ErrorKind::Overflow, self.closure.span)),
+            value => Err(mismatch(Type::Decimal, &value, self.closure.span)),
+        }
+    }
+}
+
+fn number(value: &Evaluated, span: Span) -> Result<f64, Error> {
+    match value {
+        Evaluated::Decimal(decimal) => decimal
+            .to_f64()
+            .ok_or_else(|| error(ErrorKind::Overflow, span)),
+        value => Err(mismatch(Type::Decimal, value, span)),
+    }
+}
+
+fn result(value: f64, span: Span) -> Result<Evaluated, Error> {
+    if !value.is_finite() {
+        return Err(error(ErrorKind::Undefined, span));
+    }
+
+    Decimal::from_f64(value)
+        .map(|decimal| Evaluated::Decimal(decimal.round_dp(DIGITS).normalize()))
+        .ok_or_else(|| error(ErrorKind::Overflow, span))
+}
+
+/// `integrate(expr, x, from, to)`: the definite integral of the expression over `x` from `from` to `to`.
+///
+/// This uses adaptive Simpson's rule, subdividing intervals until the estimated error is within tolerance.
+pub(crate) fn integrate(
+    ctx: &Context<'_>,
+    closure: &Closure,
+    from: &Evaluated,
+    to: &Evaluated,
+    span: Span,
+) -> Result<Evaluated, Error> {
+    let (a, b) = (number(from, span)?, number(to, span)?);
+    let mut f = Function { ctx, closure, span };
+
+    let (fa, fm, fb) = (f.at(a)?, f.at((a + b) / 2.0)?, f.at(b)?);
+    let whole = simpson(a, b, fa, fm, fb);
+    let integral = adaptive(&mut f, [a, b], [fa, fm, fb], whole, TOLERANCE, MAX_DEPTH)?;
+
+    result(integral, span)
+}
+
+/// Simpson's rule over `[a, b]`, given the value at both ends and the midpoint.
+fn simpson(a: f64, b: f64, fa: f64, fm: f64, fb: f64) -> f64 {
+    (b - a) / 6.0 * (fa + 4.0 * fm + fb)
+}
+
+fn adaptive(
+    f: &mut Function,
+    [a, b]: [f64; 2],
+    [fa, fm, fb]: [f64; 3],
+    whole: f64,
+    tolerance: f64,
+    depth: u32,
+) -> Result<f64, Error> {
+    let m = (a + b) / 2.0;
+    let (flm, frm) = (f.at((a + m) / 2.0)?, f.at((m + b) / 2.0)?);
+    let left = simpson(a, m, fa, flm, fm);
+    let right = simpson(m, b, fm, frm, fb);
+    let delta = left + right - whole;
+
+    if depth == 0 || delta.abs() <= 15.0 * tolerance {
+        return Ok(left + right + delta / 15.0);
+    }
+
+    Ok(
+        adaptive(f, [a, m], [fa, flm, fm], left, tolerance / 2.0, depth - 1)?
+            + adaptive(f, [m, b], [fm, frm, fb], right, tolerance / 2.0, depth - 1)?,
+    )
+}
+
+/// `root(expr, x, guess)`: a value of `x` near `guess` where the expression is zero.
+///
+/// This uses the secant method, starting from `guess` and a point slightly above it. Fails with
+/// [`ErrorKind::NoConvergence`] if no root is found.
+pub(crate) fn root(
+    ctx: &Context<'_>,
+    closure: &Closure,
+    guess: &Evaluated,
+    span: Span,
+) -> Result<Evaluated, Error> {
+    let mut f = Function { ctx, closure, span };
+
+    let mut x0 = number(guess, span)?;
+    let mut x1 = x0 + (x0.abs() * 1e-4).max(1e-4);
+    let (mut y0, mut y1) = (f.at(x0)?, f.at(x1)?);
+
+    for _ in 0..MAX_STEPS {
+        if y1.abs() <= TOLERANCE {
+            return result(x1, span);
+        }
+
+        let slope = (y1 - y0) / (x1 - x0);
+
+        if slope == 0.0 || !slope.is_finite() {
+            break;
+        }
+
+        let x2 = x1 - y1 / slope;
+
+        if (x2 - x1).abs() <= TOLERANCE * x2.abs().max(1.0) {
+            return result(x2, span);
+        }
+
+        (x0, y0) = (x1, y1);
+        x1 = x2;
+        y1 = f.at(x1)?;
+    }
+
+    Err(error(ErrorKind::NoConvergence, span))
+}
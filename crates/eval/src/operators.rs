@@ -0,0 +1,459 @@
+//! The table of operators used by the parser.
+//!
+//! Every prefix, infix and postfix operator is looked up in an [`OperatorTable`], which gives the node the operator
+//! produces and how tightly it binds. [`OperatorTable::STANDARD`] is used by [`eval`](crate::eval) and
+//! [`Node::parse`](crate::node::Node::parse). A different table is used with
+//! [`eval_with_operators`](crate::eval_with_operators) or
+//! [`Node::parse_with_operators`](crate::node::Node::parse_with_operators).
+//!
+//! Domains disagree on some expressions. With the standard table `-2^2` is `-(2^2)` and `2^3^2` is `2^(3^2)`, a
+//! spreadsheet evaluates them as `(-2)^2` and `(2^3)^2` instead:
+//!
+//! ```
+//! use equation_eval::{
+//!     eval_with_operators,
+//!     operators::{precedence, Arity, Associativity, OperatorTable, Symbol},
+//!     resolve::Resolver,
+//!     ErrorKind, Evaluated,
+//! };
+//! use equation_lexer::Kind;
+//! use rust_decimal::Decimal;
+//!
+//! struct Numbers;
+//!
+//! impl Resolver for Numbers {
+//!     fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+//!         None
+//!     }
+//!
+//!     fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+//!         Err(ErrorKind::UnknownFunction)
+//!     }
+//! }
+//!
+//! let mut table = OperatorTable::STANDARD;
+//!
+//! let negate = table.get_mut(&Symbol::tokens(&[Kind![-]]), Arity::Prefix).unwrap();
+//! negate.precedence = precedence::POSTFIX;
+//!
+//! let power = table.get_mut(&Symbol::tokens(&[Kind![^]]), Arity::Infix).unwrap();
+//! power.associativity = Associativity::Left;
+//!
+//! assert_eq!(eval_with_operators(&Numbers, "-2^2", &table), Ok(Evaluated::Decimal(Decimal::from(4))));
+//! assert_eq!(eval_with_operators(&Numbers, "2^3^2", &table), Ok(Evaluated::Decimal(Decimal::from(64))));
+//! ```
+//!
+//! Custom infix operators are evaluated by
+//! [`Resolver::resolve_operator`](crate::resolve::Resolver::resolve_operator).
+//!
+//! Indexing with `[`, superscripts such as `x²` and `√` are not in the table. They bind like the standard postfix and
+//! prefix operators.
+
+use alloc::borrow::Cow;
+use equation_lexer::{Kind, TokenKind};
+
+use crate::node::{EquationKind, LogicalKind, OperatorKind};
+
+/// The precedence of the operators in [`OperatorTable::STANDARD`].
+///
+/// An operator with a higher precedence binds tighter.
+pub mod precedence {
+    /// `||`
+    pub const OR: u8 = 1;
+
+    /// `&&`
+    pub const AND: u8 = 2;
+
+    /// `=`, `!=`, `<`, `>`, `<=` and `>=`
+    pub const COMPARISON: u8 = 3;
+
+    /// `+` and `-`
+    pub const SUM: u8 = 4;
+
+    /// `*`, `/`, `//` and `%`
+    pub const PRODUCT: u8 = 5;
+
+    /// Prefix `-` and `!`
+    pub const PREFIX: u8 = 6;
+
+    /// `^`
+    pub const POWER: u8 = 7;
+
+    /// Postfix `!`
+    pub const POSTFIX: u8 = 8;
+}
+
+/// How an operator is written.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub enum Symbol {
+    /// Tokens with nothing between them, such as the two `&` tokens of `&&`.
+    ///
+    /// Tokens are matched by their kind, so `^` also matches `**` lexed with
+    /// [`LexerConfig::double_star_power`](equation_lexer::LexerConfig::double_star_power).
+    Tokens(Cow<'static, [TokenKind]>),
+
+    /// A name used as an operator, such as `mod`.
+    ///
+    /// A name is only an operator where an operator is expected, so `mod(7, 2)` at the start of an expression is
+    /// still a function call.
+    Word(Cow<'static, str>),
+}
+
+impl Symbol {
+    /// A symbol written as the tokens.
+    pub const fn tokens(tokens: &'static [TokenKind]) -> Symbol {
+        Symbol::Tokens(Cow::Borrowed(tokens))
+    }
+
+    /// A symbol written as the name.
+    pub fn word(word: impl Into<Cow<'static, str>>) -> Symbol {
+        Symbol::Word(word.into())
+    }
+}
+
+/// Where an operator is written relative to its operands.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// Before a single operand, such as `-x`.
+    Prefix,
+
+    /// Between two operands, such as `x + y`.
+    Infix,
+
+    /// After a single operand, such as `x!`.
+    Postfix,
+}
+
+/// How a sequence of infix operators with the same precedence is grouped.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+/// The node an operator produces.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Operator(OperatorKind),
+
+    Equation(EquationKind),
+
+    /// A logical operator. `&&` and `||` short-circuit, skipping the right operand.
+    Logical(LogicalKind),
+
+    /// A [`Node::Custom`](crate::node::Node::Custom) named by the text of the operator.
+    ///
+    /// Only infix operators may be custom, other custom operators are never matched.
+    Custom,
+}
+
+/// An operator in an [`OperatorTable`].
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct OperatorEntry {
+    pub symbol: Symbol,
+    pub arity: Arity,
+
+    /// How tightly the operator binds, see [`precedence`].
+    pub precedence: u8,
+
+    /// How the operator is grouped with infix operators of the same precedence.
+    ///
+    /// This is ignored for prefix and postfix operators.
+    pub associativity: Associativity,
+
+    /// The node the operator produces.
+    ///
+    /// Prefix and postfix operators take one operand and infix operators take two, the node must take the same
+    /// number of operands.
+    pub node: NodeKind,
+}
+
+impl OperatorEntry {
+    const fn standard(
+        tokens: &'static [TokenKind],
+        arity: Arity,
+        precedence: u8,
+        associativity: Associativity,
+        node: NodeKind,
+    ) -> OperatorEntry {
+        OperatorEntry {
+            symbol: Symbol::tokens(tokens),
+            arity,
+            precedence,
+            associativity,
+            node,
+        }
+    }
+
+    /// The left and right binding powers of the operator.
+    ///
+    /// Operators stop an operand with a higher binding power than their left binding power, and parse their
+    /// operand on the right with their right binding power.
+    pub(crate) fn binding_power(&self) -> (u16, u16) {
+        let power = u16::from(self.precedence) * 2;
+
+        match (self.arity, self.associativity) {
+            (Arity::Infix, Associativity::Left) => (power, power + 1),
+            (Arity::Infix, Associativity::Right) => (power + 1, power),
+            _ => (power, power),
+        }
+    }
+}
+
+/// The binding power of indexing and superscripts, which bind like the standard postfix operators.
+pub(crate) const POSTFIX_BP: u16 = precedence::POSTFIX as u16 * 2;
+
+/// The binding power of `√`, which binds like the standard prefix operators.
+pub(crate) const PREFIX_BP: u16 = precedence::PREFIX as u16 * 2;
+
+const STANDARD: &[OperatorEntry] = {
+    use Arity::{Infix, Postfix, Prefix};
+    use Associativity::{Left, Right};
+    use NodeKind::{Equation, Logical, Operator};
+
+    const fn infix(tokens: &'static [TokenKind], precedence: u8, node: NodeKind) -> OperatorEntry {
+        OperatorEntry::standard(tokens, Infix, precedence, Left, node)
+    }
+
+    &[
+        infix(
+            &[Kind![|], Kind![|]],
+            precedence::OR,
+            Logical(LogicalKind::Or),
+        ),
+        infix(
+            &[Kind![&], Kind![&]],
+            precedence::AND,
+            Logical(LogicalKind::And),
+        ),
+        infix(
+            &[Kind![=]],
+            precedence::COMPARISON,
+            Equation(EquationKind::Eq),
+        ),
+        infix(
+            &[Kind![!], Kind![=]],
+            precedence::COMPARISON,
+            Equation(EquationKind::Neq),
+        ),
+        infix(
+            &[TokenKind::NotEqual],
+            precedence::COMPARISON,
+            Equation(EquationKind::Neq),
+        ),
+        infix(
+            &[Kind![>]],
+            precedence::COMPARISON,
+            Equation(EquationKind::Gt),
+        ),
+        infix(
+            &[Kind![<]],
+            precedence::COMPARISON,
+            Equation(EquationKind::Lt),
+        ),
+        infix(
+            &[Kind![>], Kind![=]],
+            precedence::COMPARISON,
+            Equation(EquationKind::Ge),
+        ),
+        infix(
+            &[TokenKind::GreaterEqual],
+            precedence::COMPARISON,
+            Equation(EquationKind::Ge),
+        ),
+        infix(
+            &[Kind![<], Kind![=]],
+            precedence::COMPARISON,
+            Equation(EquationKind::Le),
+        ),
+        infix(
+            &[TokenKind::LessEqual],
+            precedence::COMPARISON,
+            Equation(EquationKind::Le),
+        ),
+        infix(&[Kind![+]], precedence::SUM, Operator(OperatorKind::Add)),
+        infix(&[Kind![-]], precedence::SUM, Operator(OperatorKind::Sub)),
+        infix(
+            &[Kind![*]],
+            precedence::PRODUCT,
+            Operator(OperatorKind::Mul),
+        ),
+        infix(
+            &[Kind![/]],
+            precedence::PRODUCT,
+            Operator(OperatorKind::Div),
+        ),
+        infix(
+            &[TokenKind::DoubleSlash],
+            precedence::PRODUCT,
+            Operator(OperatorKind::IntDiv),
+        ),
+        infix(
+            &[Kind![%]],
+            precedence::PRODUCT,
+            Operator(OperatorKind::Mod),
+        ),
+        OperatorEntry::standard(
+            &[Kind![^]],
+            Infix,
+            precedence::POWER,
+            Right,
+            Operator(OperatorKind::Pow),
+        ),
+        OperatorEntry::standard(
+            &[Kind![-]],
+            Prefix,
+            precedence::PREFIX,
+            Left,
+            Operator(OperatorKind::Neg),
+        ),
+        OperatorEntry::standard(
+            &[Kind![!]],
+            Prefix,
+            precedence::PREFIX,
+            Left,
+            Logical(LogicalKind::Not),
+        ),
+        OperatorEntry::standard(
+            &[Kind![!]],
+            Postfix,
+            precedence::POSTFIX,
+            Left,
+            Operator(OperatorKind::Factorial),
+        ),
+    ]
+};
+
+/// The operators of expressions.
+///
+/// Where several operators match, the operator with the most tokens is used, so `!=` is not `!` followed by `=`.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone)]
+pub struct OperatorTable {
+    entries: Cow<'static, [OperatorEntry]>,
+}
+
+impl OperatorTable {
+    /// The standard operators, see [`precedence`].
+    ///
+    /// `^` is right associative and binds tighter than prefix `-`, other infix operators are left associative.
+    pub const STANDARD: OperatorTable = OperatorTable {
+        entries: Cow::Borrowed(STANDARD),
+    };
+
+    /// The operators in the table.
+    pub fn entries(&self) -> &[OperatorEntry] {
+        &self.entries
+    }
+
+    /// The operator written as the symbol with the arity.
+    pub fn get(&self, symbol: &Symbol, arity: Arity) -> Option<&OperatorEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.symbol == *symbol && entry.arity == arity)
+    }
+
+    /// The operator written as the symbol with the arity, to change its precedence, associativity or node.
+    pub fn get_mut(&mut self, symbol: &Symbol, arity: Arity) -> Option<&mut OperatorEntry> {
+        self.entries
+            .to_mut()
+            .iter_mut()
+            .find(|entry| entry.symbol == *symbol && entry.arity == arity)
+    }
+
+    /// Add an operator, returning the operator it replaces with the same symbol and arity.
+    pub fn insert(&mut self, entry: OperatorEntry) -> Option<OperatorEntry> {
+        match self.get_mut(&entry.symbol, entry.arity) {
+            Some(existing) => Some(core::mem::replace(existing, entry)),
+            None => {
+                self.entries.to_mut().push(entry);
+                None
+            }
+        }
+    }
+
+    /// Remove the operator written as the symbol with the arity.
+    pub fn remove(&mut self, symbol: &Symbol, arity: Arity) -> Option<OperatorEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.symbol == *symbol && entry.arity == arity)?;
+
+        Some(self.entries.to_mut().remove(index))
+    }
+
+    /// Add a custom infix operator written as a word, such as `x xor y`.
+    ///
+    /// The operator is evaluated by [`Resolver::resolve_operator`](crate::resolve::Resolver::resolve_operator)
+    /// with the word as its name.
+    ///
+    /// ```
+    /// use equation_eval::{
+    ///     eval_with_operators,
+    ///     operators::{precedence, Associativity, OperatorTable},
+    ///     resolve::Resolver,
+    ///     ErrorKind, Evaluated,
+    /// };
+    /// use rust_decimal::Decimal;
+    ///
+    /// struct Bits;
+    ///
+    /// impl Resolver for Bits {
+    ///     fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+    ///         None
+    ///     }
+    ///
+    ///     fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+    ///         Err(ErrorKind::UnknownFunction)
+    ///     }
+    ///
+    ///     fn resolve_operator(
+    ///         &self,
+    ///         name: &str,
+    ///         lhs: &Evaluated,
+    ///         rhs: &Evaluated,
+    ///     ) -> Result<Evaluated, ErrorKind> {
+    ///         match (name, lhs, rhs) {
+    ///             ("xor", Evaluated::Bool(lhs), Evaluated::Bool(rhs)) => Ok(Evaluated::Bool(lhs != rhs)),
+    ///             _ => Err(ErrorKind::UnknownOperator),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut table = OperatorTable::STANDARD;
+    /// table.custom("xor", precedence::OR, Associativity::Left);
+    ///
+    /// assert_eq!(
+    ///     eval_with_operators(&Bits, "1 < 2 xor true", &table),
+    ///     Ok(Evaluated::Bool(false))
+    /// );
+    /// ```
+    pub fn custom(
+        &mut self,
+        word: impl Into<Cow<'static, str>>,
+        precedence: u8,
+        associativity: Associativity,
+    ) -> Option<OperatorEntry> {
+        self.insert(OperatorEntry {
+            symbol: Symbol::word(word),
+            arity: Arity::Infix,
+            precedence,
+            associativity,
+            node: NodeKind::Custom,
+        })
+    }
+}
+
+impl Default for OperatorTable {
+    fn default() -> Self {
+        OperatorTable::STANDARD
+    }
+}
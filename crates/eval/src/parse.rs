@@ -0,0 +1,1021 @@
+//! Parsing of tokens into postfix ordered nodes.
+
+use alloc::{borrow::Cow, vec::Vec};
+use core::str::FromStr;
+use equation_lexer::{superscript_digit, Brace, BraceKind, Kind, NumberKind, Token, TokenKind};
+use rust_decimal::Decimal;
+
+use crate::{
+    limits::EvalLimits,
+    node::{
+        Assignment, CustomOperator, Definition, Equation, Function, Index, Jump, JumpKind, Lambda,
+        List, Logical, LogicalKind, Node, Operator, OperatorKind, Span, Spanned, Statement, Unary,
+        UnaryKind,
+    },
+    operators::{Arity, NodeKind, OperatorEntry, OperatorTable, Symbol, POSTFIX_BP, PREFIX_BP},
+    string, Error, ErrorKind,
+};
+
+/// The standard operators, borrowed by parsers which are not given a table.
+static STANDARD: OperatorTable = OperatorTable::STANDARD;
+
+const ROUND_OPEN: TokenKind = Brace![Round, Open];
+const ROUND_CLOSE: TokenKind = Brace![Round, Close];
+const SQUARE_OPEN: TokenKind = Brace![Square, Open];
+
+/// Pair tokens produced from `str` with their spans.
+///
+/// Token lengths are in characters, the spans are byte offsets into `str`. A token with a length of zero, which the
+/// tokenizer never produces, has an empty span.
+pub(crate) fn spans<'s>(
+    str: &'s str,
+    iter: impl Iterator<Item = Token> + 's,
+) -> impl Iterator<Item = (Token, Span)> + 's {
+    let mut chars = str.char_indices();
+    let mut start = 0;
+
+    iter.map(move |token| {
+        let end = match token.len.checked_sub(1) {
+            Some(last) => chars
+                .nth(last)
+                .map_or(str.len(), |(offset, c)| offset + c.len_utf8()),
+            None => start,
+        };
+        let span = Span { start, end };
+
+        start = end;
+        (token, span)
+    })
+}
+
+pub(crate) struct Parser<'a, 'o> {
+    str: &'a str,
+    tokens: Vec<(TokenKind, Span)>,
+    operators: &'o OperatorTable,
+
+    /// Whitespace containing a newline outside of any braces, and the index of the token after it.
+    newlines: Vec<(usize, Span)>,
+    pos: usize,
+    nodes: Vec<Node<'a>>,
+
+    /// Whether to record errors and continue instead of stopping at the first error.
+    recover: bool,
+    errors: Vec<Error>,
+}
+
+impl<'a, 'o> Parser<'a, 'o> {
+    /// Create a parser for tokens which were produced from `str`, using the standard operators.
+    ///
+    /// Fails if the tokens exceed the token count or brace nesting limits.
+    pub(crate) fn new(
+        str: &'a str,
+        iter: impl Iterator<Item = Token>,
+        limits: &EvalLimits,
+    ) -> Result<Self, Error> {
+        let mut tokens = Vec::new();
+        let mut newlines = Vec::new();
+        let mut depth = 0usize;
+
+        for (token, span) in spans(str, iter) {
+            if token.len == 0 {
+                return Err(Error {
+                    kind: ErrorKind::InvalidToken,
+                    span,
+                    suggestions: Vec::new(),
+                });
+            }
+
+            match token.kind {
+                TokenKind::Ws if depth == 0 && str[span.start..span.end].contains('\n') => {
+                    newlines.push((tokens.len(), span));
+                }
+
+                TokenKind::Brace { open: true, .. } => {
+                    depth += 1;
+
+                    if depth > limits.max_nesting {
+                        return Err(Error {
+                            kind: ErrorKind::NestingTooDeep,
+                            span,
+                            suggestions: Vec::new(),
+                        });
+                    }
+                }
+
+                TokenKind::Brace { open: false, .. } => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+
+            if !matches!(token.kind, TokenKind::Ws | TokenKind::Comment) {
+                if tokens.len() == limits.max_tokens {
+                    return Err(Error {
+                        kind: ErrorKind::TooManyTokens,
+                        span,
+                        suggestions: Vec::new(),
+                    });
+                }
+
+                tokens.push((token.kind, span));
+            }
+        }
+
+        Ok(Self {
+            str,
+            tokens,
+            operators: &STANDARD,
+            newlines,
+            pos: 0,
+            nodes: Vec::new(),
+            recover: false,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Use the operators of the table instead of the standard operators.
+    pub(crate) fn with_operators(mut self, operators: &'o OperatorTable) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    pub(crate) fn parse(mut self) -> Result<Vec<Node<'a>>, Error> {
+        self.root()?;
+        Ok(self.nodes)
+    }
+
+    /// Parse an expression, recovering from errors.
+    ///
+    /// Returns the nodes, including a [`Node::Error`] in place of each part which could not be parsed, and every
+    /// error.
+    pub(crate) fn recovering(mut self) -> (Vec<Node<'a>>, Vec<Error>) {
+        self.recover = true;
+
+        if let Err(error) = self.root() {
+            self.errors.push(error);
+        }
+
+        (self.nodes, self.errors)
+    }
+
+    /// Parse a whole expression.
+    ///
+    /// When recovering, tokens after the end of the expression are skipped and the expression continues after them,
+    /// so `1 ) + 2` still adds `1` and `2`.
+    fn root(&mut self) -> Result<(), Error> {
+        let mut span = self.expr(0)?;
+
+        while let Some((kind, unexpected_span)) = self.peek() {
+            self.error(unexpected(kind, unexpected_span))?;
+            self.pos += 1;
+            span = self.operators(span, 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Report an error.
+    ///
+    /// Unless recovering, this returns the error to stop parsing. The same error is only recorded once, since
+    /// several levels of nesting may all reach it.
+    fn error(&mut self, error: Error) -> Result<(), Error> {
+        if !self.recover {
+            return Err(error);
+        }
+
+        if self.errors.last() != Some(&error) {
+            self.errors.push(error);
+        }
+
+        Ok(())
+    }
+
+    /// Report an error in place of an operand, returning the span of the error.
+    fn error_node(&mut self, error: Error) -> Result<Span, Error> {
+        let span = error.span;
+        self.error(error.clone())?;
+        self.nodes.push(Node::Error(error));
+        Ok(span)
+    }
+
+    /// Skip tokens until a `,`, a closing brace or a `;` which is not inside braces opened by the skipped tokens.
+    fn synchronise(&mut self) {
+        let mut depth = 0usize;
+
+        while let Some((kind, _)) = self.peek() {
+            match kind {
+                Kind![,] | Kind![;] | TokenKind::Brace { open: false, .. } if depth == 0 => return,
+                TokenKind::Brace { open: true, .. } => depth += 1,
+                TokenKind::Brace { open: false, .. } => depth -= 1,
+                _ => (),
+            }
+
+            self.pos += 1;
+        }
+    }
+
+    /// Parse statements separated by `;` or a newline.
+    ///
+    /// A newline only separates statements outside of braces, inside braces it is whitespace. Empty statements are
+    /// ignored.
+    pub(crate) fn program(mut self) -> Result<Vec<Statement<'a>>, Error> {
+        let mut statements = Vec::new();
+
+        // Insert from the end so the indices of earlier newlines are not shifted.
+        for (index, span) in core::mem::take(&mut self.newlines).into_iter().rev() {
+            self.tokens.insert(index, (Kind![;], span));
+        }
+
+        loop {
+            match self.peek() {
+                Some((Kind![;], _)) => {
+                    self.pos += 1;
+                    continue;
+                }
+
+                None => break,
+                _ => (),
+            }
+
+            let statement = if let Some(params) = self.params() {
+                self.definition(params)?
+            } else if let Some(name) = self.assignment() {
+                self.expr(0)?;
+
+                Statement::Assign(Assignment {
+                    name: Cow::Borrowed(&self.str[name.start..name.end]),
+                    value: core::mem::take(&mut self.nodes),
+                    span: name,
+                })
+            } else {
+                self.expr(0)?;
+                Statement::Expr(core::mem::take(&mut self.nodes))
+            };
+
+            statements.push(statement);
+
+            match self.next() {
+                Ok((Kind![;], _)) => (),
+                Ok((kind, span)) => {
+                    self.error(unexpected(kind, span))?;
+
+                    // Skip the rest of the statement.
+                    while !matches!(self.next(), Ok((Kind![;], _)) | Err(_)) {}
+                }
+                Err(_) => break,
+            }
+        }
+
+        Ok(statements)
+    }
+
+    /// Parse the start of an assignment, `name =` or `name :=`, returning the span of the name.
+    ///
+    /// `=` is only an assignment at the start of a statement, elsewhere it compares for equality.
+    fn assignment(&mut self) -> Option<Span> {
+        let (TokenKind::Chars, name) = self.peek()? else {
+            return None;
+        };
+
+        let len = match (
+            self.tokens.get(self.pos + 1)?.0,
+            self.tokens.get(self.pos + 2),
+        ) {
+            (Kind![=], _) => 2,
+            (Kind![:], Some(&(Kind![=], assign)))
+                if self.tokens[self.pos + 1].1.end == assign.start =>
+            {
+                3
+            }
+            _ => return None,
+        };
+
+        self.pos += len;
+        Some(name)
+    }
+
+    /// Look ahead for the start of a function definition, `name(params...) =`.
+    ///
+    /// Returns the span of the name, the spans of the parameters and the index of the token after the `=`.
+    fn params(&self) -> Option<(Span, Vec<Span>, usize)> {
+        let &(TokenKind::Chars, name) = self.tokens.get(self.pos)? else {
+            return None;
+        };
+
+        let (params, end) = self.param_list(self.pos + 1)?;
+        (self.tokens.get(end)?.0 == Kind![=]).then_some((name, params, end + 1))
+    }
+
+    /// Look ahead for a list of parameter names in round braces, starting at the token at `index`.
+    ///
+    /// Returns the spans of the parameters and the index of the token after the closing brace.
+    fn param_list(&self, index: usize) -> Option<(Vec<Span>, usize)> {
+        let mut tokens = self.tokens.get(index..)?.iter().copied();
+
+        if tokens.next()?.0 != ROUND_OPEN {
+            return None;
+        }
+
+        let mut params = Vec::new();
+
+        loop {
+            match tokens.next()? {
+                (ROUND_CLOSE, _) if params.is_empty() => break,
+                (TokenKind::Chars, span) => params.push(span),
+                _ => return None,
+            }
+
+            match tokens.next()?.0 {
+                Kind![,] => continue,
+                ROUND_CLOSE => break,
+                _ => return None,
+            }
+        }
+
+        // The braces, each parameter and the commas between them.
+        let len = 2 + (params.len() * 2).saturating_sub(1);
+        Some((params, index + len))
+    }
+
+    /// Returns whether the token at `index` starts a `->` arrow.
+    fn arrow(&self, index: usize) -> bool {
+        match (self.tokens.get(index), self.tokens.get(index + 1)) {
+            (Some(&(Kind![-], minus)), Some(&(Kind![>], greater))) => minus.end == greater.start,
+            _ => false,
+        }
+    }
+
+    /// The names of parameters, which must all be different.
+    fn names(&mut self, params: &[Span]) -> Result<Vec<Cow<'a, str>>, Error> {
+        let str = self.str;
+        let text = |span: &Span| &str[span.start..span.end];
+
+        for (i, param) in params.iter().enumerate() {
+            if params[..i].iter().any(|other| text(other) == text(param)) {
+                self.error(Error {
+                    kind: ErrorKind::Redefinition,
+                    span: *param,
+                    suggestions: Vec::new(),
+                })?;
+            }
+        }
+
+        Ok(params
+            .iter()
+            .map(|param| Cow::Borrowed(text(param)))
+            .collect())
+    }
+
+    /// Parse a function definition after [`Parser::params`] found its start.
+    fn definition(
+        &mut self,
+        (name, params, body): (Span, Vec<Span>, usize),
+    ) -> Result<Statement<'a>, Error> {
+        let params = self.names(&params)?;
+
+        // The closing brace is before the `=`.
+        let (_, close) = self.tokens[body - 2];
+        self.pos = body;
+        self.expr(0)?;
+
+        Ok(Statement::Function(Definition {
+            name: Cow::Borrowed(&self.str[name.start..name.end]),
+            params,
+            body: core::mem::take(&mut self.nodes),
+            span: name.to(close),
+        }))
+    }
+
+    /// Look ahead for the parameters of a lambda, `(params...) ->`, after its opening brace.
+    ///
+    /// Returns the spans of the parameters and the index of the arrow.
+    fn lambda_params(&self) -> Option<(Vec<Span>, usize)> {
+        let (params, arrow) = self.param_list(self.pos - 1)?;
+        self.arrow(arrow).then_some((params, arrow))
+    }
+
+    /// Parse the body of a lambda after its arrow, `params -> body`.
+    ///
+    /// The body extends as far as possible, so `x -> x + 1` is never `(x -> x) + 1`.
+    fn lambda(&mut self, params: &[Span], start: Span) -> Result<Span, Error> {
+        let params = self.names(params)?;
+
+        // The body is parsed into its own nodes, which are evaluated when the lambda is called.
+        let outer = core::mem::take(&mut self.nodes);
+        let end = self.expr(0)?;
+        let body = core::mem::replace(&mut self.nodes, outer);
+
+        let span = start.to(end);
+        self.nodes.push(Node::Lambda(Lambda { params, body, span }));
+
+        Ok(span)
+    }
+
+    fn peek(&self) -> Option<(TokenKind, Span)> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    /// Find the operator of one of the arities at the next token, returning the operator and how many tokens it
+    /// spans.
+    ///
+    /// The operator with the most tokens is used, or the first in the table if several have the same number.
+    fn operator(&self, arities: &[Arity]) -> Option<(&'o OperatorEntry, usize)> {
+        let operators = self.operators;
+        let mut found = None;
+
+        for entry in operators.entries() {
+            if !arities.contains(&entry.arity)
+                || (entry.node == NodeKind::Custom && entry.arity != Arity::Infix)
+            {
+                continue;
+            }
+
+            let Some(len) = self.symbol(&entry.symbol) else {
+                continue;
+            };
+
+            if found.is_none_or(|(_, longest)| len > longest) {
+                found = Some((entry, len));
+            }
+        }
+
+        found
+    }
+
+    /// The number of tokens of the symbol if it is at the next token.
+    fn symbol(&self, symbol: &Symbol) -> Option<usize> {
+        let tokens = self.tokens.get(self.pos..)?;
+
+        match symbol {
+            Symbol::Tokens(kinds) => {
+                let matched = tokens.get(..kinds.len())?;
+
+                // The tokens must be next to each other, `& &` is not `&&`.
+                let joined = matched
+                    .windows(2)
+                    .all(|pair| pair[0].1.end == pair[1].1.start);
+                let same = matched.iter().map(|(kind, _)| kind).eq(kinds.iter());
+
+                (!kinds.is_empty() && joined && same).then_some(kinds.len())
+            }
+
+            Symbol::Word(word) => {
+                let &(kind, span) = tokens.first()?;
+                (kind == TokenKind::Chars && self.str[span.start..span.end] == **word).then_some(1)
+            }
+        }
+    }
+
+    /// Push the node of an operator, where `operator` is the span of the operator itself and `span` also includes
+    /// the operands.
+    fn push_operator(&mut self, node: NodeKind, operator: Span, span: Span) {
+        self.nodes.push(match node {
+            NodeKind::Operator(kind) => Node::Operator(Operator { kind, span }),
+            NodeKind::Equation(kind) => Node::Equation(Equation { kind, span }),
+            NodeKind::Logical(kind) => Node::Logical(Logical { kind, span }),
+            NodeKind::Custom => Node::Custom(CustomOperator {
+                name: Cow::Borrowed(&self.str[operator.start..operator.end]),
+                span,
+            }),
+        });
+    }
+
+    fn next(&mut self) -> Result<(TokenKind, Span), Error> {
+        let token = self.peek().ok_or_else(|| self.end())?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// An error for when the tokens end too early.
+    fn end(&self) -> Error {
+        let end = self.str.len();
+
+        Error {
+            kind: ErrorKind::UnexpectedEnd,
+            span: Span { start: end, end },
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Parse an expression, returning the span of the expression.
+    fn expr(&mut self, min_bp: u16) -> Result<Span, Error> {
+        let lhs = self.prefix()?;
+        self.operators(lhs, min_bp)
+    }
+
+    /// Parse the postfix and infix operators after the operand `lhs`, returning the span of the expression.
+    fn operators(&mut self, mut lhs: Span, min_bp: u16) -> Result<Span, Error> {
+        while let Some((kind, span)) = self.peek() {
+            // Superscript digits raise the operand to a power, so `x²` is `x^2`.
+            if kind == TokenKind::Superscript {
+                if POSTFIX_BP < min_bp {
+                    break;
+                }
+
+                self.pos += 1;
+
+                match superscript(&self.str[span.start..span.end]) {
+                    Some(exponent) => self.nodes.push(Node::Unary(Unary {
+                        kind: UnaryKind::Int(exponent),
+                        span,
+                    })),
+                    None => {
+                        self.error_node(Error {
+                            kind: ErrorKind::InvalidNumber,
+                            span,
+                            suggestions: Vec::new(),
+                        })?;
+                    }
+                }
+
+                lhs = lhs.to(span);
+                self.nodes.push(Node::Operator(Operator {
+                    kind: OperatorKind::Pow,
+                    span: lhs,
+                }));
+                continue;
+            }
+
+            // Indexing, `[` directly after an operand.
+            if kind == SQUARE_OPEN {
+                if POSTFIX_BP < min_bp {
+                    break;
+                }
+
+                self.pos += 1;
+                self.expr(0)?;
+                lhs = lhs.to(self.close(BraceKind::Square)?);
+                self.nodes.push(Node::Index(Index { span: lhs }));
+                continue;
+            }
+
+            let Some((entry, len)) = self.operator(&[Arity::Postfix, Arity::Infix]) else {
+                break;
+            };
+
+            let (left_bp, right_bp) = entry.binding_power();
+
+            if left_bp < min_bp {
+                break;
+            }
+
+            let operator = span.to(self.tokens[self.pos + len - 1].1);
+            self.pos += len;
+
+            if entry.arity == Arity::Postfix {
+                lhs = lhs.to(operator);
+                self.push_operator(entry.node, operator, lhs);
+                continue;
+            }
+
+            // Logical operators short-circuit, skipping the right hand side and the operator itself.
+            let jump = match entry.node {
+                NodeKind::Logical(kind @ (LogicalKind::And | LogicalKind::Or)) => {
+                    let kind = match kind {
+                        LogicalKind::Or => JumpKind::IfTrue,
+                        _ => JumpKind::IfFalse,
+                    };
+
+                    self.nodes.push(Node::Jump(Jump {
+                        kind,
+                        skip: 0,
+                        span: operator,
+                    }));
+                    Some(self.nodes.len() - 1)
+                }
+
+                _ => None,
+            };
+
+            let rhs = self.expr(right_bp)?;
+            lhs = lhs.to(rhs);
+
+            self.push_operator(entry.node, operator, lhs);
+
+            if let Some(index) = jump {
+                self.patch(index);
+            }
+        }
+
+        Ok(lhs)
+    }
+
+    /// Set the skip of the jump at `index` to jump to the end of the nodes.
+    fn patch(&mut self, index: usize) {
+        let skip = self.nodes.len() - index - 1;
+
+        if let Some(Node::Jump(jump)) = self.nodes.get_mut(index) {
+            jump.skip = skip;
+        }
+    }
+
+    /// Parse a prefix operator or an operand, returning the span of the parsed expression.
+    ///
+    /// When recovering, a token which can not start an operand is replaced by a [`Node::Error`]. Operators, commas,
+    /// closing braces and `;` are left for the caller, so `2 + * 3` continues as `2 + (error * 3)`.
+    fn prefix(&mut self) -> Result<Span, Error> {
+        let (kind, span) = match self.peek() {
+            Some(token) => token,
+            None => return self.error_node(self.end()),
+        };
+
+        if let Some((entry, len)) = self.operator(&[Arity::Prefix]) {
+            let (_, right_bp) = entry.binding_power();
+            let operator = span.to(self.tokens[self.pos + len - 1].1);
+            self.pos += len;
+
+            let span = span.to(self.expr(right_bp)?);
+            self.push_operator(entry.node, operator, span);
+            return Ok(span);
+        }
+
+        if self.recover && !operand(kind) {
+            let skip = !matches!(
+                kind,
+                Kind![,] | Kind![;] | TokenKind::Brace { open: false, .. }
+            ) && self.operator(&[Arity::Infix]).is_none();
+
+            if skip {
+                self.pos += 1;
+            }
+
+            return self.error_node(unexpected(kind, span));
+        }
+
+        self.pos += 1;
+
+        match kind {
+            // `√x` is `sqrt(x)`.
+            TokenKind::Root => {
+                let span = span.to(self.expr(PREFIX_BP)?);
+                self.nodes.push(Node::Function(Function {
+                    name: Cow::Borrowed("sqrt"),
+                    args: 1,
+                    span,
+                }));
+                Ok(span)
+            }
+
+            TokenKind::Number(number) => {
+                let kind = match self.number(number, span) {
+                    Ok(kind) => kind,
+                    Err(error) => return self.error_node(error),
+                };
+
+                self.nodes.push(Node::Unary(Unary { kind, span }));
+                Ok(span)
+            }
+
+            TokenKind::Str { terminated: true } => {
+                // Remove the quotes, which are always a single byte.
+                let raw = &self.str[span.start + 1..span.end - 1];
+
+                if let Err((start, end)) = string::unescape(raw) {
+                    self.error(Error {
+                        kind: ErrorKind::InvalidEscape,
+                        span: Span {
+                            start: span.start + 1 + start,
+                            end: span.start + 1 + end,
+                        },
+                        suggestions: Vec::new(),
+                    })?;
+                }
+
+                self.nodes.push(Node::Unary(Unary {
+                    kind: UnaryKind::Str(Cow::Borrowed(raw)),
+                    span,
+                }));
+                Ok(span)
+            }
+
+            TokenKind::Str { terminated: false } => self.error_node(Error {
+                kind: ErrorKind::UnterminatedString,
+                span,
+                suggestions: Vec::new(),
+            }),
+
+            TokenKind::Chars => self.chars(span),
+
+            TokenKind::Brace {
+                kind: BraceKind::Square,
+                open: true,
+            } => {
+                let (starts, end) = self.elements(BraceKind::Square)?;
+                let span = span.to(end);
+
+                self.nodes.push(Node::List(List {
+                    len: starts.len(),
+                    span,
+                }));
+                Ok(span)
+            }
+
+            TokenKind::Brace { kind, open: true } => {
+                if let Some((params, arrow)) = self.lambda_params() {
+                    self.pos = arrow + 2;
+                    return self.lambda(&params, span);
+                }
+
+                self.expr(0)?;
+                let end = self.close(kind)?;
+                Ok(span.to(end))
+            }
+
+            kind => self.error_node(unexpected(kind, span)),
+        }
+    }
+
+    /// Expect a closing brace of the specified kind, returning the span of the brace.
+    ///
+    /// When recovering, other tokens before the brace are skipped. If the brace is missing, a different closing brace
+    /// or a `;` is left for the enclosing expression.
+    fn close(&mut self, brace: BraceKind) -> Result<Span, Error> {
+        loop {
+            match self.peek() {
+                Some((TokenKind::Brace { kind, open: false }, span)) if kind == brace => {
+                    self.pos += 1;
+                    return Ok(span);
+                }
+
+                Some((kind @ (TokenKind::Brace { open: false, .. } | Kind![;]), span)) => {
+                    self.error(unexpected(kind, span))?;
+                    return Ok(span);
+                }
+
+                Some((kind, span)) => {
+                    self.error(unexpected(kind, span))?;
+                    self.pos += 1;
+                    self.synchronise();
+                }
+
+                None => {
+                    let end = self.end();
+                    let span = end.span;
+                    self.error(end)?;
+                    return Ok(span);
+                }
+            }
+        }
+    }
+
+    fn number(&self, kind: NumberKind, span: Span) -> Result<UnaryKind<'a>, Error> {
+        let text = &self.str[span.start..span.end];
+        let invalid = || Error {
+            kind: ErrorKind::InvalidNumber,
+            span,
+            suggestions: Vec::new(),
+        };
+
+        let radix = match kind {
+            NumberKind::Decimal => {
+                let decimal = if text.contains(['e', 'E']) {
+                    Decimal::from_scientific(text)
+                } else {
+                    Decimal::from_str(text)
+                };
+
+                return decimal.map(UnaryKind::Decimal).map_err(|_| invalid());
+            }
+
+            NumberKind::Binary => 2,
+            NumberKind::Hexadecimal => 16,
+        };
+
+        text.get(2..)
+            .and_then(|digits| i64::from_str_radix(digits, radix).ok())
+            .map(UnaryKind::Int)
+            .ok_or_else(invalid)
+    }
+
+    /// Parse a sequence of characters: a constant, a boolean, a function call or a lambda with a single parameter.
+    fn chars(&mut self, span: Span) -> Result<Span, Error> {
+        let name = &self.str[span.start..span.end];
+
+        if self.arrow(self.pos) {
+            self.pos += 2;
+            return self.lambda(&[span], span);
+        }
+
+        if !matches!(self.peek(), Some((ROUND_OPEN, _))) {
+            let kind = match name {
+                "true" => UnaryKind::Bool(true),
+                "false" => UnaryKind::Bool(false),
+                // Symbols for constants are resolved by name.
+                "π" => UnaryKind::Constant(Cow::Borrowed("pi")),
+                "τ" => UnaryKind::Constant(Cow::Borrowed("tau")),
+                "∞" => UnaryKind::Constant(Cow::Borrowed("infinity")),
+                name => UnaryKind::Constant(Cow::Borrowed(name)),
+            };
+
+            self.nodes.push(Node::Unary(Unary { kind, span }));
+            return Ok(span);
+        }
+
+        self.pos += 1;
+        let (starts, end) = self.elements(BraceKind::Round)?;
+
+        let span = span.to(end);
+
+        if name == "if" {
+            return self.conditional(&starts, span);
+        }
+
+        let bound = match (name, starts.len()) {
+            ("sum" | "prod", 4) => self.bind(&starts, 0, 3),
+            ("integrate", 4) | ("root", 3) => self.bind(&starts, 1, 0),
+            _ => Ok(starts.len()),
+        };
+
+        let args = match bound {
+            Ok(args) => args,
+            // `sum(1, 2, 3, 4)` is the sum of four values.
+            Err(_) if name == "sum" => starts.len(),
+            Err(span) => {
+                return self.error_node(Error {
+                    kind: ErrorKind::InvalidArgument,
+                    span,
+                    suggestions: Vec::new(),
+                })
+            }
+        };
+
+        self.nodes.push(Node::Function(Function {
+            name: Cow::Borrowed(name),
+            args,
+            span,
+        }));
+
+        Ok(span)
+    }
+
+    /// Replace the argument `var`, a single name, and the argument `body` with a lambda binding the name in the
+    /// body, returning the number of arguments.
+    ///
+    /// This is used by functions such as `sum(k, from, to, body)` and `integrate(body, x, from, to)` which evaluate
+    /// the body with different values of the name. The lambda takes the place of the body. If the argument `var` is
+    /// not a single name the arguments are left as they are and the span of the argument is returned.
+    ///
+    /// This only depends on the syntax: a name is bound even if an outer scope has a value with the same name, which
+    /// the body then shadows.
+    fn bind(&mut self, starts: &[usize], var: usize, body: usize) -> Result<usize, Span> {
+        let end = |nodes: &[Node<'_>], i: usize| starts.get(i + 1).copied().unwrap_or(nodes.len());
+        let range = starts[var]..end(&self.nodes, var);
+
+        let name = match &self.nodes[range.clone()] {
+            [Node::Unary(Unary {
+                kind: UnaryKind::Constant(name),
+                ..
+            })] => name,
+
+            nodes => {
+                let span = nodes.iter().map(Spanned::span).reduce(Span::to);
+                return Err(span.unwrap_or(Span { start: 0, end: 0 }));
+            }
+        };
+
+        let params = alloc::vec![name.clone()];
+        let range = starts[body]..end(&self.nodes, body);
+        let len = range.len();
+        let body = self.nodes.drain(range.clone()).collect::<Vec<_>>();
+
+        let span = body
+            .iter()
+            .map(Spanned::span)
+            .reduce(Span::to)
+            .unwrap_or(Span { start: 0, end: 0 });
+
+        self.nodes
+            .insert(range.start, Node::Lambda(Lambda { params, body, span }));
+
+        // The body is now a single node, which shifts the name if it comes after the body.
+        let var = match starts[var] > range.start {
+            true => starts[var] + 1 - len,
+            false => starts[var],
+        };
+        self.nodes.remove(var);
+
+        Ok(starts.len() - 1)
+    }
+
+    /// Parse comma separated expressions until a closing brace of the specified kind.
+    ///
+    /// Returns the index of the first node of each expression and the span of the closing brace.
+    fn elements(&mut self, brace: BraceKind) -> Result<(Vec<usize>, Span), Error> {
+        let mut starts = Vec::new();
+
+        let end = loop {
+            if let Some((TokenKind::Brace { kind, open: false }, end)) = self.peek() {
+                // Allow no elements, but not a trailing comma.
+                if kind == brace && starts.is_empty() {
+                    self.pos += 1;
+                    break end;
+                }
+            }
+
+            starts.push(self.nodes.len());
+            self.expr(0)?;
+
+            // When recovering, skip anything unexpected after the element, such as the `2` of `f(1 2, 3)`.
+            loop {
+                match self.peek() {
+                    Some((Kind![,], _)) => break,
+                    Some((TokenKind::Brace { open: false, .. } | Kind![;], _)) | None => {
+                        return Ok((starts, self.close(brace)?));
+                    }
+
+                    Some((kind, span)) => {
+                        self.error(unexpected(kind, span))?;
+                        self.pos += 1;
+                        self.synchronise();
+                    }
+                }
+            }
+
+            self.pos += 1;
+        };
+
+        Ok((starts, end))
+    }
+
+    /// Insert the jumps of an `if(condition, then, else)` conditional.
+    ///
+    /// Only one of the branches is evaluated.
+    fn conditional(&mut self, starts: &[usize], span: Span) -> Result<Span, Error> {
+        let &[_, then, otherwise] = starts else {
+            return self.error_node(Error {
+                kind: ErrorKind::ArgumentCount {
+                    expected: 3,
+                    found: starts.len(),
+                },
+                span,
+                suggestions: Vec::new(),
+            });
+        };
+
+        // Insert the later jump first so the index of the earlier jump is not shifted.
+        self.nodes.insert(
+            otherwise,
+            Node::Jump(Jump {
+                kind: JumpKind::Always,
+                skip: self.nodes.len() - otherwise,
+                span,
+            }),
+        );
+        self.nodes.insert(
+            then,
+            Node::Jump(Jump {
+                kind: JumpKind::Unless,
+                // Skip the then branch and the jump over the else branch.
+                skip: otherwise - then + 1,
+                span,
+            }),
+        );
+
+        Ok(span)
+    }
+}
+
+/// Returns whether the token may start an operand, other than a prefix operator.
+fn operand(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Root
+            | TokenKind::Number(_)
+            | TokenKind::Str { .. }
+            | TokenKind::Chars
+            | TokenKind::Brace { open: true, .. }
+    )
+}
+
+/// The value of superscript digits, such as `²` or `⁻¹`.
+///
+/// Returns [`None`] if there are no digits or the value overflows.
+fn superscript(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('⁻') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+
+    let mut value = None;
+
+    for c in digits.chars() {
+        let digit = superscript_digit(c)?;
+        value = value
+            .unwrap_or(0i64)
+            .checked_mul(10)?
+            .checked_add(i64::from(digit));
+    }
+
+    value.map(|value| if negative { -value } else { value })
+}
+
+fn unexpected(kind: TokenKind, span: Span) -> Error {
+    let kind = match kind {
+        TokenKind::Invalid => ErrorKind::InvalidToken,
+        _ => ErrorKind::UnexpectedToken,
+    };
+
+    Error {
+        kind,
+        span,
+        suggestions: Vec::new(),
+    }
+}
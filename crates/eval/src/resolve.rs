@@ -1,23 +1,84 @@
 use alloc::vec::Vec;
-use rust_decimal::Decimal;
 
-use crate::{node::Node, Error, Evaluated};
+use crate::{ErrorKind, Evaluated};
 
-pub enum Constant {
-    Integer(i64),
-
-    Decimal(Decimal),
-}
+/// The default of [`Resolver::max_iterations`].
+pub const DEFAULT_MAX_ITERATIONS: usize = 100_000;
 
 /// A trait used to resolve unknown constants and functions.
 pub trait Resolver {
     /// Resolve a constant represented by a symbol
     ///
     /// If the symbol is not able to be resolved, then return [`None`].
-    fn resolve_const_symbol(&self, name: &str) -> Option<Constant>;
+    ///
+    /// With the `unicode` feature `π`, `τ` and `∞` are resolved as the constants `pi`, `tau` and `infinity`. A
+    /// [`Decimal`](rust_decimal::Decimal) can not be infinite, so `infinity` is only defined if the resolver provides
+    /// a value for it, such as [`Decimal::MAX`](rust_decimal::Decimal::MAX). Otherwise `∞` fails with
+    /// [`ErrorKind::UnknownConstant`].
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated>;
 
     /// Resolve a function
     ///
-    /// The return value of this function indicates how many unary operands need to be on the stack
-    fn resolve_function(&self, name: &str, stack: &mut Vec<Node>) -> Result<Evaluated, Error>;
+    /// The arguments are evaluated before the function is resolved. Built-in functions such as `concat` take
+    /// precedence and are never passed to the resolver.
+    ///
+    /// A lambda argument such as `x -> x^2` is passed as an [`Evaluated::Lambda`] without being evaluated. The
+    /// resolver may evaluate it any number of times with [`Closure::call`](crate::lambda::Closure::call), which
+    /// allows functions that take unevaluated expressions.
+    ///
+    /// An error returned by this function is reported at the span of the function call.
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind>;
+
+    /// Resolve a custom infix operator
+    ///
+    /// Custom operators are added to an [`OperatorTable`](crate::operators::OperatorTable), such as `xor` with
+    /// [`OperatorTable::custom`](crate::operators::OperatorTable::custom). Both operands are evaluated before the
+    /// operator is resolved.
+    ///
+    /// An error returned by this function is reported at the span of the operator and its operands. The default
+    /// implementation resolves no operators.
+    fn resolve_operator(
+        &self,
+        name: &str,
+        lhs: &Evaluated,
+        rhs: &Evaluated,
+    ) -> Result<Evaluated, ErrorKind> {
+        let _ = (name, lhs, rhs);
+        Err(ErrorKind::UnknownOperator)
+    }
+
+    /// Returns whether the resolver provides a function
+    ///
+    /// This is used to detect user-defined functions which conflict with the resolver, and by tools such as a
+    /// language server to describe calls. Resolvers which provide functions should override this, the default
+    /// implementation returns `false` so no functions are detected.
+    fn has_function(&self, name: &str) -> bool {
+        let _ = name;
+        false
+    }
+
+    /// The maximum number of times the body of `sum(k, from, to, body)` or `prod(k, from, to, body)` may be
+    /// evaluated
+    ///
+    /// This also limits how many times `integrate` and `root` evaluate their expression. Evaluations are counted
+    /// across the whole expression, so nested series share the limit. A range which would exceed the limit fails
+    /// with [`ErrorKind::IterationLimit`] before the body is evaluated.
+    fn max_iterations(&self) -> usize {
+        DEFAULT_MAX_ITERATIONS
+    }
+
+    /// The names of the constants the resolver provides
+    ///
+    /// This is used to suggest similar names for an unknown constant, see [`Error::suggestions`](crate::Error).
+    /// The default implementation returns no names.
+    fn const_symbols(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// The names of the functions the resolver provides
+    ///
+    /// This is used to suggest similar names for an unknown function. The default implementation returns no names.
+    fn functions(&self) -> Vec<&str> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,344 @@
+//! Evaluation sessions with variables and user-defined functions.
+//!
+//! A [`Session`] evaluates programs of statements separated by `;` or a newline. A statement is one of:
+//! - An expression
+//! - An assignment such as `rate = 0.07` or `rate := 0.07`
+//! - A function definition such as `f(x) = x^2 + 1`
+//!
+//! `=` is only an assignment at the start of a statement, `(rate = 0.07)` compares for equality instead.
+//! Variables and functions are kept by the session and may be used by later statements and by later calls to
+//! [`Session::eval`].
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::{borrow::Borrow, cell::RefCell};
+use equation_lexer::Token;
+
+use crate::{
+    builtin,
+    limits::{Context, EvalLimits, Usage},
+    node::{Definition, Node, Spanned, Statement},
+    operators::OperatorTable,
+    parse::Parser,
+    resolve::Resolver,
+    run, Error, ErrorKind, Evaluated,
+};
+
+/// Names which can never be defined because the parser treats them specially.
+const RESERVED: &[&str] = &["if", "true", "false"];
+
+/// An evaluation session which retains user-defined functions and variables.
+///
+/// Names which are not defined in the session are passed to the [`Resolver`].
+pub struct Session<'r> {
+    resolver: &'r dyn Resolver,
+    functions: BTreeMap<String, Definition<'static>>,
+    variables: BTreeMap<String, Evaluated>,
+    max_depth: usize,
+    max_iterations: Option<usize>,
+    limits: EvalLimits,
+    operators: OperatorTable,
+
+    /// The resources used by the current call to [`Session::eval`].
+    usage: Usage,
+
+    /// The suggestions of an error in the body of a user-defined function.
+    ///
+    /// A function is called through [`Resolver::resolve_function`], which only returns an [`ErrorKind`], so the
+    /// suggestions are kept here until the error reaches the call.
+    suggestions: RefCell<Vec<String>>,
+}
+
+impl<'r> Session<'r> {
+    /// The default maximum depth of nested user-defined function calls.
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+    pub fn new(resolver: &'r dyn Resolver) -> Self {
+        Self {
+            resolver,
+            functions: BTreeMap::new(),
+            variables: BTreeMap::new(),
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_iterations: None,
+            limits: EvalLimits::UNLIMITED,
+            operators: OperatorTable::STANDARD,
+            usage: Usage::default(),
+            suggestions: RefCell::default(),
+        }
+    }
+
+    /// Set the maximum depth of nested user-defined function calls.
+    ///
+    /// Calls deeper than this fail with [`ErrorKind::RecursionLimit`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Set the maximum number of iterations of `sum` and `prod` over a range.
+    ///
+    /// By default the limit of the resolver is used.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Set the resource limits of each call to [`Session::eval`].
+    ///
+    /// By default there are no limits.
+    pub fn with_limits(mut self, limits: EvalLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Parse programs with the operators of a table instead of the standard operators.
+    ///
+    /// Custom operators, including those used in the bodies of user-defined functions and lambdas, are evaluated by
+    /// [`Resolver::resolve_operator`] of the resolver of the session.
+    pub fn with_operators(mut self, operators: OperatorTable) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    /// Define a variable which may be used by later evaluations.
+    ///
+    /// Returns [`ErrorKind::Redefinition`] if the resolver already provides a constant with the name.
+    pub fn define_variable(&mut self, name: &str, value: Evaluated) -> Result<(), ErrorKind> {
+        if RESERVED.contains(&name) || self.resolver.resolve_const_symbol(name).is_some() {
+            return Err(ErrorKind::Redefinition);
+        }
+
+        self.variables.insert(name.into(), value);
+        Ok(())
+    }
+
+    /// Returns the variables defined in the session, ordered by name.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &Evaluated)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Returns the value of a variable defined in the session.
+    pub fn variable(&self, name: &str) -> Option<&Evaluated> {
+        self.variables.get(name)
+    }
+
+    /// Returns a function defined in the session.
+    pub fn function(&self, name: &str) -> Option<&Definition<'static>> {
+        self.functions.get(name)
+    }
+
+    /// Evaluate a program.
+    ///
+    /// Returns the value of the last statement, or [`None`] if the last statement is a function definition. The value
+    /// of an assignment is the assigned value.
+    ///
+    /// Statements are evaluated in order, if a statement fails the variables and functions defined by the statements
+    /// before it are kept.
+    pub fn eval(&mut self, str: &str) -> Result<Option<Evaluated>, Error> {
+        self.eval_tokens(str, equation_lexer::Tokenizer::from(str))
+    }
+
+    /// Evaluate the tokens of a program which were produced from `str`.
+    pub fn eval_tokens(
+        &mut self,
+        str: &str,
+        tokens: impl Iterator<Item = Token>,
+    ) -> Result<Option<Evaluated>, Error> {
+        self.limits.check_input(str)?;
+        self.usage = Usage::default();
+        self.suggestions.take();
+
+        let mut result = None;
+
+        let statements = Parser::new(str, tokens, &self.limits)?
+            .with_operators(&self.operators)
+            .program()?;
+
+        for statement in statements {
+            result = match statement {
+                Statement::Function(definition) => {
+                    self.define(definition)?;
+                    None
+                }
+
+                Statement::Assign(assignment) => {
+                    let value = self.run(&self.scope(&[], 0), assignment.value)?;
+
+                    self.define_variable(&assignment.name, value.clone())
+                        .map_err(|kind| Error {
+                            kind,
+                            span: assignment.span,
+                            suggestions: Vec::new(),
+                        })?;
+
+                    Some(value)
+                }
+
+                Statement::Expr(nodes) => Some(self.run(&self.scope(&[], 0), nodes)?),
+            };
+        }
+
+        Ok(result)
+    }
+
+    fn define(&mut self, definition: Definition<'_>) -> Result<(), Error> {
+        let span = definition.span();
+        let name = &*definition.name;
+
+        if RESERVED.contains(&name) || builtin::exists(name) || self.resolver.has_function(name) {
+            return Err(Error {
+                kind: ErrorKind::Redefinition,
+                span,
+                suggestions: Vec::new(),
+            });
+        }
+
+        if definition
+            .params
+            .iter()
+            .any(|param| RESERVED.contains(&&**param))
+        {
+            return Err(Error {
+                kind: ErrorKind::Redefinition,
+                span,
+                suggestions: Vec::new(),
+            });
+        }
+
+        self.functions.insert(name.into(), definition.into_owned());
+        Ok(())
+    }
+
+    /// Evaluate nodes within the limits of the session.
+    fn run<'a, N: Borrow<Node<'a>>>(
+        &self,
+        scope: &Scope<'_, '_>,
+        nodes: impl IntoIterator<Item = N>,
+    ) -> Result<Evaluated, Error> {
+        run(
+            &Context::new(scope, &self.limits, &self.usage),
+            nodes.into_iter(),
+        )
+        .map_err(|mut err| {
+            if err.suggestions.is_empty() {
+                err.suggestions = self.suggestions.take();
+            }
+
+            err
+        })
+    }
+
+    fn const_symbols(&self) -> Vec<&str> {
+        let variables = self.variables.keys().map(String::as_str);
+        variables.chain(self.resolver.const_symbols()).collect()
+    }
+
+    fn functions(&self) -> Vec<&str> {
+        let functions = self.functions.keys().map(String::as_str);
+        functions.chain(self.resolver.functions()).collect()
+    }
+
+    fn scope<'s>(&'s self, params: &'s [(&'s str, Evaluated)], depth: usize) -> Scope<'s, 'r> {
+        Scope {
+            session: self,
+            params,
+            depth,
+        }
+    }
+}
+
+/// The names visible while evaluating an expression.
+///
+/// Parameters are lexically scoped: the body of a function only sees its own parameters, never the parameters of
+/// the function which called it.
+struct Scope<'s, 'r> {
+    session: &'s Session<'r>,
+    params: &'s [(&'s str, Evaluated)],
+    depth: usize,
+}
+
+impl Scope<'_, '_> {
+    fn call(
+        &self,
+        definition: &Definition<'_>,
+        args: &[Evaluated],
+    ) -> Result<Evaluated, ErrorKind> {
+        if args.len() != definition.params.len() {
+            return Err(ErrorKind::ArgumentCount {
+                expected: definition.params.len(),
+                found: args.len(),
+            });
+        }
+
+        if self.depth >= self.session.max_depth {
+            return Err(ErrorKind::RecursionLimit);
+        }
+
+        let params = definition
+            .params
+            .iter()
+            .map(|param| &**param)
+            .zip(args.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let scope = self.session.scope(&params, self.depth + 1);
+
+        self.session
+            .run(&scope, definition.body.iter())
+            .map_err(|err| {
+                *self.session.suggestions.borrow_mut() = err.suggestions;
+                err.kind
+            })
+    }
+}
+
+impl Resolver for Scope<'_, '_> {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        let param = self
+            .params
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value);
+
+        match param.or_else(|| self.session.variables.get(name)) {
+            Some(value) => Some(value.clone()),
+            None => self.session.resolver.resolve_const_symbol(name),
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match self.session.functions.get(name) {
+            Some(definition) => self.call(definition, args),
+            None => self.session.resolver.resolve_function(name, args),
+        }
+    }
+
+    fn resolve_operator(
+        &self,
+        name: &str,
+        lhs: &Evaluated,
+        rhs: &Evaluated,
+    ) -> Result<Evaluated, ErrorKind> {
+        self.session.resolver.resolve_operator(name, lhs, rhs)
+    }
+
+    fn has_function(&self, name: &str) -> bool {
+        self.session.functions.contains_key(name) || self.session.resolver.has_function(name)
+    }
+
+    fn max_iterations(&self) -> usize {
+        self.session
+            .max_iterations
+            .unwrap_or_else(|| self.session.resolver.max_iterations())
+    }
+
+    fn const_symbols(&self) -> Vec<&str> {
+        let params = self.params.iter().map(|(param, _)| *param);
+        params.chain(self.session.const_symbols()).collect()
+    }
+
+    fn functions(&self) -> Vec<&str> {
+        self.session.functions()
+    }
+}
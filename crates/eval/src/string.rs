@@ -0,0 +1,63 @@
+//! String literal escape sequences.
+
+use alloc::string::String;
+
+/// Unescape the contents of a string literal, excluding the quotes.
+///
+/// The following escape sequences are supported:
+/// - `\\`, `\"` and `\'`
+/// - `\n`, `\r`, `\t` and `\0`
+/// - `\u{...}`, a unicode scalar value of up to 6 hexadecimal digits
+///
+/// On failure the byte range of the invalid escape sequence in `raw` is returned.
+pub(crate) fn unescape(raw: &str) -> Result<String, (usize, usize)> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some((_, c @ ('\\' | '"' | '\''))) => c,
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, '0')) => '\0',
+            Some((_, 'u')) => unicode(&mut chars).ok_or_else(|| {
+                let end = chars.clone().next().map_or(raw.len(), |(end, _)| end);
+                (start, end)
+            })?,
+
+            Some((end, c)) => return Err((start, end + c.len_utf8())),
+            None => return Err((start, raw.len())),
+        };
+
+        out.push(escaped);
+    }
+
+    Ok(out)
+}
+
+/// Parse the `{...}` of a `\u{...}` escape sequence.
+fn unicode(chars: &mut core::str::CharIndices) -> Option<char> {
+    if chars.next()?.1 != '{' {
+        return None;
+    }
+
+    let mut value = 0u32;
+    let mut digits = 0;
+
+    loop {
+        match chars.next()?.1 {
+            '}' if digits > 0 => break char::from_u32(value),
+            c if digits < 6 => {
+                value = value * 16 + c.to_digit(16)?;
+                digits += 1;
+            }
+            _ => break None,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Suggestions of similar names for unknown constants and functions.
+//!
+//! Names are compared by their edit distance, the number of characters which need to be inserted, removed or replaced
+//! to turn one name into the other.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{builtin, lambda, resolve::Resolver};
+
+/// The maximum number of suggested names.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Returns the constants most similar to an unknown constant, closest first.
+///
+/// Constants are suggested from [`Resolver::const_symbols`], which for the scope of a lambda or session includes its
+/// parameters and variables.
+pub(crate) fn constants(resolver: &dyn Resolver, name: &str) -> Vec<String> {
+    let literals = ["true", "false"].into_iter();
+    similar(name, literals.chain(resolver.const_symbols()))
+}
+
+/// Returns the functions most similar to an unknown function, closest first.
+///
+/// Functions are suggested from [`Resolver::functions`] and the built-in functions.
+pub(crate) fn functions(resolver: &dyn Resolver, name: &str) -> Vec<String> {
+    let builtin = builtin::FUNCTIONS
+        .iter()
+        .chain(lambda::FUNCTIONS)
+        .chain(&["if"])
+        .copied();
+
+    similar(name, builtin.chain(resolver.functions()))
+}
+
+/// Returns the known names most similar to an unknown name.
+fn similar<'a>(name: &str, known: impl Iterator<Item = &'a str>) -> Vec<String> {
+    // Allow about one edit for every three characters, so short names only match names which are close.
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    let mut similar = known
+        .filter(|&known| known != name)
+        .filter_map(|known| {
+            let distance = distance(name, known);
+            (distance <= max_distance).then_some((distance, known))
+        })
+        .collect::<Vec<_>>();
+
+    similar.sort_unstable();
+    similar.dedup();
+
+    similar
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, known)| known.into())
+        .collect()
+}
+
+/// The edit distance between two names.
+///
+/// Swapping two adjacent characters counts as a single edit, since it is a common typing mistake.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+
+    // The distances from the prefixes of `a` of the previous two rows and the current row to each prefix of `b`.
+    let mut previous = Vec::new();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for i in 1..=a.len() {
+        let mut next = alloc::vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            next[j] = (row[j - 1] + cost).min(row[j] + 1).min(next[j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                next[j] = next[j].min(previous[j - 2] + 1);
+            }
+        }
+
+        previous = core::mem::replace(&mut row, next);
+    }
+
+    row[b.len()]
+}
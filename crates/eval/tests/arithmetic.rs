@@ -0,0 +1,73 @@
+//! Arithmetic on numbers
+
+use equation_eval::{eval, resolve::Resolver, ErrorKind, Evaluated};
+use rust_decimal::Decimal;
+
+struct Empty;
+
+impl Resolver for Empty {
+    fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+        None
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(
+                eval(&Empty, $input),
+                Ok(Evaluated::Decimal(Decimal::from($expected)))
+            );
+        }
+    };
+}
+
+generate_test!(add: "2+2", 4);
+generate_test!(precedence: "2 + 3 * 4", 14);
+generate_test!(braces: "(2 + 3) * 4", 20);
+generate_test!(curly_braces: "{2 + 3} * 4", 20);
+generate_test!(modulo: "28 % 3", 1);
+generate_test!(pow_right_associative: "2^3^2", 512);
+generate_test!(neg_pow: "-2^2", -4);
+generate_test!(factorial: "3!", 6);
+generate_test!(hexadecimal: "0xff", 255);
+generate_test!(binary: "0b101", 5);
+generate_test!(scientific: "5e2", 500);
+
+#[test]
+fn divide_by_zero() {
+    let err = eval(&Empty, "1 / (2 - 2)").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::DivideByZero);
+    assert_eq!((err.span.start, err.span.end), (0, 11));
+}
+
+#[test]
+fn mismatched_brace() {
+    let err = eval(&Empty, "(2 + 3]").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnexpectedToken);
+    assert_eq!((err.span.start, err.span.end), (6, 7));
+}
+
+#[test]
+fn unknown_constant() {
+    let err = eval(&Empty, "1 + foo").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnknownConstant);
+    assert_eq!((err.span.start, err.span.end), (4, 7));
+}
+
+#[test]
+fn empty() {
+    let err = eval(&Empty, "").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnexpectedEnd);
+}
+
+#[test]
+fn invalid_binary() {
+    let err = eval(&Empty, "0b5344").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidNumber);
+}
@@ -0,0 +1,167 @@
+//! Rendering errors with source snippets
+
+use equation_eval::{
+    diagnostic::Diagnostic, eval, resolve::Resolver, session::Session, ErrorKind, Evaluated, Type,
+};
+
+struct Empty;
+
+impl Resolver for Empty {
+    fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+        None
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Empty, $input).unwrap_err();
+            assert_eq!(Diagnostic::new($input, &err).to_string(), $expected);
+        }
+    };
+}
+
+generate_test!(unknown_function: "1 + sqr(2)", "\
+error: unknown function
+ --> 1:5
+  |
+1 | 1 + sqr(2)
+  |     ^^^^^^
+");
+
+generate_test!(tabs: "1 +\t\tx", "\
+error: unknown constant
+ --> 1:6
+  |
+1 | 1 +        x
+  |            ^
+");
+
+generate_test!(wide_characters: "\"日本\" + x", "\
+error: unknown constant
+ --> 1:8
+  |
+1 | \"日本\" + x
+  |          ^
+");
+
+generate_test!(multi_byte: "\"héllo\" * 2", "\
+error: expected decimal, found string
+ --> 1:1
+  |
+1 | \"héllo\" * 2
+  | ^^^^^^^
+");
+
+generate_test!(unexpected_end: "(1 + 2", "\
+error: unexpected end of expression
+ --> 1:7
+  |
+1 | (1 + 2
+  |       ^
+");
+
+generate_test!(later_line: "1 + 2\n* 3 +\n[1, 2] * true", "\
+error: expected decimal, found bool
+ --> 3:10
+  |
+3 | [1, 2] * true
+  |          ^^^^
+");
+
+generate_test!(suggestions: "lenn([1, 2])", "\
+error: unknown function
+ --> 1:1
+  |
+1 | lenn([1, 2])
+  | ^^^^^^^^^^^^
+  |
+  = help: did you mean `len`?
+");
+
+#[test]
+fn label_and_notes() {
+    let input = "1 + sqr(2)";
+    let err = eval(&Empty, input).unwrap_err();
+    let diagnostic = Diagnostic::new(input, &err)
+        .with_label("not provided by the resolver")
+        .with_help("did you mean `sqrt`?")
+        .with_note("functions are provided by the resolver");
+
+    assert_eq!(
+        diagnostic.to_string(),
+        "\
+error: unknown function
+ --> 1:5
+  |
+1 | 1 + sqr(2)
+  |     ^^^^^^ not provided by the resolver
+  |
+  = help: did you mean `sqrt`?
+  = note: functions are provided by the resolver
+"
+    );
+}
+
+#[test]
+fn first_line() {
+    let input = "1 + sqr(2)";
+    let err = eval(&Empty, input).unwrap_err();
+
+    assert_eq!(
+        Diagnostic::new(input, &err).with_first_line(12).to_string(),
+        "\
+error: unknown function
+  --> 12:5
+   |
+12 | 1 + sqr(2)
+   |     ^^^^^^
+"
+    );
+}
+
+#[test]
+fn multiple_lines() {
+    let input = "f(x) = x\nf(\n  1,\n  2)";
+    let err = Session::new(&Empty).eval(input).unwrap_err();
+
+    assert_eq!(
+        Diagnostic::new(input, &err)
+            .with_label("called here")
+            .to_string(),
+        "\
+error: expected 1 argument, found 2
+ --> 2:1
+  |
+2 | f(
+  | ^^
+3 |   1,
+  | ^^^^
+4 |   2)
+  | ^^^^ called here
+"
+    );
+}
+
+#[test]
+fn error_kind_display() {
+    let kind = ErrorKind::TypeMismatch {
+        expected: Type::List,
+        found: Type::Lambda,
+    };
+
+    assert_eq!(kind.to_string(), "expected list, found lambda");
+    assert_eq!(
+        ErrorKind::ArgumentCount {
+            expected: 3,
+            found: 1
+        }
+        .to_string(),
+        "expected 3 arguments, found 1"
+    );
+}
@@ -0,0 +1,149 @@
+//! Evaluating other dialects of expressions lexed with a `LexerConfig`
+
+use equation_eval::{
+    eval, eval_tokens,
+    export::latex,
+    node::{Node, Spanned},
+    resolve::Resolver,
+    ErrorKind, Evaluated,
+};
+use equation_lexer::{LexerConfig, Token, TokenKind, Tokenizer};
+use rust_decimal::Decimal;
+
+struct Order;
+
+impl Resolver for Order {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        let value = match name {
+            "order.total" => Decimal::from(120),
+            "order.items" => Decimal::from(7),
+            "$rate" => Decimal::new(5, 2),
+            "x" => Decimal::TWO,
+            _ => return None,
+        };
+
+        Some(Evaluated::Decimal(value))
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+const PYTHON: LexerConfig = LexerConfig {
+    double_star_power: true,
+    double_slash_division: true,
+    hash_comments: true,
+    dollar_identifiers: true,
+    dotted_identifiers: true,
+};
+
+fn eval_dialect(input: &str) -> Result<Evaluated, equation_eval::Error> {
+    eval_tokens(&Order, input, Tokenizer::with_config(input, PYTHON))
+}
+
+/// Check an expression in the dialect evaluates to the same value as a standard expression.
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $standard: expr) => {
+        #[test]
+        fn $name() {
+            let value = eval_dialect($input);
+
+            assert!(value.is_ok(), "{:?}", value);
+            assert_eq!(value, eval(&Order, $standard));
+        }
+    };
+}
+
+generate_test!(power: "2 ** 3 ** 2", "2 ^ 3 ^ 2");
+generate_test!(negated_power: "-x ** 2", "-x ^ 2");
+generate_test!(integer_division: "7 // 2", "3");
+generate_test!(negative_integer_division: "-7 // 2", "-4");
+generate_test!(decimal_integer_division: "7.5 // 2.5", "3");
+generate_test!(integer_division_precedence: "1 + 7 // 2 * 2", "1 + 6");
+generate_test!(comment: "1 + 2 # the answer", "3");
+generate_test!(comment_lines: "x # first\n+ 1 # second", "x + 1");
+generate_test!(dollar: "$rate * 100", "5");
+generate_test!(dotted: "order.total / order.items", "120 / 7");
+generate_test!(
+    everything: "order.total * $rate // 1 ** 2 # rounded down",
+    "6"
+);
+
+#[test]
+fn integer_division_by_zero() {
+    let error = eval_dialect("1 // 0").unwrap_err();
+
+    assert_eq!(error.kind, ErrorKind::DivideByZero);
+    assert_eq!((error.span.start, error.span.end), (0, 6));
+}
+
+#[test]
+fn standard_dialect() {
+    // Without the options these are not valid expressions.
+    assert_eq!(
+        eval(&Order, "2 ** 3").unwrap_err().kind,
+        ErrorKind::UnexpectedToken
+    );
+    assert_eq!(
+        eval(&Order, "7 // 2").unwrap_err().kind,
+        ErrorKind::UnexpectedToken
+    );
+    assert_eq!(
+        eval(&Order, "1 # one").unwrap_err().kind,
+        ErrorKind::InvalidToken
+    );
+    assert_eq!(
+        eval(&Order, "$rate").unwrap_err().kind,
+        ErrorKind::InvalidToken
+    );
+    assert_eq!(
+        eval(&Order, "order.total").unwrap_err().kind,
+        ErrorKind::InvalidToken
+    );
+}
+
+#[test]
+fn spans() {
+    let input = "$rate ** 2 # squared";
+    let nodes = Node::from_tokens(input, Tokenizer::with_config(input, PYTHON))
+        .unwrap()
+        .map(|node| {
+            let span = node.span();
+            (span.start, span.end)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(nodes, [(0, 5), (9, 10), (0, 10)]);
+}
+
+#[test]
+fn empty_token() {
+    // The tokenizer never produces empty tokens, but tokens may come from elsewhere.
+    let input = "1 + 2";
+    let mut tokens = Tokenizer::from(input).collect::<Vec<_>>();
+    tokens.insert(
+        1,
+        Token {
+            kind: TokenKind::Ws,
+            len: 0,
+        },
+    );
+
+    let error = eval_tokens(&Order, input, tokens.into_iter()).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidToken);
+    assert_eq!((error.span.start, error.span.end), (1, 1));
+}
+
+#[test]
+fn export_integer_division() {
+    let input = "(x + 1) // 2";
+    let nodes = Node::from_tokens(input, Tokenizer::with_config(input, PYTHON))
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        latex(&nodes).unwrap(),
+        "\\left\\lfloor \\frac{x + 1}{2} \\right\\rfloor"
+    );
+}
@@ -0,0 +1,138 @@
+//! Exporting expressions to LaTeX and MathML
+
+use equation_eval::{
+    export::{latex, mathml},
+    node::Node,
+    ErrorKind,
+};
+
+fn nodes(input: &str) -> Vec<Node<'_>> {
+    Node::parse(input).unwrap().collect()
+}
+
+macro_rules! generate_latex {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(latex(&nodes($input)).unwrap(), $expected);
+        }
+    };
+}
+
+macro_rules! generate_mathml {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let expected = concat!(
+                "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">",
+                $expected,
+                "</math>"
+            );
+            assert_eq!(mathml(&nodes($input)).unwrap(), expected);
+        }
+    };
+}
+
+generate_latex!(latex_sum: "1 + 2 - x", "1 + 2 - x");
+generate_latex!(latex_product: "2 * x % 3", "2 \\cdot x \\bmod 3");
+generate_latex!(latex_grouped_sum: "(1 + 2) * 3", "\\left(1 + 2\\right) \\cdot 3");
+generate_latex!(latex_redundant_parens: "(1 * 2) + (3)", "1 \\cdot 2 + 3");
+generate_latex!(latex_right_operand: "1 - (2 - 3)", "1 - \\left(2 - 3\\right)");
+generate_latex!(latex_left_operand: "(1 - 2) - 3", "1 - 2 - 3");
+generate_latex!(latex_fraction: "(a + b) / 2", "\\frac{a + b}{2}");
+generate_latex!(latex_nested_fraction: "1 / (2 / x)", "\\frac{1}{\\frac{2}{x}}");
+generate_latex!(latex_fraction_product: "a / b * c", "\\frac{a}{b} \\cdot c");
+generate_latex!(latex_power: "x^2 + 1", "x^{2} + 1");
+generate_latex!(latex_power_right: "2^3^2", "2^{3^{2}}");
+generate_latex!(latex_power_left: "(2^3)^2", "\\left(2^{3}\\right)^{2}");
+generate_latex!(latex_power_of_fraction: "(a / b)^2", "\\left(\\frac{a}{b}\\right)^{2}");
+generate_latex!(latex_power_of_negation: "(-2)^2", "\\left(-2\\right)^{2}");
+generate_latex!(latex_negated_power: "-2^2", "-2^{2}");
+generate_latex!(latex_negated_sum: "-(a + b)", "-\\left(a + b\\right)");
+generate_latex!(latex_double_negation: "-(-x)", "-\\left(-x\\right)");
+generate_latex!(latex_factorial: "(n + 1)! * 2", "\\left(n + 1\\right)! \\cdot 2");
+generate_latex!(latex_sqrt: "sqrt(x + 1)", "\\sqrt{x + 1}");
+generate_latex!(latex_abs: "abs(-x)", "\\left|-x\\right|");
+generate_latex!(latex_operator_names: "sin(x) + ln(2)", "\\sin\\left(x\\right) + \\ln\\left(2\\right)");
+generate_latex!(latex_other_functions: "round(x, 2) + f(y)", "\\operatorname{round}\\left(x, 2\\right) + f\\left(y\\right)");
+generate_latex!(latex_constants: "2 * pi * r + tau", "2 \\cdot \\pi \\cdot r + \\tau");
+generate_latex!(latex_names: "rate * x_1 + Delta", "\\mathrm{rate} \\cdot x_{1} + \\Delta");
+generate_latex!(latex_comparison: "x <= 2 && y >= 3 || z != 4", "x \\leq 2 \\land y \\geq 3 \\lor z \\neq 4");
+generate_latex!(latex_grouped_comparison: "(a < b) = true", "\\left(a < b\\right) = \\mathrm{true}");
+generate_latex!(latex_grouped_comparison_right: "x = (y != z)", "x = \\left(y \\neq z\\right)");
+generate_latex!(latex_grouped_or: "(a || b) && !c", "\\left(a \\lor b\\right) \\land \\lnot c");
+generate_latex!(latex_not_comparison: "!(x = 1)", "\\lnot \\left(x = 1\\right)");
+generate_latex!(latex_literals: "concat('a_b', true)", "\\operatorname{concat}\\left(\\text{``a\\_b''}, \\mathrm{true}\\right)");
+generate_latex!(latex_list: "[1, 2][0]", "\\left[1, 2\\right]\\left[0\\right]");
+generate_latex!(latex_lambda: "map(xs, x -> x * 2)", "\\operatorname{map}\\left(\\mathrm{xs}, x \\mapsto x \\cdot 2\\right)");
+generate_latex!(latex_lambda_params: "reduce(xs, 0, (a, b) -> a + b)", "\\operatorname{reduce}\\left(\\mathrm{xs}, 0, \\left(a, b\\right) \\mapsto a + b\\right)");
+generate_latex!(
+    latex_conditional: "if(x > 0, x, -x) + 1",
+    "\\begin{cases} x & \\text{if } x > 0 \\\\ -x & \\text{otherwise} \\end{cases} + 1"
+);
+generate_latex!(
+    latex_nested_conditional: "if(a, 1, if(b, 2, 3))",
+    "\\begin{cases} 1 & \\text{if } a \\\\ \\begin{cases} 2 & \\text{if } b \\\\ 3 & \\text{otherwise} \\end{cases} & \\text{otherwise} \\end{cases}"
+);
+generate_latex!(latex_series: "sum(k, 1, n, k^2)", "\\sum_{k=1}^{n} k^{2}");
+generate_latex!(latex_series_body: "2 * prod(k, 1, n, k + 1)", "2 \\cdot \\left(\\prod_{k=1}^{n} \\left(k + 1\\right)\\right)");
+generate_latex!(latex_integral: "integrate(x^2, x, 0, 1)", "\\int_{0}^{1} x^{2} \\, dx");
+
+generate_mathml!(mathml_sum: "1 + x", "<mn>1</mn><mo>+</mo><mi>x</mi>");
+generate_mathml!(
+    mathml_grouped: "(1 - x) * 2",
+    "<mrow><mo>(</mo><mn>1</mn><mo>−</mo><mi>x</mi><mo>)</mo></mrow><mo>⋅</mo><mn>2</mn>"
+);
+generate_mathml!(
+    mathml_fraction: "pi / 2",
+    "<mfrac><mrow><mi>π</mi></mrow><mrow><mn>2</mn></mrow></mfrac>"
+);
+generate_mathml!(
+    mathml_power: "(-x)^2",
+    "<msup><mrow><mrow><mo>(</mo><mo>−</mo><mi>x</mi><mo>)</mo></mrow></mrow><mrow><mn>2</mn></mrow></msup>"
+);
+generate_mathml!(
+    mathml_double_negation: "-(-x)",
+    "<mo>−</mo><mrow><mo>(</mo><mo>−</mo><mi>x</mi><mo>)</mo></mrow>"
+);
+generate_mathml!(
+    mathml_grouped_comparison: "(a < b) = c",
+    "<mrow><mo>(</mo><mi>a</mi><mo>&lt;</mo><mi>b</mi><mo>)</mo></mrow><mo>=</mo><mi>c</mi>"
+);
+generate_mathml!(mathml_sqrt: "sqrt(2)", "<msqrt><mn>2</mn></msqrt>");
+generate_mathml!(
+    mathml_function: "max(a, b)",
+    "<mi>max</mi><mo>&#x2061;</mo><mrow><mo>(</mo><mi>a</mi><mo>,</mo><mi>b</mi><mo>)</mo></mrow>"
+);
+generate_mathml!(
+    mathml_comparison: "a < b && c >= d",
+    "<mi>a</mi><mo>&lt;</mo><mi>b</mi><mo>∧</mo><mi>c</mi><mo>≥</mo><mi>d</mi>"
+);
+generate_mathml!(mathml_subscript: "x_1", "<msub><mi>x</mi><mn>1</mn></msub>");
+generate_mathml!(mathml_string: "'<a & b>'", "<ms>&lt;a &amp; b&gt;</ms>");
+generate_mathml!(
+    mathml_conditional: "if(x, 1, 2)",
+    "<mrow><mo>{</mo><mtable><mtr><mtd><mn>1</mn></mtd><mtd><mtext>if&#xa0;</mtext><mi>x</mi></mtd></mtr><mtr><mtd><mn>2</mn></mtd><mtd><mtext>otherwise</mtext></mtd></mtr></mtable></mrow>"
+);
+generate_mathml!(
+    mathml_series: "sum(k, 1, n, k)",
+    "<munderover><mo>∑</mo><mrow><mi>k</mi><mo>=</mo><mn>1</mn></mrow><mrow><mi>n</mi></mrow></munderover><mi>k</mi>"
+);
+
+#[test]
+fn recovered_error() {
+    let (nodes, errors) = Node::parse_recovering("1 + ) * 2");
+    let error = latex(&nodes).unwrap_err();
+
+    assert_eq!(error, errors[0]);
+    assert_eq!(mathml(&nodes).unwrap_err(), error);
+}
+
+#[test]
+fn missing_operand() {
+    assert_eq!(latex(&[]).unwrap_err().kind, ErrorKind::MissingOperand);
+
+    let mut nodes = nodes("1 + 2");
+    nodes.remove(0);
+    assert_eq!(mathml(&nodes).unwrap_err().kind, ErrorKind::MissingOperand);
+}
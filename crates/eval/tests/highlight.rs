@@ -0,0 +1,255 @@
+//! Highlighting and brace matching
+
+use equation_eval::{
+    highlight::{
+        ansi, html, lexical, lexical_with_config, semantic, semantic_with_config, BracePair,
+        Braces, Class,
+    },
+    limits::EvalLimits,
+    node::Span,
+    resolve::Resolver,
+    ErrorKind, Evaluated,
+};
+use equation_lexer::LexerConfig;
+use rust_decimal::Decimal;
+
+struct Constants;
+
+impl Resolver for Constants {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "pi" => Some(Evaluated::Decimal(Decimal::PI)),
+            "tau" => Some(Evaluated::Decimal(Decimal::TWO_PI)),
+            "e" => Some(Evaluated::Decimal(Decimal::E)),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+/// The text and class of each highlight which is not whitespace.
+fn classes(str: &str, highlights: &[equation_eval::highlight::Highlight]) -> Vec<(String, Class)> {
+    highlights
+        .iter()
+        .filter(|highlight| highlight.class != Class::Whitespace)
+        .map(|highlight| {
+            let text = &str[highlight.span.start..highlight.span.end];
+            (text.to_owned(), highlight.class)
+        })
+        .collect()
+}
+
+macro_rules! generate_test {
+    ($name: ident: $highlight: ident, $input: expr, [$(($text: expr, $class: ident)),* $(,)?]) => {
+        #[test]
+        fn $name() {
+            let highlights = generate_test!(@highlight $highlight, $input);
+            let expected: Vec<(String, Class)> = vec![$(($text.to_owned(), Class::$class)),*];
+            assert_eq!(classes($input, &highlights), expected);
+        }
+    };
+
+    (@highlight lexical, $input: expr) => { lexical($input) };
+    (@highlight semantic, $input: expr) => { semantic(&Constants, $input) };
+}
+
+generate_test!(lexical_tokens: lexical, "sqrt(x) + 1.5 * \"a\"", [
+    ("sqrt", Function), ("(", Brace), ("x", Name), (")", Brace), ("+", Operator), ("1.5", Number),
+    ("*", Operator), ("\"a\"", String),
+]);
+generate_test!(lexical_keywords: lexical, "if(true, 1, false)", [
+    ("if", Keyword), ("(", Brace), ("true", Keyword), (",", Separator), ("1", Number), (",", Separator),
+    ("false", Keyword), (")", Brace),
+]);
+generate_test!(lexical_invalid: lexical, "1 $ 2", [("1", Number), ("$", Invalid), ("2", Number)]);
+generate_test!(lexical_call_with_space: lexical, "f (1)", [
+    ("f", Function), ("(", Brace), ("1", Number), (")", Brace),
+]);
+generate_test!(semantic_constants: semantic, "pi * r", [("pi", Constant), ("*", Operator), ("r", Name)]);
+generate_test!(semantic_lambda: semantic, "map([1], x -> x * e)", [
+    ("map", Function), ("(", Brace), ("[", Brace), ("1", Number), ("]", Brace), (",", Separator),
+    ("x", Variable), ("-", Operator), (">", Operator), ("x", Variable), ("*", Operator), ("e", Constant),
+    (")", Brace),
+]);
+generate_test!(semantic_lambda_params: semantic, "reduce([1], (acc, e) -> acc + e)", [
+    ("reduce", Function), ("(", Brace), ("[", Brace), ("1", Number), ("]", Brace), (",", Separator),
+    ("(", Brace), ("acc", Variable), (",", Separator), ("e", Variable), (")", Brace), ("-", Operator),
+    (">", Operator), ("acc", Variable), ("+", Operator), ("e", Variable), (")", Brace),
+]);
+generate_test!(semantic_shadowed_outside: semantic, "map([e], e -> e) + e", [
+    ("map", Function), ("(", Brace), ("[", Brace), ("e", Constant), ("]", Brace), (",", Separator),
+    ("e", Variable), ("-", Operator), (">", Operator), ("e", Variable), (")", Brace), ("+", Operator),
+    ("e", Constant),
+]);
+generate_test!(semantic_parse_error: semantic, "pi + ) * tau", [
+    ("pi", Constant), ("+", Operator), (")", Brace), ("*", Operator), ("tau", Constant),
+]);
+
+#[test]
+fn covers_everything() {
+    let str = "sqrt(é) +\t\"π\"";
+    let highlights = lexical(str);
+
+    let mut end = 0;
+    for highlight in &highlights {
+        assert_eq!(highlight.span.start, end);
+        end = highlight.span.end;
+    }
+    assert_eq!(end, str.len());
+}
+
+#[test]
+fn render_html() {
+    let str = "a < \"<b>\" && f(1)";
+
+    assert_eq!(
+        html(str, &lexical(str)),
+        "<span class=\"name\">a</span> <span class=\"operator\">&lt;</span> \
+         <span class=\"string\">&quot;&lt;b&gt;&quot;</span> \
+         <span class=\"operator\">&amp;</span><span class=\"operator\">&amp;</span> \
+         <span class=\"function\">f</span><span class=\"brace\">(</span>\
+         <span class=\"number\">1</span><span class=\"brace\">)</span>"
+    );
+}
+
+#[test]
+fn render_ansi() {
+    let str = "pi + x";
+
+    assert_eq!(
+        ansi(str, &semantic(&Constants, str)),
+        "\x1b[35mpi\x1b[0m \x1b[33m+\x1b[0m x"
+    );
+}
+
+#[test]
+fn render_without_highlights() {
+    assert_eq!(html("1 < 2", &[]), "1 < 2");
+    assert_eq!(ansi("1 < 2", &[]), "1 < 2");
+}
+
+fn span(start: usize, end: usize) -> Span {
+    Span { start, end }
+}
+
+/// A dialect with `#` comments and names such as `$p`.
+fn dialect() -> LexerConfig {
+    LexerConfig {
+        hash_comments: true,
+        dollar_identifiers: true,
+        ..LexerConfig::default()
+    }
+}
+
+#[test]
+fn lexical_comment() {
+    let str = "1 + x # (a note";
+
+    assert_eq!(
+        classes(str, &lexical_with_config(str, dialect())),
+        [
+            ("1".to_owned(), Class::Number),
+            ("+".to_owned(), Class::Operator),
+            ("x".to_owned(), Class::Name),
+            ("# (a note".to_owned(), Class::Comment),
+        ]
+    );
+    assert_eq!(
+        classes(str, &lexical(str))[3],
+        ("#".to_owned(), Class::Invalid)
+    );
+}
+
+#[test]
+fn semantic_dialect() {
+    let str = "map([1], $p -> $p * pi) # scaled";
+
+    let highlights = semantic_with_config(&Constants, str, dialect(), &EvalLimits::default());
+    let classes = classes(str, &highlights);
+
+    assert!(classes.contains(&("$p".to_owned(), Class::Variable)));
+    assert!(classes.contains(&("pi".to_owned(), Class::Constant)));
+    assert_eq!(
+        classes.last(),
+        Some(&("# scaled".to_owned(), Class::Comment))
+    );
+}
+
+#[test]
+fn semantic_limits() {
+    let str = format!("{}pi{}", "(".repeat(100_000), ")".repeat(100_000));
+
+    // Braces nested too deeply are not parsed, so every name is classified as by `lexical`.
+    let highlights = semantic(&Constants, &str);
+    assert_eq!(highlights, lexical(&str));
+
+    let limits = EvalLimits {
+        max_tokens: 2,
+        ..EvalLimits::default()
+    };
+    let highlights = semantic_with_config(&Constants, "pi + pi", LexerConfig::default(), &limits);
+    assert_eq!(highlights, lexical("pi + pi"));
+}
+
+#[test]
+fn braces_in_comment() {
+    let braces = Braces::with_config("(1) # (", dialect());
+
+    assert_eq!(braces.pairs.len(), 1);
+    assert!(braces.unmatched.is_empty());
+}
+
+#[test]
+fn brace_pairs() {
+    let braces = Braces::new("f([1, (2)], 3)");
+
+    assert_eq!(
+        braces.pairs,
+        [
+            BracePair {
+                open: span(6, 7),
+                close: span(8, 9),
+                depth: 2
+            },
+            BracePair {
+                open: span(2, 3),
+                close: span(9, 10),
+                depth: 1
+            },
+            BracePair {
+                open: span(1, 2),
+                close: span(13, 14),
+                depth: 0
+            },
+        ]
+    );
+    assert!(braces.unmatched.is_empty());
+}
+
+#[test]
+fn brace_matching() {
+    let braces = Braces::new("(1 + [2])");
+
+    assert_eq!(braces.matching(0), Some(span(8, 9)));
+    assert_eq!(braces.matching(8), Some(span(0, 1)));
+    assert_eq!(braces.matching(5), Some(span(7, 8)));
+    assert_eq!(braces.matching(1), None);
+}
+
+#[test]
+fn brace_unmatched() {
+    let braces = Braces::new("(1] + ((2)");
+
+    assert_eq!(
+        braces.pairs,
+        [BracePair {
+            open: span(7, 8),
+            close: span(9, 10),
+            depth: 2
+        }]
+    );
+    assert_eq!(braces.unmatched, [span(0, 1), span(2, 3), span(6, 7)]);
+}
@@ -0,0 +1,101 @@
+//! Lambdas and higher-order functions
+
+use equation_eval::{eval, resolve::Resolver, session::Session, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Orders;
+
+impl Resolver for Orders {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "threshold" => Some(Evaluated::Decimal(Decimal::TWO)),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("half", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(value / Decimal::TWO)),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+fn decimals(values: &[i64]) -> Evaluated {
+    Evaluated::List(
+        values
+            .iter()
+            .map(|value| Evaluated::Decimal(Decimal::from(*value)))
+            .collect(),
+    )
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Orders, $input), Ok($expected));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Orders, $input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(map: "map([1, 2, 3], x -> x * 2)", decimals(&[2, 4, 6]));
+generate_test!(filter: "filter([1, 2, 3, 4], x -> x % 2 = 0)", decimals(&[2, 4]));
+generate_test!(reduce: "reduce([1, 2, 3, 4], (acc, x) -> acc + x)", Evaluated::Decimal(Decimal::TEN));
+generate_test!(reduce_initial: "reduce([1, 2, 3], (acc, x) -> acc * x, 10)", Evaluated::Decimal(Decimal::from(60)));
+generate_test!(reduce_empty_initial: "reduce([], (acc, x) -> acc + x, 0)", Evaluated::Decimal(Decimal::ZERO));
+generate_test!(captures_resolver: "filter([1, 2, 3], x -> x > threshold)", decimals(&[3]));
+generate_test!(calls_resolver: "map([2, 4], x -> half(x))", decimals(&[1, 2]));
+generate_test!(nested: "map([1, 2], x -> sum(map([10, 20], y -> x * y)))", decimals(&[30, 60]));
+generate_test!(rows: "map([[1, 2], [3, 4]], row -> sum(row))", decimals(&[3, 7]));
+generate_test!(matrix_result: "map([1, 2], x -> [x, x])", eval(&Orders, "[[1, 1], [2, 2]]").unwrap());
+generate_test!(parenthesized_single: "map([1], (x) -> x + 1)", decimals(&[2]));
+generate_test!(body_extends: "map([1], x -> x + 1 * 2)", decimals(&[3]));
+generate_test!(short_circuit: "filter([0, 1], x -> x != 0 && 1 / x = 1)", decimals(&[1]));
+generate_test!(lambda_value: "len(concat(x -> x))", Evaluated::Decimal(Decimal::from(10)));
+generate_test!(subtract_greater: "3 - 1 > 1", Evaluated::Bool(true));
+
+// Errors inside the body point into the body.
+generate_error!(body_error: "map([1, 0], x -> 1 / x)", ErrorKind::DivideByZero, 17, 22);
+generate_error!(body_type: "map([1], x -> x && true)", ErrorKind::TypeMismatch { expected: Type::Bool, found: Type::Decimal }, 14, 15);
+generate_error!(predicate_type: "filter([1], x -> x)", ErrorKind::TypeMismatch { expected: Type::Bool, found: Type::Decimal }, 12, 18);
+generate_error!(not_lambda: "map([1], 2)", ErrorKind::TypeMismatch { expected: Type::Lambda, found: Type::Decimal }, 0, 11);
+generate_error!(not_list: "map(1, x -> x)", ErrorKind::TypeMismatch { expected: Type::List, found: Type::Decimal }, 0, 14);
+generate_error!(lambda_arity: "map([1], (a, b) -> a)", ErrorKind::ArgumentCount { expected: 2, found: 1 }, 0, 21);
+generate_error!(map_arity: "map([1])", ErrorKind::ArgumentCount { expected: 2, found: 1 }, 0, 8);
+generate_error!(reduce_empty: "reduce([], (a, b) -> a)", ErrorKind::InvalidArgument, 0, 23);
+generate_error!(duplicate_param: "map([1], (x, x) -> x)", ErrorKind::Redefinition, 13, 14);
+generate_error!(spaced_arrow: "map([1], x - > x)", ErrorKind::UnexpectedToken, 13, 14);
+generate_error!(arithmetic: "(x -> x) + 1", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Lambda }, 1, 7);
+
+#[test]
+fn closure_captures_bindings() {
+    let mut session = Session::new(&Orders);
+    session.eval("scale(k, xs) = map(xs, x -> x * k)").unwrap();
+
+    assert_eq!(
+        session.eval("scale(3, [1, 2])"),
+        Ok(Some(decimals(&[3, 6])))
+    );
+}
+
+#[test]
+fn closure_captures_at_creation() {
+    let mut session = Session::new(&Orders);
+    session
+        .eval("rate = 2; f = x -> x * rate; rate = 3")
+        .unwrap();
+
+    assert_eq!(session.eval("map([1], f)"), Ok(Some(decimals(&[2]))));
+}
@@ -0,0 +1,205 @@
+//! Parsing LaTeX input
+
+use equation_eval::{
+    eval,
+    latex::{self, translate, MAX_NESTING},
+    limits::EvalLimits,
+    node::Spanned,
+    resolve::Resolver,
+    ErrorKind, Evaluated,
+};
+use rust_decimal::Decimal;
+
+struct Paper;
+
+impl Resolver for Paper {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        let value = match name {
+            "x" => Decimal::TWO,
+            "y" => Decimal::from(3),
+            "n" => Decimal::from(4),
+            "x_1" => Decimal::TEN,
+            "v_max" => Decimal::from(20),
+            "pi" => Decimal::PI,
+            "r" => Decimal::new(15, 1),
+            "theta" => Decimal::ZERO,
+            _ => return None,
+        };
+
+        Some(Evaluated::Decimal(value))
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("sin" | "cos", [Evaluated::Decimal(value)]) if value.is_zero() => {
+                let value = match name {
+                    "sin" => Decimal::ZERO,
+                    _ => Decimal::ONE,
+                };
+
+                Ok(Evaluated::Decimal(value))
+            }
+            ("sqrt", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(
+                rust_decimal::MathematicalOps::sqrt(value).ok_or(ErrorKind::Undefined)?,
+            )),
+            ("abs", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(value.abs())),
+            ("f", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(value + Decimal::ONE)),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+/// Check the translation of LaTeX to the native syntax.
+macro_rules! generate_translation {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(translate($input).as_deref(), Ok($expected));
+        }
+    };
+}
+
+/// Check LaTeX evaluates to the same value as a native expression.
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $native: expr) => {
+        #[test]
+        fn $name() {
+            let value = latex::eval(&Paper, $input);
+
+            assert!(value.is_ok(), "{:?}", value);
+            assert_eq!(value, eval(&Paper, $native));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $kind: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let error = latex::parse($input).unwrap_err();
+
+            assert_eq!(error.kind, $kind);
+            assert_eq!((error.span.start, error.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_translation!(operators: "1 + 2 - 3", "1+2-3");
+generate_translation!(fraction: "\\frac{x}{2}", "((x)/(2))");
+generate_translation!(root: "\\sqrt{x}", "sqrt(x)");
+generate_translation!(nth_root: "\\sqrt[3]{x}", "((x)^(1/(3)))");
+generate_translation!(products: "2 \\cdot x \\times y", "2*x*y");
+generate_translation!(implicit_product: "2\\pi r", "2*pi*r");
+generate_translation!(letters: "xy", "x*y");
+generate_translation!(power: "x^{n+1}", "x^(n+1)");
+generate_translation!(single_digit_power: "x^23", "x^(2)*3");
+generate_translation!(subscripts: "x_1 + v_{max}", "x_1+v_max");
+generate_translation!(function: "\\sin x", "sin(x)");
+generate_translation!(function_parens: "\\sin(x + 1)", "sin(x+1)");
+generate_translation!(function_power: "\\sin^2 x", "sin(x)^(2)");
+generate_translation!(operatorname: "\\operatorname{f}(x)", "f(x)");
+generate_translation!(left_right: "\\left( x + 1 \\right) y", "(x+1)*y");
+generate_translation!(absolute: "\\left| x \\right|", "abs(x)");
+generate_translation!(comparison: "x \\leq y \\land x \\neq 1", "x<=y&&x!=1");
+generate_translation!(spacing: "x \\, \\quad y % a comment\n + 1", "x*y+1");
+generate_translation!(names: "\\mathrm{rate} \\cdot 2", "rate*2");
+
+generate_test!(eval_fraction: "\\frac{1 + x}{2}", "(1 + x) / 2");
+generate_test!(eval_nested: "\\frac{\\frac{1}{2}}{x}", "(1 / 2) / x");
+generate_test!(eval_nth_root: "\\sqrt[2]{16}", "16 ^ (1 / 2)");
+generate_test!(eval_root: "\\sqrt{y^2 + 7}", "sqrt(y^2 + 7)");
+generate_test!(eval_circle: "\\pi r^2", "pi * r^2");
+generate_test!(eval_implicit: "2x^2 + 3x", "2 * x^2 + 3 * x");
+generate_test!(eval_power_group: "2^{x+1}", "2^(x + 1)");
+generate_test!(eval_subscript: "x_1 \\cdot x_{1}", "x_1 * x_1");
+generate_test!(eval_subscript_name: "v_{max} - x", "v_max - x");
+generate_test!(eval_trig: "\\cos\\theta + \\sin(\\theta)", "cos(theta) + sin(theta)");
+generate_test!(eval_trig_power: "\\cos^2 \\theta", "cos(theta)^2");
+generate_test!(eval_left_right: "\\left(x + y\\right)\\left[x - 1\\right]", "(x + y) * (x - 1)");
+generate_test!(eval_abs: "\\left| 1 - y \\right|", "abs(1 - y)");
+generate_test!(eval_call: "\\operatorname{f}(x) \\div 3", "f(x) / 3");
+generate_test!(eval_comparison: "x \\geq 2 \\lor y < 1", "x >= 2 || y < 1");
+generate_test!(eval_factorial: "n! - 2", "n! - 2");
+
+generate_error!(unknown_command: "1 + \\infty", ErrorKind::InvalidToken, 4, 10);
+generate_error!(unclosed_group: "\\frac{1}{2", ErrorKind::UnexpectedEnd, 10, 10);
+generate_error!(unmatched_right: "\\left( x \\right]", ErrorKind::UnexpectedToken, 15, 16);
+generate_error!(stray_brace: "x }", ErrorKind::UnexpectedToken, 2, 3);
+generate_error!(native_error: "1 + \\cdot 2", ErrorKind::UnexpectedToken, 4, 9);
+generate_error!(empty: "", ErrorKind::UnexpectedEnd, 0, 0);
+
+#[test]
+fn spans() {
+    let nodes = latex::parse("\\frac{x}{2} + \\pi").unwrap();
+    let spans = nodes
+        .iter()
+        .map(|node| {
+            let span = node.span();
+            (span.start, span.end)
+        })
+        .collect::<Vec<_>>();
+
+    // `x`, `2`, the fraction, `\pi` and the sum.
+    assert_eq!(spans, [(6, 7), (9, 10), (0, 11), (14, 17), (0, 17)]);
+}
+
+#[test]
+fn eval_error_span() {
+    let error = latex::eval(&Paper, "1 + \\frac{2}{x - 2}").unwrap_err();
+
+    assert_eq!(error.kind, ErrorKind::DivideByZero);
+    assert_eq!((error.span.start, error.span.end), (4, 19));
+}
+
+#[test]
+fn nesting_limit() {
+    let deep = [
+        format!("{}1{}", "{".repeat(100_000), "}".repeat(100_000)),
+        format!("{}1{}", "\\frac{".repeat(100_000), "}{2}".repeat(100_000)),
+        format!("{}x", "\\sqrt".repeat(100_000)),
+        format!("{}x", "\\sin".repeat(100_000)),
+    ];
+
+    for src in &deep {
+        assert_eq!(
+            latex::parse(src).unwrap_err().kind,
+            ErrorKind::NestingTooDeep
+        );
+        assert_eq!(translate(src).unwrap_err().kind, ErrorKind::NestingTooDeep);
+    }
+
+    let nested = |depth| format!("{}1{}", "{".repeat(depth), "}".repeat(depth));
+    assert!(latex::eval(&Paper, &nested(MAX_NESTING - 1)).is_ok());
+
+    let error = latex::parse(&nested(MAX_NESTING)).unwrap_err();
+    assert_eq!(
+        (error.span.start, error.span.end),
+        (MAX_NESTING, MAX_NESTING + 1)
+    );
+}
+
+#[test]
+fn limits() {
+    let limits = EvalLimits {
+        max_nesting: 4,
+        max_input_len: 32,
+        ..EvalLimits::UNLIMITED
+    };
+
+    assert_eq!(
+        latex::eval_with_limits(&Paper, "\\frac{x}{2}", &limits),
+        Ok(Evaluated::Decimal(Decimal::ONE))
+    );
+
+    let error = latex::parse_with_limits("{{{{{x}}}}}", &limits).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingTooDeep);
+    assert_eq!((error.span.start, error.span.end), (4, 5));
+
+    let error = latex::eval_with_limits(&Paper, &"x + ".repeat(10), &limits).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InputTooLong);
+
+    // Fractions translate to two parentheses, so the nesting of the translation is limited too.
+    let error =
+        latex::parse_with_limits("\\frac{\\frac{\\frac{1}{2}}{2}}{2}", &limits).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingTooDeep);
+}
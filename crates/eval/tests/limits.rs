@@ -0,0 +1,145 @@
+//! Resource limits for untrusted expressions
+
+use equation_eval::{
+    eval, eval_with_limits, limits::EvalLimits, node::Node, resolve::Resolver, session::Session,
+    ErrorKind, Evaluated,
+};
+use rust_decimal::Decimal;
+
+struct Untrusted;
+
+impl Resolver for Untrusted {
+    fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+        None
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+const LIMITS: EvalLimits = EvalLimits {
+    max_input_len: 64,
+    max_tokens: 32,
+    max_nesting: 4,
+    max_steps: 100,
+    max_stack: 8,
+    max_exponent: 64,
+    max_factorial: 20,
+    max_elements: 16,
+    max_string_len: 32,
+};
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(
+                eval_with_limits(&Untrusted, $input, &LIMITS),
+                Ok(Evaluated::Decimal(Decimal::from($expected)))
+            );
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval_with_limits(&Untrusted, $input, &LIMITS).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(within_limits: "((((1 + 2)))) * 2^64 / 2^63 + 20!", 2432902008176640006u64);
+generate_test!(negative_exponent: "2^-3 * 8", 1);
+generate_test!(sum_within_steps: "sum(i, 1, 10, i)", 55);
+
+generate_error!(input_too_long: "1 + 2 + 3 + 4 + 5 + 6 + 7 + 8 + 9 + 10 + 11 + 12 + 13 + 14 + 15 + 16", ErrorKind::InputTooLong, 64, 68);
+generate_error!(too_many_tokens: "1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1", ErrorKind::TooManyTokens, 32, 33);
+generate_error!(nesting_too_deep: "(((((1)))))", ErrorKind::NestingTooDeep, 4, 5);
+generate_error!(step_limit: "sum(i, 1, 50, i * i)", ErrorKind::StepLimit, 14, 15);
+generate_error!(stack_overflow: "[1, 2, 3, 4, 5, 6, 7, 8, 9]", ErrorKind::StackOverflow, 25, 26);
+generate_error!(exponent_too_large: "2^65", ErrorKind::ExponentTooLarge, 0, 4);
+generate_error!(negative_exponent_too_large: "2^-65", ErrorKind::ExponentTooLarge, 0, 5);
+generate_error!(factorial_too_large: "21!", ErrorKind::FactorialTooLarge, 0, 3);
+generate_error!(identity_too_many_elements: "identity(5)", ErrorKind::TooManyElements, 0, 11);
+generate_error!(map_too_many_elements: "map([1, 2, 3], x -> [x, x, x, x, x, x])", ErrorKind::TooManyElements, 0, 39);
+generate_error!(concat_too_long: "reduce([1, 2], (s, x) -> concat(s, s), \"abcdefghi\")", ErrorKind::StringTooLong, 25, 37);
+generate_error!(det_step_limit: "det(identity(4)) + det(identity(4))", ErrorKind::StepLimit, 19, 35);
+generate_error!(multiply_step_limit: "identity(4) * identity(4) * identity(4)", ErrorKind::StepLimit, 0, 39);
+generate_error!(format_too_long: "format(\"{}{}{}\", 10^10, 10^10, 10^10)", ErrorKind::StringTooLong, 0, 37);
+
+#[test]
+fn unlimited_by_default() {
+    assert_eq!(
+        eval(&Untrusted, "(((((1)))))"),
+        Ok(Evaluated::Decimal(Decimal::ONE))
+    );
+}
+
+#[test]
+fn default_size_limits() {
+    let limits = EvalLimits::default();
+    let error = |input| {
+        eval_with_limits(&Untrusted, input, &limits)
+            .unwrap_err()
+            .kind
+    };
+
+    assert_eq!(error("len(identity(30000))"), ErrorKind::TooManyElements);
+    assert_eq!(
+        error("reduce([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20], (s, x) -> concat(s, s), \"ab\")"),
+        ErrorKind::StringTooLong
+    );
+    assert_eq!(
+        error("reduce([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20], (l, x) -> [l, l], [])"),
+        ErrorKind::TooManyElements
+    );
+    assert_eq!(
+        eval_with_limits(&Untrusted, "len(identity(256))", &limits),
+        Ok(Evaluated::Decimal(Decimal::from(256)))
+    );
+
+    // Matrix functions count a step for each multiplication, so large matrices fail before the work is done.
+    assert_eq!(error("inverse(identity(250))"), ErrorKind::StepLimit);
+    assert_eq!(error("det(identity(101))"), ErrorKind::StepLimit);
+    assert_eq!(error("identity(250) * identity(250)"), ErrorKind::StepLimit);
+    assert_eq!(
+        eval_with_limits(&Untrusted, "det(inverse(identity(50)))", &limits),
+        Ok(Evaluated::Decimal(Decimal::ONE))
+    );
+}
+
+#[test]
+fn parse_limits() {
+    let error = Node::parse_with_limits("(((((1)))))", &LIMITS).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingTooDeep);
+
+    let input = "1 + ".repeat(20);
+    let (nodes, errors) = Node::parse_recovering_with_limits(&input, &LIMITS);
+    assert!(nodes.is_empty());
+    assert_eq!(
+        errors.iter().map(|error| error.kind).collect::<Vec<_>>(),
+        [ErrorKind::InputTooLong]
+    );
+}
+
+#[test]
+fn session_limits() {
+    let mut session = Session::new(&Untrusted).with_limits(LIMITS);
+    assert_eq!(session.eval("f(x) = f(x)").map(|_| ()), Ok(()));
+    assert_eq!(session.eval("f(1)").unwrap_err().kind, ErrorKind::StepLimit);
+
+    // The step budget is reset by each evaluation.
+    assert_eq!(
+        session.eval("sum(i, 1, 10, i)"),
+        Ok(Some(Evaluated::Decimal(Decimal::from(55))))
+    );
+    assert_eq!(
+        session.eval("21!").unwrap_err().kind,
+        ErrorKind::FactorialTooLarge
+    );
+}
@@ -0,0 +1,87 @@
+//! Lists, indexing and aggregate functions
+
+use equation_eval::{eval, resolve::Resolver, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Data;
+
+impl Resolver for Data {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "v" => Some(list(&[2, 4, 4, 4, 5, 5, 7, 9])),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+fn number(value: i64) -> Evaluated {
+    Evaluated::Decimal(Decimal::from(value))
+}
+
+fn list(values: &[i64]) -> Evaluated {
+    Evaluated::List(values.iter().copied().map(number).collect())
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Data, $input), Ok($expected));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Data, $input).unwrap_err().kind, $expected);
+        }
+    };
+}
+
+generate_test!(literal: "[1, 2, 3]", list(&[1, 2, 3]));
+generate_test!(empty: "[]", list(&[]));
+generate_test!(nested: "[[1], []]", Evaluated::List(vec![list(&[1]), list(&[])]));
+generate_test!(mixed: "[1, 'a']", Evaluated::List(vec![number(1), Evaluated::Str("a".into())]));
+generate_test!(index: "[1, 2, 3][1]", number(2));
+generate_test!(index_expression: "v[1 + 1] * 2", number(8));
+generate_test!(index_nested: "[[1, 2], [3, 4]][1][0]", number(3));
+generate_test!(add_lists: "[1, 2] + [10, 20]", list(&[11, 22]));
+generate_test!(broadcast_rhs: "[1, 2, 3] * 2", list(&[2, 4, 6]));
+generate_test!(broadcast_lhs: "10 - [1, 2]", list(&[9, 8]));
+generate_test!(negate: "-[1, -2]", list(&[-1, 2]));
+generate_test!(equal: "[1, 2] = [1, 2]", Evaluated::Bool(true));
+generate_test!(concat: "concat([1, 2])", Evaluated::Str("[1, 2]".into()));
+
+generate_test!(sum_list: "sum(v)", number(40));
+generate_test!(sum_variadic: "sum(1, 2, 3)", number(6));
+generate_test!(sum_empty: "sum([])", number(0));
+generate_test!(avg: "avg(v)", number(5));
+generate_test!(min: "min(v)", number(2));
+generate_test!(max: "max(3, 9, 1)", number(9));
+generate_test!(len_list: "len(v)", number(8));
+generate_test!(len_variadic: "len(1, 2)", number(2));
+generate_test!(median_even: "median(v)", Evaluated::Decimal(Decimal::new(45, 1)));
+generate_test!(median_odd: "median(3, 1, 2)", number(2));
+generate_test!(stddev: "stddev(v)", number(2));
+
+generate_error!(out_of_range: "[1, 2][2]", ErrorKind::OutOfRange);
+generate_error!(negative_index: "v[-1]", ErrorKind::InvalidArgument);
+generate_error!(index_number: "1[0]", ErrorKind::TypeMismatch { expected: Type::List, found: Type::Decimal });
+generate_error!(length_mismatch: "[1, 2] + [1]", ErrorKind::LengthMismatch { expected: 2, found: 1 });
+generate_error!(add_bool_element: "[1, true] + 1", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Bool });
+generate_error!(avg_empty: "avg([])", ErrorKind::InvalidArgument);
+generate_error!(sum_strings: "sum('a', 'b')", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Str });
+generate_error!(trailing_comma: "[1, 2,]", ErrorKind::UnexpectedToken);
+
+#[test]
+fn element_error_span() {
+    let err = eval(&Data, "[1, 2] / [1, 0]").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::DivideByZero);
+    assert_eq!((err.span.start, err.span.end), (0, 15));
+}
@@ -0,0 +1,79 @@
+//! Boolean values, logical operators and conditionals
+
+use equation_eval::{eval, resolve::Resolver, ErrorKind, Evaluated};
+use rust_decimal::Decimal;
+
+struct Flags;
+
+impl Resolver for Flags {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "age" => Some(Evaluated::Decimal(Decimal::from(21))),
+            "country" => Some(Evaluated::Decimal(Decimal::ONE)),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match name {
+            // Used to check that short-circuited operands are never evaluated.
+            "fail" => Err(ErrorKind::Undefined),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Flags, $input), Ok($expected));
+        }
+    };
+}
+
+generate_test!(literal_true: "true", Evaluated::Bool(true));
+generate_test!(literal_false: "false", Evaluated::Bool(false));
+generate_test!(not: "!true", Evaluated::Bool(false));
+generate_test!(double_not: "!!true", Evaluated::Bool(true));
+generate_test!(and: "true && false", Evaluated::Bool(false));
+generate_test!(or: "false || true", Evaluated::Bool(true));
+generate_test!(and_binds_tighter_than_or: "true || false && false", Evaluated::Bool(true));
+generate_test!(not_equal: "1 != 2", Evaluated::Bool(true));
+generate_test!(greater_or_equal: "2 >= 2", Evaluated::Bool(true));
+generate_test!(less: "2 < 2", Evaluated::Bool(false));
+generate_test!(feature_flag: "age >= 18 && country = 1", Evaluated::Bool(true));
+generate_test!(bool_equality: "(1 < 2) = true", Evaluated::Bool(true));
+generate_test!(if_then: "if(1 < 2, 10, 20)", Evaluated::Decimal(Decimal::TEN));
+generate_test!(if_else: "if(1 > 2, 10, 20)", Evaluated::Decimal(Decimal::from(20)));
+generate_test!(nested_if: "if(false, 1, if(true, 2, 3)) + 1", Evaluated::Decimal(Decimal::from(3)));
+generate_test!(factorial_not_equal: "3! != 6", Evaluated::Bool(false));
+
+// Short-circuiting skips evaluation of the right hand side.
+generate_test!(and_short_circuit: "false && fail()", Evaluated::Bool(false));
+generate_test!(or_short_circuit: "true || fail()", Evaluated::Bool(true));
+generate_test!(if_short_circuit: "if(true, 1, fail())", Evaluated::Decimal(Decimal::ONE));
+
+#[test]
+fn and_evaluates_rhs() {
+    let err = eval(&Flags, "true && fail()").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Undefined);
+}
+
+#[test]
+fn if_argument_count() {
+    let err = eval(&Flags, "if(true, 1)").unwrap_err();
+    assert_eq!(
+        err.kind,
+        ErrorKind::ArgumentCount {
+            expected: 3,
+            found: 2
+        }
+    );
+}
+
+#[test]
+fn single_amp() {
+    let err = eval(&Flags, "true & false").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnexpectedToken);
+}
@@ -0,0 +1,104 @@
+//! Matrix literals and linear algebra
+
+use equation_eval::{eval, matrix::Matrix, resolve::Resolver, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Transforms;
+
+impl Resolver for Transforms {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "rot" => Some(matrix(2, 2, &[0, -1, 1, 0])),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+fn matrix(rows: usize, cols: usize, values: &[i64]) -> Evaluated {
+    let data = values.iter().copied().map(Decimal::from).collect();
+    Evaluated::Matrix(Matrix::new(rows, cols, data).unwrap())
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Transforms, $input), Ok($expected));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Transforms, $input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(literal: "[[1, 2], [3, 4]]", matrix(2, 2, &[1, 2, 3, 4]));
+generate_test!(row_vector: "[[1, 2, 3]]", matrix(1, 3, &[1, 2, 3]));
+generate_test!(multiply: "[[1, 2], [3, 4]] * [[5, 6], [7, 8]]", matrix(2, 2, &[19, 22, 43, 50]));
+generate_test!(multiply_vector: "rot * [[1], [0]]", matrix(2, 1, &[0, 1]));
+generate_test!(add: "[[1, 2], [3, 4]] + [[1, 1], [1, 1]]", matrix(2, 2, &[2, 3, 4, 5]));
+generate_test!(scale: "2 * [[1, 2], [3, 4]]", matrix(2, 2, &[2, 4, 6, 8]));
+generate_test!(negate: "-rot", matrix(2, 2, &[0, 1, -1, 0]));
+generate_test!(transpose: "transpose([[1, 2, 3], [4, 5, 6]])", matrix(3, 2, &[1, 4, 2, 5, 3, 6]));
+generate_test!(det: "det([[1, 2], [3, 4]])", Evaluated::Decimal(Decimal::from(-2)));
+generate_test!(det_pivot: "det([[0, 1, 2], [1, 0, 3], [4, -3, 8]])", Evaluated::Decimal(Decimal::from(-2)));
+generate_test!(det_singular: "det([[1, 2], [2, 4]])", Evaluated::Decimal(Decimal::ZERO));
+generate_test!(identity: "identity(2)", matrix(2, 2, &[1, 0, 0, 1]));
+generate_test!(inverse_identity: "inverse(rot) * rot = identity(2)", Evaluated::Bool(true));
+generate_test!(inverse_pivot: "inverse([[0, 1, 2], [1, 0, 3], [4, -3, 8]]) * [[0, 1, 2], [1, 0, 3], [4, -3, 8]] = identity(3)", Evaluated::Bool(true));
+generate_test!(inverse_large: "det(inverse(identity(100)))", Evaluated::Decimal(Decimal::ONE));
+generate_test!(row: "[[1, 2], [3, 4]][1]", Evaluated::List(vec![Evaluated::Decimal(Decimal::from(3)), Evaluated::Decimal(Decimal::from(4))]));
+
+#[test]
+fn inverse() {
+    let Ok(Evaluated::Matrix(inverse)) = eval(&Transforms, "inverse([[1, 2], [3, 4]])") else {
+        panic!("expected a matrix");
+    };
+
+    assert_eq!(inverse.get(0, 0), Some(Decimal::from(-2)));
+    assert_eq!(inverse.get(0, 1), Some(Decimal::ONE));
+    assert_eq!(inverse.get(1, 0), Some(Decimal::new(15, 1)));
+    assert_eq!(inverse.get(1, 1), Some(Decimal::new(-5, 1)));
+}
+
+// Shape errors point at the right hand operand.
+generate_error!(
+    multiply_shape: "[[1, 2, 3]] * [[1, 2]]",
+    ErrorKind::ShapeMismatch { expected: (3, 2), found: (1, 2) },
+    14,
+    22
+);
+generate_error!(
+    add_shape: "rot + [[1, 2, 3]]",
+    ErrorKind::ShapeMismatch { expected: (2, 2), found: (1, 3) },
+    6,
+    17
+);
+generate_error!(
+    det_not_square: "det([[1, 2]])",
+    ErrorKind::ShapeMismatch { expected: (1, 1), found: (1, 2) },
+    0,
+    13
+);
+generate_error!(identity_zero: "identity(0)", ErrorKind::InvalidArgument, 0, 11);
+generate_error!(identity_too_large: "identity(30000)", ErrorKind::InvalidArgument, 0, 15);
+generate_error!(singular: "inverse([[1, 2], [2, 4]])", ErrorKind::SingularMatrix, 0, 25);
+generate_error!(singular_column: "inverse([[0, 1], [0, 2]])", ErrorKind::SingularMatrix, 0, 25);
+generate_error!(
+    add_list: "rot + [1, 2]",
+    ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::List },
+    6,
+    12
+);
+generate_error!(transpose_number: "transpose(1)", ErrorKind::TypeMismatch { expected: Type::Matrix, found: Type::Decimal }, 0, 12);
@@ -0,0 +1,75 @@
+//! Numeric integration, root-finding and lazy arguments
+
+use equation_eval::{eval, resolve::Resolver, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Physics;
+
+impl Resolver for Physics {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "g" => Some(Evaluated::Decimal(Decimal::new(981, 2))),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            // Applies a lazily evaluated expression twice.
+            ("twice", [Evaluated::Lambda(f), x]) => f
+                .call(self, std::slice::from_ref(x))
+                .and_then(|x| f.call(self, &[x]))
+                .map_err(|err| err.kind),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+
+    fn max_iterations(&self) -> usize {
+        10_000
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let expected = Evaluated::Decimal($expected.parse().unwrap());
+            assert_eq!(eval(&Physics, $input), Ok(expected));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Physics, $input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(integrate_square: "integrate(x^2, x, 0, 1)", "0.3333333333");
+generate_test!(integrate_cubic: "integrate(t^3 - t, t, -1, 2)", "2.25");
+generate_test!(integrate_reversed: "integrate(x, x, 1, 0)", "-0.5");
+generate_test!(integrate_constant: "integrate(g, t, 0, 2)", "19.62");
+generate_test!(integrate_reciprocal: "integrate(1 / x, x, 1, 3)", "1.0986122887");
+generate_test!(integrate_conditional: "integrate(if(x < 1, 0, 1), x, 0, 2)", "1");
+generate_test!(integrate_lambda: "integrate(x -> 2 * x, 0, 3)", "9");
+generate_test!(root_sqrt: "root(x^2 - 2, x, 1)", "1.4142135624");
+generate_test!(root_linear: "root(3 * y - 6, y, 0)", "2");
+generate_test!(root_negative: "root(x^3 + 8, x, -1)", "-2");
+generate_test!(root_shadows_constant: "root(g - 4, g, 0)", "4");
+generate_test!(lazy_resolver: "twice(x -> x * 3, 2)", "18");
+
+generate_error!(root_no_solution: "root(x^2 + 1, x, 0)", ErrorKind::NoConvergence, 0, 19);
+generate_error!(integrate_limit: "integrate(1 / x, x, 0.000001, 1)", ErrorKind::IterationLimit, 0, 32);
+generate_error!(integrate_bound: "integrate(x, x, 0, true)", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Bool }, 0, 24);
+generate_error!(integrate_body: "integrate(x > 1, x, 0, 2)", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Bool }, 10, 15);
+generate_error!(integrate_body_error: "integrate(1 / x, x, 0, 1)", ErrorKind::DivideByZero, 10, 15);
+generate_error!(integrate_arity: "integrate(1, 0)", ErrorKind::ArgumentCount { expected: 4, found: 2 }, 0, 15);
+generate_error!(root_arity: "root(1)", ErrorKind::ArgumentCount { expected: 3, found: 1 }, 0, 7);
+generate_error!(integrate_variable: "integrate(x^2, 2 * x, 0, 1)", ErrorKind::InvalidArgument, 15, 20);
+generate_error!(root_variable: "root(x^2, 1, 0)", ErrorKind::InvalidArgument, 10, 11);
+generate_error!(integrate_not_lambda: "integrate(1, 0, 1)", ErrorKind::TypeMismatch { expected: Type::Lambda, found: Type::Decimal }, 0, 18);
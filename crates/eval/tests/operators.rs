@@ -0,0 +1,271 @@
+//! Parsing with an `OperatorTable` other than the standard table, and custom infix operators.
+
+use equation_eval::{
+    eval, eval_with_operators, eval_with_operators_and_limits,
+    export::latex,
+    limits::EvalLimits,
+    node::{Node, Spanned},
+    operators::{precedence, Arity, Associativity, OperatorTable, Symbol},
+    resolve::Resolver,
+    session::Session,
+    ErrorKind, Evaluated,
+};
+use equation_lexer::{Kind, TokenKind};
+use rust_decimal::Decimal;
+
+struct Words;
+
+impl Resolver for Words {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "x" => Some(Evaluated::Decimal(Decimal::TWO)),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("mod", [Evaluated::Decimal(lhs), Evaluated::Decimal(rhs)]) => {
+                Ok(Evaluated::Decimal(lhs % rhs))
+            }
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+
+    fn resolve_operator(
+        &self,
+        name: &str,
+        lhs: &Evaluated,
+        rhs: &Evaluated,
+    ) -> Result<Evaluated, ErrorKind> {
+        match (name, lhs, rhs) {
+            (_, Evaluated::Decimal(_), Evaluated::Decimal(rhs)) if rhs.is_zero() => {
+                Err(ErrorKind::DivideByZero)
+            }
+            ("mod", Evaluated::Decimal(lhs), Evaluated::Decimal(rhs)) => {
+                Ok(Evaluated::Decimal(lhs % rhs))
+            }
+            ("div", Evaluated::Decimal(lhs), Evaluated::Decimal(rhs)) => {
+                Ok(Evaluated::Decimal((lhs / rhs).floor()))
+            }
+            ("xor", Evaluated::Bool(lhs), Evaluated::Bool(rhs)) => Ok(Evaluated::Bool(lhs != rhs)),
+            _ => Err(ErrorKind::UnknownOperator),
+        }
+    }
+}
+
+/// The standard table with `mod`, `div` and `xor`.
+fn words() -> OperatorTable {
+    let mut table = OperatorTable::STANDARD;
+    table.custom("mod", precedence::PRODUCT, Associativity::Left);
+    table.custom("div", precedence::PRODUCT, Associativity::Left);
+    table.custom("xor", precedence::OR, Associativity::Left);
+    table
+}
+
+/// A table evaluating `-2^2` as `(-2)^2` and `2^3^2` as `(2^3)^2`.
+fn spreadsheet() -> OperatorTable {
+    let mut table = OperatorTable::STANDARD;
+    table
+        .get_mut(&Symbol::tokens(&[Kind![-]]), Arity::Prefix)
+        .unwrap()
+        .precedence = precedence::POSTFIX;
+    table
+        .get_mut(&Symbol::tokens(&[Kind![^]]), Arity::Infix)
+        .unwrap()
+        .associativity = Associativity::Left;
+    table
+}
+
+/// Check an expression evaluates to the same value with the table as a standard expression.
+macro_rules! generate_test {
+    ($name: ident: $table: expr, $input: expr, $standard: expr) => {
+        #[test]
+        fn $name() {
+            let value = eval_with_operators(&Words, $input, &$table);
+
+            assert!(value.is_ok(), "{:?}", value);
+            assert_eq!(value, eval(&Words, $standard));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $table: expr, $input: expr, $kind: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let error = eval_with_operators(&Words, $input, &$table).unwrap_err();
+
+            assert_eq!(error.kind, $kind);
+            assert_eq!((error.span.start, error.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(standard_negated_power: OperatorTable::STANDARD, "-2^2", "-4");
+generate_test!(standard_power_chain: OperatorTable::STANDARD, "2^3^2", "512");
+generate_test!(standard_everything: OperatorTable::default(), "1 + 2 * 3! - 4 % 3 >= 2 && !false", "true");
+generate_test!(spreadsheet_negated_power: spreadsheet(), "-2^2", "4");
+generate_test!(spreadsheet_power_chain: spreadsheet(), "2^3^2", "64");
+generate_test!(spreadsheet_negated_factorial: spreadsheet(), "-3!", "-6");
+generate_test!(modulo: words(), "7 mod 4", "3");
+generate_test!(divide: words(), "7 div 2", "3");
+generate_test!(product_precedence: words(), "1 + 7 mod 4 * 2", "1 + 6");
+generate_test!(left_associative: words(), "100 div 7 div 2", "7");
+generate_test!(operands: words(), "(x + 5) mod -x", "1");
+generate_test!(xor: words(), "1 < 2 xor x = 2", "false");
+generate_test!(xor_precedence: words(), "true && false xor true", "true");
+generate_test!(function_call: words(), "mod(7, 2) + 1", "2");
+generate_test!(not_equal: words(), "3! != 6", "false");
+generate_test!(lambda_body: words(), "map([true, false], x -> x xor true)", "[false, true]");
+
+generate_error!(word_in_standard_table: OperatorTable::STANDARD, "7 mod 2", ErrorKind::UnexpectedToken, 2, 5);
+generate_error!(unknown_operands: words(), "true mod 2", ErrorKind::UnknownOperator, 0, 10);
+generate_error!(operator_error: words(), "1 + 7 div (x - 2)", ErrorKind::DivideByZero, 4, 17);
+generate_error!(missing_rhs: words(), "7 mod", ErrorKind::UnexpectedEnd, 5, 5);
+
+#[test]
+fn default_resolver() {
+    struct Plain;
+
+    impl Resolver for Plain {
+        fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+            None
+        }
+
+        fn resolve_function(
+            &self,
+            _name: &str,
+            _args: &[Evaluated],
+        ) -> Result<Evaluated, ErrorKind> {
+            Err(ErrorKind::UnknownFunction)
+        }
+    }
+
+    // Only resolvers implementing `resolve_operator` evaluate custom operators.
+    let error = eval_with_operators(&Plain, "2 mod 1", &words()).unwrap_err();
+
+    assert_eq!(error.kind, ErrorKind::UnknownOperator);
+    assert_eq!((error.span.start, error.span.end), (0, 7));
+}
+
+#[test]
+fn session() {
+    let mut session = Session::new(&Words).with_operators(words());
+
+    assert_eq!(
+        session.eval("f(y) = y mod 4; a = f(7) div 2; a"),
+        Ok(Some(Evaluated::Decimal(Decimal::ONE)))
+    );
+    assert_eq!(
+        session.eval("g(b) = map([b], c -> c xor b); g(true)"),
+        Ok(Some(Evaluated::List(vec![Evaluated::Bool(false)])))
+    );
+
+    let error = Session::new(&Words).eval("7 mod 4").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::UnexpectedToken);
+}
+
+#[test]
+fn right_associative() {
+    let mut table = OperatorTable::STANDARD;
+    table.custom("div", precedence::PRODUCT, Associativity::Right);
+
+    assert_eq!(
+        eval_with_operators(&Words, "100 div 7 div 2", &table),
+        eval(&Words, "33")
+    );
+}
+
+#[test]
+fn insert_and_remove() {
+    let mut table = words();
+    let factorial = Symbol::tokens(&[TokenKind::Exclamation]);
+
+    assert!(table.get(&Symbol::word("mod"), Arity::Infix).is_some());
+    assert!(table.get(&Symbol::word("mod"), Arity::Prefix).is_none());
+
+    // Replacing an operator returns the previous entry.
+    let previous = table
+        .custom("mod", precedence::SUM, Associativity::Left)
+        .unwrap();
+    assert_eq!(previous.precedence, precedence::PRODUCT);
+    assert_eq!(
+        eval_with_operators(&Words, "1 + 7 mod 4", &table),
+        eval(&Words, "0")
+    );
+
+    assert!(table.remove(&Symbol::word("mod"), Arity::Infix).is_some());
+    assert!(table.remove(&Symbol::word("mod"), Arity::Infix).is_none());
+    assert_eq!(
+        eval_with_operators(&Words, "7 mod 2", &table)
+            .unwrap_err()
+            .kind,
+        ErrorKind::UnexpectedToken
+    );
+
+    // Without the factorial, `!` is only a prefix operator.
+    table.remove(&factorial, Arity::Postfix).unwrap();
+    assert_eq!(
+        eval_with_operators(&Words, "!false", &table),
+        Ok(Evaluated::Bool(true))
+    );
+    assert_eq!(
+        eval_with_operators(&Words, "3!", &table).unwrap_err().kind,
+        ErrorKind::UnexpectedToken
+    );
+}
+
+#[test]
+fn spans() {
+    let spans = Node::parse_with_operators("x mod 3 + 1", &words())
+        .unwrap()
+        .map(|node| {
+            let span = node.span();
+            (span.start, span.end)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(spans, [(0, 1), (6, 7), (0, 7), (10, 11), (0, 11)]);
+}
+
+#[test]
+fn custom_node() {
+    let nodes = Node::parse_with_operators("x xor true", &words())
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    match &nodes[2] {
+        Node::Custom(custom) => assert_eq!(custom.name, "xor"),
+        node => panic!("expected a custom operator, found {:?}", node),
+    }
+}
+
+#[test]
+fn export_custom() {
+    let nodes = Node::parse_with_operators("(x + 1) mod 3", &words())
+        .unwrap()
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        latex(&nodes).unwrap(),
+        "\\left(x + 1\\right) \\mathbin{\\mathrm{mod}} 3"
+    );
+}
+
+#[test]
+fn limits() {
+    let limits = EvalLimits {
+        max_nesting: 2,
+        ..EvalLimits::default()
+    };
+
+    assert_eq!(
+        eval_with_operators_and_limits(&Words, "7 mod (2 + 2)", &words(), &limits),
+        eval(&Words, "3")
+    );
+
+    let error =
+        eval_with_operators_and_limits(&Words, "((((7)))) mod 2", &words(), &limits).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::NestingTooDeep);
+}
@@ -0,0 +1,92 @@
+//! Programs of multiple statements with assignments
+
+use equation_eval::{resolve::Resolver, session::Session, ErrorKind, Evaluated};
+
+struct Config;
+
+impl Resolver for Config {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "currency" => Some(Evaluated::Str("EUR".into())),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+fn decimal(str: &str) -> Evaluated {
+    Evaluated::Decimal(str.parse().unwrap())
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let mut session = Session::new(&Config);
+            assert_eq!(session.eval($input), Ok(Some($expected)));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let mut session = Session::new(&Config);
+            let err = session.eval($input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(config: "rate = 0.07; base = 1200; base * (1 + rate)", decimal("1284.00"));
+generate_test!(newlines: "rate = 0.07\nbase = 1200\nbase * (1 + rate)", decimal("1284.00"));
+generate_test!(crlf: "a = 1\r\n\r\nb = 2\r\na + b", decimal("3"));
+generate_test!(walrus: "x := 4; x * x", decimal("16"));
+generate_test!(assignment_value: "x = 4", decimal("4"));
+generate_test!(reassign: "x = 1; x = x + 1; x", decimal("2"));
+generate_test!(equality_in_expression: "x = 2; x = 2", decimal("2"));
+generate_test!(equality_in_braces: "x = 2; (x = 2)", Evaluated::Bool(true));
+generate_test!(walrus_expression: "x := 2 = 2", Evaluated::Bool(true));
+generate_test!(newline_in_braces: "total = (1\n+ 2)\ntotal", decimal("3"));
+generate_test!(newline_in_call: "max(1,\n  5)", decimal("5"));
+generate_test!(with_function: "scale = 3\nf(x) = x * scale\nf(2)", decimal("6"));
+generate_test!(list_variable: "xs = [1, 2, 3]; sum(xs)", decimal("6"));
+generate_test!(uses_resolver: "label = concat(currency, \" \", 5); label", Evaluated::Str("EUR 5".into()));
+
+generate_error!(assign_resolver_constant: "currency = 1", ErrorKind::Redefinition, 0, 8);
+generate_error!(assign_reserved: "true := 1", ErrorKind::Redefinition, 0, 4);
+generate_error!(missing_value: "x =", ErrorKind::UnexpectedEnd, 3, 3);
+generate_error!(separated_colon: "x : = 1", ErrorKind::UnexpectedToken, 2, 3);
+generate_error!(undefined_variable: "a = 1\nb", ErrorKind::UnknownConstant, 6, 7);
+
+#[test]
+fn retains_bindings() {
+    let mut session = Session::new(&Config);
+    assert_eq!(session.eval("rate = 0.5"), Ok(Some(decimal("0.5"))));
+    assert_eq!(session.eval("10 * rate"), Ok(Some(decimal("5.0"))));
+    assert_eq!(session.variable("rate"), Some(&decimal("0.5")));
+}
+
+#[test]
+fn failed_statement_keeps_earlier_bindings() {
+    let mut session = Session::new(&Config);
+    assert!(session.eval("a = 1; b = a / 0; c = 3").is_err());
+
+    let names = session
+        .variables()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["a"]);
+}
+
+#[test]
+fn empty_program() {
+    let mut session = Session::new(&Config);
+    assert_eq!(session.eval(""), Ok(None));
+    assert_eq!(session.eval(";\n;"), Ok(None));
+}
@@ -0,0 +1,99 @@
+//! Reporting every error in an expression
+
+use equation_eval::{eval_nodes, node::Node, resolve::Resolver, ErrorKind, Evaluated};
+use rust_decimal::Decimal;
+
+struct Functions;
+
+impl Resolver for Functions {
+    fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+        None
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("foo", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(*value)),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+macro_rules! generate_errors {
+    ($name: ident: $input: expr, [$(($expected: expr, $start: expr, $end: expr)),* $(,)?]) => {
+        #[test]
+        fn $name() {
+            let (_, errors) = Node::parse_recovering($input);
+            let errors = errors
+                .iter()
+                .map(|err| (err.kind, err.span.start, err.span.end))
+                .collect::<Vec<_>>();
+
+            assert_eq!(errors, [$(($expected, $start, $end)),*]);
+        }
+    };
+}
+
+generate_errors!(no_errors: "1 + foo(2)", []);
+generate_errors!(missing_operand: "2 + * 3", [(ErrorKind::UnexpectedToken, 4, 5)]);
+generate_errors!(all_errors: "2 + * 3 ) + foo(", [
+    (ErrorKind::UnexpectedToken, 4, 5),
+    (ErrorKind::UnexpectedToken, 8, 9),
+    (ErrorKind::UnexpectedEnd, 16, 16),
+]);
+generate_errors!(between_arguments: "foo(1 2, 3 $, 4)", [
+    (ErrorKind::UnexpectedToken, 6, 7),
+    (ErrorKind::InvalidToken, 11, 12),
+]);
+generate_errors!(empty_arguments: "foo(, 1, )", [
+    (ErrorKind::UnexpectedToken, 4, 5),
+    (ErrorKind::UnexpectedToken, 9, 10),
+]);
+generate_errors!(mismatched_brace: "[1, (2 + 3] + 4", [(ErrorKind::UnexpectedToken, 10, 11)]);
+generate_errors!(unclosed_brace: "(1 + 2", [(ErrorKind::UnexpectedEnd, 6, 6)]);
+generate_errors!(unmatched_brace: "1 + 2) * 3]", [
+    (ErrorKind::UnexpectedToken, 5, 6),
+    (ErrorKind::UnexpectedToken, 10, 11),
+]);
+generate_errors!(literals: "1.2.3 + \"\\q\" + \"open", [
+    (ErrorKind::InvalidNumber, 0, 5),
+    (ErrorKind::InvalidEscape, 9, 11),
+    (ErrorKind::UnterminatedString, 15, 20),
+]);
+generate_errors!(conditional: "if(true, 1) + if(false)", [
+    (ErrorKind::ArgumentCount { expected: 3, found: 2 }, 0, 11),
+    (ErrorKind::ArgumentCount { expected: 3, found: 1 }, 14, 23),
+]);
+generate_errors!(lambda_params: "(x, x) -> x + * 1", [
+    (ErrorKind::Redefinition, 4, 5),
+    (ErrorKind::UnexpectedToken, 14, 15),
+]);
+
+#[test]
+fn same_nodes_without_errors() {
+    let input = "foo(1 + 2) * [3, 4][0] && !(1 < 2) || if(true, 1, 2) = 1";
+    let (nodes, errors) = Node::parse_recovering(input);
+
+    assert!(errors.is_empty());
+    assert_eq!(nodes.len(), Node::parse(input).unwrap().len(),);
+}
+
+#[test]
+fn error_nodes() {
+    let (nodes, _) = Node::parse_recovering("foo(1) + * 3");
+    let err = eval_nodes(&Functions, nodes.into_iter()).unwrap_err();
+
+    assert_eq!(err.kind, ErrorKind::UnexpectedToken);
+    assert_eq!((err.span.start, err.span.end), (9, 10));
+}
+
+#[test]
+fn partial_tree() {
+    // The stray brace is skipped, the rest of the expression is still parsed.
+    let (nodes, errors) = Node::parse_recovering("foo(2) ) + 3");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        eval_nodes(&Functions, nodes.into_iter()),
+        Ok(Evaluated::Decimal(Decimal::from(5)))
+    );
+}
@@ -0,0 +1,200 @@
+//! Serializing nodes, values and errors with the `serde` feature.
+
+#![cfg(feature = "serde")]
+
+use equation_eval::{
+    eval, eval_nodes, matrix::Matrix, node::Node, resolve::Resolver, ErrorKind, Evaluated,
+};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+struct Sensor {
+    /// A lambda received from another process.
+    scale: Option<Evaluated>,
+}
+
+impl Resolver for Sensor {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "reading" => Some(Evaluated::Decimal(Decimal::new(25, 1))),
+            "scale" => self.scale.clone(),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("double", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(value * Decimal::TWO)),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+const SENSOR: Sensor = Sensor { scale: None };
+
+/// Serialize the nodes of an expression, deserialize them and check they evaluate to the same value.
+macro_rules! generate_nodes {
+    ($name: ident: $input: expr) => {
+        #[test]
+        fn $name() {
+            let nodes = Node::parse($input).unwrap().collect::<Vec<_>>();
+            let json = serde_json::to_string(&nodes).unwrap();
+            let decoded = serde_json::from_str::<Vec<Node<'static>>>(&json).unwrap();
+
+            assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+            assert_eq!(
+                eval_nodes(&SENSOR, decoded.into_iter()),
+                eval(&SENSOR, $input)
+            );
+        }
+    };
+}
+
+/// Check the JSON of a value and that it deserializes to the same value.
+macro_rules! generate_value {
+    ($name: ident: $value: expr, $json: expr) => {
+        #[test]
+        fn $name() {
+            let value: Evaluated = $value;
+            let json = serde_json::to_value(&value).unwrap();
+
+            assert_eq!(json, $json);
+            assert_eq!(serde_json::from_value::<Evaluated>(json).unwrap(), value);
+        }
+    };
+}
+
+generate_nodes!(arithmetic: "1.5 + 2 * -reading ^ 2");
+generate_nodes!(functions: "double(reading) + len([1])");
+generate_nodes!(strings: "concat('a\\'b', upper(\"c\"))");
+generate_nodes!(logical: "if(reading >= 2 && !false, 1, 0)");
+generate_nodes!(lists: "[1, 2, 3][1] + sum([4, 5])");
+generate_nodes!(lambdas: "map([1, 2], x -> x * reading)");
+
+generate_value!(decimal: Evaluated::Decimal(Decimal::new(15, 1)), json!({ "decimal": "1.5" }));
+generate_value!(bool: Evaluated::Bool(true), json!({ "bool": true }));
+generate_value!(string: Evaluated::Str("a\"b".into()), json!({ "str": "a\"b" }));
+generate_value!(
+    list: Evaluated::List(vec![Evaluated::Bool(false), Evaluated::Str("x".into())]),
+    json!({ "list": [{ "bool": false }, { "str": "x" }] })
+);
+generate_value!(
+    matrix: Evaluated::Matrix(Matrix::new(2, 1, vec![Decimal::ONE, Decimal::TWO]).unwrap()),
+    json!({ "matrix": { "rows": 2, "cols": 1, "data": ["1", "2"] } })
+);
+
+#[test]
+fn node_shape() {
+    let nodes = Node::parse("1.5 + sqrt(x)").unwrap().collect::<Vec<_>>();
+
+    assert_eq!(
+        serde_json::to_value(&nodes).unwrap(),
+        json!([
+            { "unary": { "kind": { "decimal": "1.5" }, "span": { "start": 0, "end": 3 } } },
+            { "unary": { "kind": { "constant": "x" }, "span": { "start": 11, "end": 12 } } },
+            { "function": { "name": "sqrt", "args": 1, "span": { "start": 6, "end": 13 } } },
+            { "operator": { "kind": "add", "span": { "start": 0, "end": 13 } } },
+        ])
+    );
+}
+
+#[test]
+fn precision() {
+    let value = eval(&SENSOR, "0.1 + 0.2").unwrap();
+    let json = serde_json::to_string(&value).unwrap();
+
+    assert_eq!(json, r#"{"decimal":"0.3"}"#);
+    assert_eq!(
+        serde_json::from_str::<Evaluated>(&json).unwrap(),
+        Evaluated::Decimal(Decimal::new(3, 1))
+    );
+}
+
+#[test]
+fn lambda() {
+    let closure = eval(&SENSOR, "x -> x * reading").unwrap();
+    let json = serde_json::to_value(&closure).unwrap();
+
+    assert_eq!(json["lambda"]["params"], json!(["x"]));
+    assert_eq!(
+        json["lambda"]["captured"],
+        json!([["reading", { "decimal": "2.5" }]])
+    );
+
+    let sensor = Sensor {
+        scale: Some(serde_json::from_value(json).unwrap()),
+    };
+
+    assert_eq!(
+        eval(&sensor, "map([2, 4], scale)"),
+        eval(&SENSOR, "[5, 10]")
+    );
+}
+
+#[test]
+fn invalid_matrix() {
+    let json = json!({ "matrix": { "rows": 2, "cols": 2, "data": ["1", "2", "3"] } });
+    assert!(serde_json::from_value::<Evaluated>(json).is_err());
+
+    let json = json!({ "matrix": { "rows": 0, "cols": 1, "data": [] } });
+    assert!(serde_json::from_value::<Evaluated>(json).is_err());
+}
+
+#[test]
+fn error() {
+    let error = eval(&SENSOR, "if(true, 1)").unwrap_err();
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(
+        json,
+        json!({
+            "kind": { "argument_count": { "expected": 3, "found": 2 } },
+            "span": { "start": 0, "end": 11 },
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<equation_eval::Error>(json).unwrap(),
+        error
+    );
+}
+
+#[test]
+fn error_unit_kind() {
+    let error = eval(&SENSOR, "1 / 0").unwrap_err();
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(json["kind"], json!("divide_by_zero"));
+    assert_eq!(
+        serde_json::from_value::<equation_eval::Error>(json).unwrap(),
+        error
+    );
+}
+
+#[test]
+fn error_suggestions() {
+    let error = eval(&SENSOR, "lenn(\"abc\")").unwrap_err();
+    let json = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(json["suggestions"], json!(["len"]));
+    assert_eq!(
+        serde_json::from_value::<equation_eval::Error>(json).unwrap(),
+        error
+    );
+}
+
+#[test]
+fn recovered_errors() {
+    let (nodes, errors) = Node::parse_recovering("1 + ) * 2");
+    let json = serde_json::to_value(&nodes).unwrap();
+
+    assert!(
+        matches!(&json, Value::Array(nodes) if nodes.iter().any(|node| node.get("error").is_some()))
+    );
+    assert_eq!(
+        serde_json::from_value::<Vec<Node<'static>>>(json)
+            .unwrap()
+            .len(),
+        nodes.len()
+    );
+    assert!(!errors.is_empty());
+}
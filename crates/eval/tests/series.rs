@@ -0,0 +1,134 @@
+//! Summation and product notation over integer ranges
+
+use equation_eval::{eval, resolve::Resolver, session::Session, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Bounds;
+
+impl Resolver for Bounds {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "n" => Some(Evaluated::Decimal(Decimal::from(5))),
+            "m" => Some(Evaluated::Decimal(Decimal::from(1000))),
+            "b" => Some(Evaluated::Decimal(Decimal::TWO)),
+            "c" => Some(Evaluated::Decimal(Decimal::from(3))),
+            "d" => Some(Evaluated::Decimal(Decimal::from(4))),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+
+    fn max_iterations(&self) -> usize {
+        1000
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(
+                eval(&Bounds, $input),
+                Ok(Evaluated::Decimal(Decimal::from($expected)))
+            );
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Bounds, $input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(sum_squares: "sum(k, 1, 100, k^2)", 338350);
+generate_test!(prod_factorial: "prod(i, 1, n, i)", 120);
+generate_test!(bounds_expressions: "sum(i, n - 4, n * 2, 1)", 10);
+generate_test!(empty_sum: "sum(i, 5, 1, i)", 0);
+generate_test!(empty_prod: "prod(i, 5, 1, i)", 1);
+generate_test!(negative_range: "sum(i, -3, 3, i)", 0);
+generate_test!(shadows_constant: "prod(m, 1, 3, m) + m", 1006);
+generate_test!(nested: "sum(i, 1, 3, sum(j, 1, i, j))", 10);
+generate_test!(conditional_body: "sum(i, 1, 10, if(i % 2 = 0, i, 0))", 30);
+generate_test!(aggregate_sum: "sum(1, 2, 3, 4)", 10);
+generate_test!(aggregate_sum_names: "sum(n, m)", 1005);
+generate_test!(aggregate_sum_expression_first: "sum(b + 0, c, d, m)", 1009);
+generate_test!(series_four_names: "sum(a, b, c, d)", 8);
+generate_test!(series_shadows_constant: "sum(m, 1, 3, m)", 6);
+generate_test!(nested_same_name: "sum(i, 1, 3, sum(i, 1, 2, i))", 9);
+generate_test!(max_iterations: "sum(i, 1, 1000, 1)", 1000);
+
+generate_error!(iteration_limit: "sum(i, 1, 1001, 1)", ErrorKind::IterationLimit, 0, 18);
+generate_error!(nested_iteration_limit: "sum(i, 1, 999, sum(j, 1, 999, 1))", ErrorKind::IterationLimit, 15, 32);
+generate_error!(nested_numeric_iteration_limit: "sum(i, 1, 500, integrate(x, x, 0, i))", ErrorKind::IterationLimit, 15, 36);
+generate_error!(fractional_bound: "prod(i, 1, 2.5, i)", ErrorKind::InvalidArgument, 0, 18);
+generate_error!(bool_bound: "sum(i, true, 2, i)", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Bool }, 0, 18);
+generate_error!(body_type: "sum(i, 1, 2, i > 1)", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Bool }, 13, 18);
+generate_error!(body_error: "sum(i, 0, 2, 1 / i)", ErrorKind::DivideByZero, 13, 18);
+generate_error!(prod_arity: "prod(1, 2)", ErrorKind::ArgumentCount { expected: 4, found: 2 }, 0, 10);
+generate_error!(prod_variable: "prod(2 * i, 1, 3, i)", ErrorKind::InvalidArgument, 5, 10);
+
+#[test]
+fn session_limit() {
+    let mut session = Session::new(&Bounds).with_max_iterations(10);
+    assert_eq!(
+        session.eval("sum(i, 1, 11, i)").unwrap_err().kind,
+        ErrorKind::IterationLimit
+    );
+    assert_eq!(
+        session.eval("f(x) = prod(i, 1, x, 2); f(10)"),
+        Ok(Some(Evaluated::Decimal(Decimal::from(1024))))
+    );
+}
+
+#[test]
+fn session_variable_is_shadowed() {
+    let mut session = Session::new(&Bounds);
+    assert_eq!(
+        session.eval("k = 5; sum(k, 1, 10, k^2)"),
+        Ok(Some(Evaluated::Decimal(Decimal::from(385))))
+    );
+    assert_eq!(
+        session.eval("i = 1; sum(i, 1, 3, sum(i, 1, 2, i)) + i"),
+        Ok(Some(Evaluated::Decimal(Decimal::from(10))))
+    );
+}
+
+#[test]
+fn default_iteration_limit() {
+    struct Unbounded;
+
+    impl Resolver for Unbounded {
+        fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+            None
+        }
+
+        fn resolve_function(
+            &self,
+            _name: &str,
+            _args: &[Evaluated],
+        ) -> Result<Evaluated, ErrorKind> {
+            Err(ErrorKind::UnknownFunction)
+        }
+    }
+
+    // Each range is within the default limit, the iterations of both are counted together.
+    assert_eq!(
+        eval(&Unbounded, "sum(i, 1, 99999, sum(j, 1, 99999, 1))")
+            .unwrap_err()
+            .kind,
+        ErrorKind::IterationLimit
+    );
+    assert_eq!(
+        eval(&Unbounded, "sum(i, 1, 300, sum(j, 1, 300, 1))"),
+        Ok(Evaluated::Decimal(Decimal::from(90000)))
+    );
+}
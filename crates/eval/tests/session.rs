@@ -0,0 +1,145 @@
+//! User-defined functions in an evaluation session
+
+use equation_eval::{resolve::Resolver, session::Session, ErrorKind, Evaluated};
+use rust_decimal::Decimal;
+
+struct Prices;
+
+impl Resolver for Prices {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "base" => Some(Evaluated::Decimal(Decimal::from(100))),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("double", [Evaluated::Decimal(value)]) => Ok(Evaluated::Decimal(value * Decimal::TWO)),
+            ("double", _) => Err(ErrorKind::ArgumentCount {
+                expected: 1,
+                found: args.len(),
+            }),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+
+    fn has_function(&self, name: &str) -> bool {
+        name == "double"
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let mut session = Session::new(&Prices);
+            assert_eq!(
+                session.eval($input),
+                Ok(Some(Evaluated::Decimal(Decimal::from($expected))))
+            );
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let mut session = Session::new(&Prices);
+            let err = session.eval($input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(single: "f(x) = x^2 + 1; f(3) + f(4)", 27);
+generate_test!(expression_only: "1 + 2", 3);
+generate_test!(no_params: "answer() = 42; answer()", 42);
+generate_test!(two_params: "area(w, h) = w * h; area(3, 4)", 12);
+generate_test!(trailing_separator: "f(x) = x; f(2);", 2);
+generate_test!(uses_resolver: "discount(x) = base - x; discount(double(5))", 90);
+generate_test!(calls_other: "f(x) = x + 1; g(x) = f(x) * 2; g(1)", 4);
+generate_test!(defined_later: "g(x) = f(x) * 2; f(x) = x + 1; g(1)", 4);
+generate_test!(param_shadows_constant: "f(base) = base * 2; f(3)", 6);
+generate_test!(redefine: "f(x) = x; f(x) = x * 10; f(2)", 20);
+generate_test!(recursion: "fact(n) = if(n <= 1, 1, n * fact(n - 1)); fact(5)", 120);
+generate_test!(comparison_is_not_definition: "f(x) = x; f(2) = 2; 5", 5);
+
+generate_error!(redefine_resolver: "double(x) = x", ErrorKind::Redefinition, 0, 9);
+generate_error!(redefine_builtin: "len(x) = x", ErrorKind::Redefinition, 0, 6);
+generate_error!(redefine_if: "if(a, b, c) = a", ErrorKind::Redefinition, 0, 11);
+generate_error!(duplicate_param: "f(x, x) = x", ErrorKind::Redefinition, 5, 6);
+generate_error!(argument_count: "f(x) = x; f(1, 2)", ErrorKind::ArgumentCount { expected: 1, found: 2 }, 10, 17);
+generate_error!(missing_separator: "f(x) = x f(1)", ErrorKind::UnexpectedToken, 9, 10);
+generate_error!(infinite: "loop(x) = loop(x); loop(1)", ErrorKind::RecursionLimit, 19, 26);
+
+// Parameters are only visible inside the function which declares them.
+generate_error!(lexical_scope: "f(x) = g(); g() = x; f(1)", ErrorKind::UnknownConstant, 21, 25);
+
+#[test]
+fn retains_definitions() {
+    let mut session = Session::new(&Prices);
+    assert_eq!(session.eval("tax(x) = x * 0.2"), Ok(None));
+    assert_eq!(
+        session.eval("tax(base)"),
+        Ok(Some(Evaluated::Decimal(Decimal::from(20))))
+    );
+    assert!(session.function("tax").is_some());
+}
+
+#[test]
+fn variables() {
+    let mut session = Session::new(&Prices);
+    session
+        .define_variable("rate", Evaluated::Decimal(Decimal::TWO))
+        .unwrap();
+
+    assert_eq!(
+        session.eval("f(x) = x * rate; f(base)"),
+        Ok(Some(Evaluated::Decimal(Decimal::from(200))))
+    );
+    assert_eq!(
+        session.define_variable("base", Evaluated::Decimal(Decimal::ONE)),
+        Err(ErrorKind::Redefinition)
+    );
+}
+
+#[test]
+fn max_depth() {
+    let mut session = Session::new(&Prices).with_max_depth(3);
+    session.eval("down(n) = if(n = 0, 0, down(n - 1))").unwrap();
+
+    assert!(session.eval("down(2)").is_ok());
+    assert_eq!(
+        session.eval("down(3)").unwrap_err().kind,
+        ErrorKind::RecursionLimit
+    );
+}
+
+#[test]
+fn has_function_does_not_call_resolver() {
+    struct First;
+
+    impl Resolver for First {
+        fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+            None
+        }
+
+        fn resolve_function(
+            &self,
+            _name: &str,
+            args: &[Evaluated],
+        ) -> Result<Evaluated, ErrorKind> {
+            Ok(args[0].clone())
+        }
+    }
+
+    // Without `has_function` the resolver provides no functions to conflict with.
+    let mut session = Session::new(&First);
+    assert_eq!(
+        session.eval("first(x) = x + 1; first(1)"),
+        Ok(Some(Evaluated::Decimal(Decimal::TWO)))
+    );
+}
@@ -0,0 +1,82 @@
+//! String literals and string functions
+
+use equation_eval::{eval, resolve::Resolver, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Report;
+
+impl Resolver for Report {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "x" => Some(Evaluated::Decimal(Decimal::new(1250, 2))),
+            "name" => Some(Evaluated::Str("Ferris".into())),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, _name: &str, _args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        Err(ErrorKind::UnknownFunction)
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Report, $input), Ok($expected));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Report, $input).unwrap_err().kind, $expected);
+        }
+    };
+}
+
+fn str(str: &str) -> Evaluated {
+    Evaluated::Str(str.into())
+}
+
+generate_test!(literal: r#""hello""#, str("hello"));
+generate_test!(single_quoted: "'it\"s'", str("it\"s"));
+generate_test!(escapes: r#""a\tb\n\\\"\u{1F980}""#, str("a\tb\n\\\"🦀"));
+generate_test!(concat: r#"concat("Total: ", x)"#, str("Total: 12.50"));
+generate_test!(concat_many: r#"concat(name, " ", 1 < 2, "!")"#, str("Ferris true!"));
+generate_test!(len: "len(name)", Evaluated::Decimal(Decimal::from(6)));
+generate_test!(len_multibyte: "len('カニ')", Evaluated::Decimal(Decimal::TWO));
+generate_test!(upper: "upper(name)", str("FERRIS"));
+generate_test!(lower: "lower(name)", str("ferris"));
+generate_test!(substr: "substr(name, 1, 3)", str("err"));
+generate_test!(substr_to_end: "substr(name, 4)", str("is"));
+generate_test!(substr_past_end: "substr(name, 10, 2)", str(""));
+generate_test!(contains: "contains(name, 'rr')", Evaluated::Bool(true));
+generate_test!(to_number: "to_number(' 2.5 ') * 2", Evaluated::Decimal(Decimal::new(50, 1)));
+generate_test!(format: "format('{} has {} {{items}}', name, 3)", str("Ferris has 3 {items}"));
+generate_test!(equal: "name = 'Ferris'", Evaluated::Bool(true));
+generate_test!(not_equal: "upper(name) != name", Evaluated::Bool(true));
+
+generate_error!(unterminated: "concat('abc", ErrorKind::UnterminatedString);
+generate_error!(invalid_number: "to_number('two')", ErrorKind::InvalidNumber);
+generate_error!(negative_index: "substr(name, -1)", ErrorKind::InvalidArgument);
+generate_error!(format_missing: "format('{} {}', 1)", ErrorKind::ArgumentCount { expected: 3, found: 2 });
+generate_error!(format_unmatched: "format('{', 1)", ErrorKind::InvalidArgument);
+generate_error!(
+    len_number: "len(x)",
+    ErrorKind::TypeMismatch {
+        expected: Type::List,
+        found: Type::Decimal
+    }
+);
+generate_error!(add_strings: "name + name", ErrorKind::TypeMismatch { expected: Type::Decimal, found: Type::Str });
+generate_error!(equal_mixed: "name = 1", ErrorKind::TypeMismatch { expected: Type::Str, found: Type::Decimal });
+
+#[test]
+fn invalid_escape() {
+    let err = eval(&Report, r#"len("ab\qc")"#).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidEscape);
+    assert_eq!((err.span.start, err.span.end), (7, 9));
+}
@@ -0,0 +1,119 @@
+//! Suggestions for unknown constants and functions
+
+use equation_eval::{eval, resolve::Resolver, session::Session, ErrorKind, Evaluated};
+use rust_decimal::Decimal;
+
+struct Named;
+
+impl Resolver for Named {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "pi" => Some(Evaluated::Decimal(Decimal::PI)),
+            "tau" => Some(Evaluated::Decimal(Decimal::TWO_PI)),
+            "rate" => Some(Evaluated::Decimal(Decimal::new(7, 2))),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("sqrt" | "sin" | "cos", [Evaluated::Decimal(_)]) => Err(ErrorKind::Undefined),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+
+    fn const_symbols(&self) -> Vec<&str> {
+        vec!["pi", "tau", "rate"]
+    }
+
+    fn functions(&self) -> Vec<&str> {
+        vec!["sqrt", "sin", "cos"]
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Named, $input).unwrap_err();
+            let expected: &[&str] = &$expected;
+            assert_eq!(err.suggestions, expected);
+        }
+    };
+}
+
+generate_test!(constant: "2 * pj", ["pi"]);
+generate_test!(constant_prefix: "rat + 1", ["rate"]);
+generate_test!(closest_first: "sim(1) + sqrtt(2)", ["sin", "sum"]);
+generate_test!(function: "1 + sqr(2)", ["sqrt"]);
+generate_test!(function_with_space: "sn (1)", ["sin"]);
+generate_test!(builtin_function: "lenn(\"abc\")", ["len"]);
+generate_test!(lambda_function: "mapp([1], x -> x)", ["map"]);
+generate_test!(swapped: "ture && false", ["true"]);
+generate_test!(nothing_similar: "1 + unrelated", []);
+generate_test!(other_error: "1 / 0", []);
+generate_test!(lambda_param: "map([1], y -> yy)", ["y"]);
+generate_test!(lambda_captured: "map([1], x -> map([2], y -> x + xx))", ["x"]);
+generate_test!(series_variable: "sum(i, 1, 3, ii)", ["i", "pi"]);
+
+#[test]
+fn no_enumeration() {
+    struct Opaque;
+
+    impl Resolver for Opaque {
+        fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+            None
+        }
+
+        fn resolve_function(
+            &self,
+            _name: &str,
+            _args: &[Evaluated],
+        ) -> Result<Evaluated, ErrorKind> {
+            Err(ErrorKind::UnknownFunction)
+        }
+    }
+
+    let input = "avgg(1, 2) + pj";
+    let err = eval(&Opaque, input).unwrap_err();
+    assert_eq!(err.suggestions, ["avg"]);
+}
+
+#[test]
+fn session() {
+    let mut session = Session::new(&Named);
+    session.eval("total = 10; double(x) = x * 2").unwrap();
+
+    let input = "totl + pii";
+    let err = session.eval(input).unwrap_err();
+    assert_eq!(err.suggestions, ["total"]);
+
+    let input = "doubel(1)";
+    let err = session.eval(input).unwrap_err();
+    assert_eq!(err.suggestions, ["double"]);
+}
+
+#[test]
+fn function_body() {
+    let mut session = Session::new(&Named);
+    session.eval("f(x) = x + totl; total = 3").unwrap();
+    session.eval("g(y) = f(y) * 2; h(x) = x + xx").unwrap();
+
+    // The names of the function body are suggested, even though the error is reported at the call.
+    let err = session.eval("f(1)").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnknownConstant);
+    assert_eq!(err.suggestions, ["total"]);
+
+    let err = session.eval("g(1)").unwrap_err();
+    assert_eq!(err.suggestions, ["total"]);
+
+    let err = session.eval("h(1)").unwrap_err();
+    assert_eq!(err.suggestions, ["x"]);
+
+    let err = session.eval("map([1], z -> f(z))").unwrap_err();
+    assert_eq!(err.suggestions, ["total"]);
+
+    // Suggestions of an earlier evaluation are not reported again.
+    let err = session.eval("1 / 0").unwrap_err();
+    assert!(err.suggestions.is_empty());
+}
@@ -0,0 +1,69 @@
+//! Type checking of values
+
+use equation_eval::{eval, resolve::Resolver, ErrorKind, Evaluated, Type};
+use rust_decimal::Decimal;
+
+struct Values;
+
+impl Resolver for Values {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        match name {
+            "enabled" => Some(Evaluated::Bool(true)),
+            "rate" => Some(Evaluated::Decimal(Decimal::TWO)),
+            _ => None,
+        }
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("positive", [Evaluated::Decimal(value)]) => {
+                Ok(Evaluated::Bool(value.is_sign_positive()))
+            }
+            ("positive", [value]) => Err(ErrorKind::TypeMismatch {
+                expected: Type::Decimal,
+                found: value.ty(),
+            }),
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(eval(&Values, $input), Ok($expected));
+        }
+    };
+}
+
+macro_rules! generate_mismatch {
+    ($name: ident: $input: expr, $expected: ident, $found: ident, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Values, $input).unwrap_err();
+            assert_eq!(
+                err.kind,
+                ErrorKind::TypeMismatch {
+                    expected: Type::$expected,
+                    found: Type::$found,
+                }
+            );
+            assert_eq!((err.span.start, err.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(bool_constant: "enabled && rate > 1", Evaluated::Bool(true));
+generate_test!(bool_function: "positive(rate - 3)", Evaluated::Bool(false));
+generate_test!(function_in_condition: "if(positive(rate), rate, 0) * 2", Evaluated::Decimal(Decimal::from(4)));
+
+generate_mismatch!(add_bool: "1 + true", Decimal, Bool, 4, 8);
+generate_mismatch!(add_comparison: "(1 < 2) + 1", Decimal, Bool, 1, 6);
+generate_mismatch!(neg_bool: "-enabled", Decimal, Bool, 1, 8);
+generate_mismatch!(and_number: "rate && enabled", Bool, Decimal, 0, 4);
+generate_mismatch!(not_number: "!rate", Bool, Decimal, 1, 5);
+generate_mismatch!(if_number: "if(rate, 1, 2)", Bool, Decimal, 3, 7);
+generate_mismatch!(equals_mixed: "1 = true", Decimal, Bool, 4, 8);
+generate_mismatch!(compare_bool: "enabled > 1", Decimal, Bool, 0, 7);
+generate_mismatch!(resolver_mismatch: "positive(enabled)", Decimal, Bool, 0, 17);
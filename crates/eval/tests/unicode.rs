@@ -0,0 +1,128 @@
+//! Unicode operators and names with the `unicode` feature.
+
+#![cfg(feature = "unicode")]
+
+use equation_eval::{
+    eval,
+    node::{Node, Spanned},
+    resolve::Resolver,
+    ErrorKind, Evaluated,
+};
+use rust_decimal::{Decimal, MathematicalOps};
+
+struct Physics;
+
+impl Resolver for Physics {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        let value = match name {
+            "x" => Decimal::TWO,
+            "α" => Decimal::from(3),
+            "θ_0" => Decimal::ZERO,
+            "größe" => Decimal::TEN,
+            "infinity" => Decimal::MAX,
+            "pi" => Decimal::PI,
+            "tau" => Decimal::TWO_PI,
+            _ => return None,
+        };
+
+        Some(Evaluated::Decimal(value))
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        match (name, args) {
+            ("sqrt", [Evaluated::Decimal(x)]) => {
+                x.sqrt().map(Evaluated::Decimal).ok_or(ErrorKind::Undefined)
+            }
+            _ => Err(ErrorKind::UnknownFunction),
+        }
+    }
+}
+
+/// Check a Unicode expression evaluates to the same value as its ASCII equivalent.
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $ascii: expr) => {
+        #[test]
+        fn $name() {
+            let value = eval(&Physics, $input);
+
+            assert!(value.is_ok(), "{:?}", value);
+            assert_eq!(value, eval(&Physics, $ascii));
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $kind: expr, $start: expr, $end: expr) => {
+        #[test]
+        fn $name() {
+            let error = eval(&Physics, $input).unwrap_err();
+
+            assert_eq!(error.kind, $kind);
+            assert_eq!((error.span.start, error.span.end), ($start, $end));
+        }
+    };
+}
+
+generate_test!(times: "2 × 3 · x ⋅ 4", "2 * 3 * x * 4");
+generate_test!(divide: "10 ÷ 4", "10 / 4");
+generate_test!(minus: "5 − x − −1", "5 - x - -1");
+generate_test!(comparisons: "x ≤ 2 && α ≥ 3 && x ≠ α", "x <= 2 && α >= 3 && x != α");
+generate_test!(root: "√16 + √(x + 7)", "sqrt(16) + sqrt(x + 7)");
+generate_test!(root_binds_tighter_than_sum: "√4 + 5", "2 + 5");
+generate_test!(root_of_power: "√x²", "sqrt(x^2)");
+generate_test!(pi: "2 × π", "2 * pi");
+generate_test!(tau: "τ ÷ 2", "pi");
+generate_test!(infinity: "∞ > x", "infinity > x");
+generate_test!(square: "x² + 1", "x^2 + 1");
+generate_test!(cube: "10³", "1000");
+generate_test!(multiple_digits: "x¹⁰", "x^10");
+generate_test!(negative_exponent: "x⁻¹", "x^-1");
+generate_test!(negated_square: "−x²", "-(x^2)");
+generate_test!(power_of_square: "2^x²", "2^(x^2)");
+generate_test!(grouped_square: "(x + 1)²", "(x + 1)^2");
+generate_test!(factorial_square: "x!²", "(x!)^2");
+generate_test!(greek: "α × θ_0 + größe", "3 * 0 + 10");
+generate_test!(middle_dot_names: "x·α", "x * α");
+
+generate_error!(lone_superscript_minus: "x⁻", ErrorKind::InvalidNumber, 1, 4);
+generate_error!(superscript_without_operand: "²", ErrorKind::UnexpectedToken, 0, 2);
+generate_error!(root_without_operand: "√", ErrorKind::UnexpectedEnd, 3, 3);
+generate_error!(invalid: "1 + 🙃", ErrorKind::InvalidToken, 4, 8);
+
+#[test]
+fn infinity_without_resolver() {
+    struct Finite;
+
+    impl Resolver for Finite {
+        fn resolve_const_symbol(&self, _name: &str) -> Option<Evaluated> {
+            None
+        }
+
+        fn resolve_function(
+            &self,
+            _name: &str,
+            _args: &[Evaluated],
+        ) -> Result<Evaluated, ErrorKind> {
+            Err(ErrorKind::UnknownFunction)
+        }
+    }
+
+    let error = eval(&Finite, "1 < ∞").unwrap_err();
+
+    assert_eq!(error.kind, ErrorKind::UnknownConstant);
+    assert_eq!((error.span.start, error.span.end), (4, 7));
+}
+
+#[test]
+fn spans() {
+    let spans = Node::parse("α² ≤ √x")
+        .unwrap()
+        .map(|node| {
+            let span = node.span();
+            (span.start, span.end)
+        })
+        .collect::<Vec<_>>();
+
+    // `α`, `²`, `α²`, `x`, `√x` and the comparison, in bytes.
+    assert_eq!(spans, [(0, 2), (2, 4), (0, 4), (12, 13), (9, 13), (0, 13)]);
+}
@@ -0,0 +1,47 @@
+//! Options for the dialect of expressions.
+
+/// Options for the dialect lexed by a [`Tokenizer`](crate::Tokenizer).
+///
+/// Every option is disabled by [`Default`], which lexes the same tokens as [`Tokenizer::from`](crate::Tokenizer).
+/// Options only change how text which would otherwise be invalid or split into several tokens is lexed.
+///
+/// ```
+/// use equation_lexer::{LexerConfig, TokenKind, Tokenizer};
+///
+/// let config = LexerConfig {
+///     double_star_power: true,
+///     hash_comments: true,
+///     ..LexerConfig::default()
+/// };
+///
+/// let kinds = Tokenizer::with_config("2**3 # cube", config)
+///     .map(|token| token.kind)
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(kinds[1], TokenKind::Caret);
+/// assert_eq!(kinds[4], TokenKind::Comment);
+/// ```
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LexerConfig {
+    /// Lex `**` as [`TokenKind::Caret`](crate::TokenKind::Caret), a power.
+    pub double_star_power: bool,
+
+    /// Lex `//` as [`TokenKind::DoubleSlash`](crate::TokenKind::DoubleSlash), integer division.
+    pub double_slash_division: bool,
+
+    /// Lex `#` and the rest of the line as a [`TokenKind::Comment`](crate::TokenKind::Comment).
+    pub hash_comments: bool,
+
+    /// Allow identifiers to start with `$`, such as `$price`.
+    ///
+    /// The `$` is part of the [`TokenKind::Chars`](crate::TokenKind::Chars) token and the name.
+    pub dollar_identifiers: bool,
+
+    /// Allow `.` between the characters of identifiers, such as `order.total`.
+    ///
+    /// A `.` which is not followed by a character of an identifier is not part of the identifier, so `x.` is `x` and
+    /// an invalid token.
+    pub dotted_identifiers: bool,
+}
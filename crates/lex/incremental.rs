@@ -0,0 +1,182 @@
+//! Incremental lexing of edited text.
+//!
+//! The kind and length of a token only depend on the text from the start of the token onwards. After an edit, the
+//! tokens before the edit are unchanged and lexing from the token touching the edit eventually reaches the start of
+//! a token after the edit, from which point the remaining tokens are unchanged.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{LexerConfig, Token, Tokenizer};
+
+/// An edit replacing a range of text.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Edit<'a> {
+    /// The byte range of the text before the edit which is replaced
+    pub range: Range<usize>,
+
+    /// The text inserted in place of the range
+    pub text: &'a str,
+}
+
+/// The tokens changed by [`relex`].
+///
+/// Tokens only have a kind and a length, so replacing a token with a different token of the same kind and length is
+/// not a change.
+#[cfg_attr(feature = "fmt", derive(Debug))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The indices of the new tokens in the updated tokens
+    pub tokens: Range<usize>,
+
+    /// The number of tokens which were replaced, starting at `tokens.start`
+    pub removed: usize,
+}
+
+/// Update the tokens of some text after an edit, lexing as little of the text as possible.
+///
+/// `tokens` must be the tokens of the text before the edit and `str` must be the text after the edit. The tokens
+/// are updated to be the same as the tokens produced by a [`Tokenizer`] from `str`.
+///
+/// ```
+/// use equation_lexer::{relex, Edit, Tokenizer};
+///
+/// let mut tokens = Tokenizer::from("1 + 2").collect::<Vec<_>>();
+///
+/// // Replace `2` with `x * y`.
+/// let edit = Edit { range: 4..5, text: "x * y" };
+/// let change = relex(&mut tokens, "1 + x * y", &edit);
+///
+/// assert_eq!(tokens, Tokenizer::from("1 + x * y").collect::<Vec<_>>());
+/// assert_eq!(change.tokens, 4..9);
+/// assert_eq!(change.removed, 1);
+/// ```
+pub fn relex(tokens: &mut Vec<Token>, str: &str, edit: &Edit<'_>) -> Change {
+    relex_with_config(tokens, str, edit, LexerConfig::default())
+}
+
+/// Update the tokens of some text lexed with a [`LexerConfig`] after an edit.
+///
+/// The tokens are updated to be the same as the tokens produced by [`Tokenizer::with_config`] from `str`. See
+/// [`relex`].
+pub fn relex_with_config(
+    tokens: &mut Vec<Token>,
+    str: &str,
+    edit: &Edit<'_>,
+    config: LexerConfig,
+) -> Change {
+    let Some(edit_end) = edit.range.start.checked_add(edit.text.len()) else {
+        return relex_all(tokens, str, config);
+    };
+
+    if edit.range.start > edit.range.end || edit_end > str.len() {
+        return relex_all(tokens, str, config);
+    }
+
+    // The first token which may change, which is the first token ending at or after the start of the edit. The
+    // token ending at the start is included since inserted text may extend it. A dotted identifier also depends on
+    // the character after a `.`, so the token ending one character before the start is included too.
+    let changed = retreat(str, edit.range.start, 1).unwrap_or(0);
+    let mut first = 0;
+    let mut start = 0;
+
+    for token in tokens.iter() {
+        let end = advance(str, start, token.len);
+
+        if end >= changed {
+            break;
+        }
+
+        first += 1;
+        start = end;
+    }
+
+    // The first token after the edit and its offset in the text after the edit, found from the end of the text since
+    // the text after the edit is unchanged.
+    let mut next = tokens.len();
+    let mut next_start = str.len();
+
+    for token in tokens[first..].iter().rev() {
+        match retreat(str, next_start, token.len) {
+            Some(start) if start >= edit_end => {
+                next -= 1;
+                next_start = start;
+            }
+
+            _ => break,
+        }
+    }
+
+    // Lex until a new token ends where a token after the edit starts.
+    let mut new = Vec::new();
+    let mut offset = start;
+
+    for token in Tokenizer::with_config(str.get(start..).unwrap_or(""), config) {
+        new.push(token);
+        offset = advance(str, offset, token.len);
+
+        // Skip the tokens after the edit which the new tokens have passed.
+        while next < tokens.len() && next_start < offset {
+            next_start = advance(str, next_start, tokens[next].len);
+            next += 1;
+        }
+
+        if next < tokens.len() && offset >= edit_end && offset == next_start {
+            break;
+        }
+    }
+
+    // Tokens at either end which are the same as before are not part of the change.
+    let old = &tokens[first..next];
+    let same = |(new, old): &(&Token, &Token)| new == old;
+    let prefix = new.iter().zip(old).take_while(same).count();
+    let suffix = new[prefix..]
+        .iter()
+        .rev()
+        .zip(old[prefix..].iter().rev())
+        .take_while(same)
+        .count();
+
+    let first = first + prefix;
+    let removed = next - suffix - first;
+    let inserted = new.len() - suffix - prefix;
+
+    tokens.splice(
+        first..first + removed,
+        new.into_iter().skip(prefix).take(inserted),
+    );
+
+    Change {
+        tokens: first..first + inserted,
+        removed,
+    }
+}
+
+fn relex_all(tokens: &mut Vec<Token>, str: &str, config: LexerConfig) -> Change {
+    let removed = tokens.len();
+
+    tokens.clear();
+    tokens.extend(Tokenizer::with_config(str, config));
+
+    Change {
+        tokens: 0..tokens.len(),
+        removed,
+    }
+}
+
+/// The byte offset `chars` characters after `offset`.
+fn advance(str: &str, offset: usize, chars: usize) -> usize {
+    str.get(offset..)
+        .and_then(|rest| rest.char_indices().nth(chars))
+        .map_or(str.len(), |(len, _)| offset + len)
+}
+
+/// The byte offset `chars` characters before `offset`.
+fn retreat(str: &str, offset: usize, chars: usize) -> Option<usize> {
+    str.get(..offset)?
+        .char_indices()
+        .rev()
+        .nth(chars.checked_sub(1)?)
+        .map(|(start, _)| start)
+}
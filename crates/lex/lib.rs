@@ -4,18 +4,53 @@
 //! Tokens are representations of the human readable expression in the input and should be converted into
 //! another form for evaluation.
 //!
-//! A [`Tokenizer`] may be constructed using it's [`From`] implementation.
+//! A [`Tokenizer`] may be constructed using it's [`From`] implementation, or with [`Tokenizer::with_config`] to lex
+//! a different dialect of expressions described by a [`LexerConfig`].
+//!
+//! With the `alloc` feature, [`relex`] updates the tokens of some text after an edit without lexing all of the text
+//! again.
+//!
+//! With the `serde` feature, [`Token`] and the kinds of tokens can be serialized. A token is an object with a `kind`
+//! and a `len`, kinds are named in snake case and kinds with data are externally tagged:
+//!
+//! ```json
+//! { "kind": { "brace": { "kind": "round", "open": true } }, "len": 1 }
+//! { "kind": { "number": "hexadecimal" }, "len": 4 }
+//! { "kind": "ws", "len": 2 }
+//! ```
+//!
+//! With the `unicode` feature, common mathematical symbols are recognized: `×` and `·` lex as
+//! [`TokenKind::Multiply`], `÷` as [`TokenKind::Divide`] and `−` as [`TokenKind::Minus`]. `≤`, `≥`, `≠`, `√` and
+//! superscript digits such as `²` have their own kinds of tokens, and [`TokenKind::Chars`] follows the Unicode
+//! identifier rules so names like `α`, `θ_0` or `π` are single tokens. The lengths of tokens are always counted in
+//! characters, not bytes.
 
 #![no_std]
 #![forbid(clippy::expect_used, clippy::panic, clippy::unwrap_used)]
 #![warn(missing_docs)]
 #![cfg_attr(feature = "fmt", forbid(missing_debug_implementations))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core::str::Chars;
 
+mod config;
+#[cfg(feature = "alloc")]
+mod incremental;
+
+pub use config::LexerConfig;
+#[cfg(feature = "alloc")]
+pub use incremental::{relex, relex_with_config, Change, Edit};
+
 /// A kind of brace
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum BraceKind {
     /// Round brackets: `(` or `)`
     Round,
@@ -32,6 +67,11 @@ pub enum BraceKind {
 /// A number token is simply a way to guess if the value is likely a number. The number itself could be invalid.
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum NumberKind {
     /// Decimal number
     ///
@@ -48,6 +88,11 @@ pub enum NumberKind {
 /// A kind of token
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum TokenKind {
     /// `+`
     Plus,
@@ -85,7 +130,10 @@ pub enum TokenKind {
     /// `&`
     Amp,
 
-    /// `^`
+    /// `|`
+    Pipe,
+
+    /// `^`, or `**` with [`LexerConfig::double_star_power`]
     Caret,
 
     /// `!`
@@ -94,8 +142,52 @@ pub enum TokenKind {
     /// `,`
     Comma,
 
+    /// `;`
+    Semicolon,
+
+    /// `:`
+    Colon,
+
+    /// `≤`
+    ///
+    /// Only produced with the `unicode` feature.
+    LessEqual,
+
+    /// `≥`
+    ///
+    /// Only produced with the `unicode` feature.
+    GreaterEqual,
+
+    /// `≠`
+    ///
+    /// Only produced with the `unicode` feature.
+    NotEqual,
+
+    /// `√`
+    ///
+    /// Only produced with the `unicode` feature.
+    Root,
+
+    /// `//`
+    ///
+    /// Only produced with [`LexerConfig::double_slash_division`].
+    DoubleSlash,
+
+    /// Superscript digits, such as the `²` in `x²`
+    ///
+    /// The digits may be preceded by a superscript minus, `⁻`. Whether there are any digits is checked later in the
+    /// parsing process.
+    ///
+    /// Only produced with the `unicode` feature.
+    Superscript,
+
     /// A sequence of characters
     ///
+    /// Characters are ASCII letters, digits or `_`, and the sequence never starts with a digit. With the `unicode`
+    /// feature, the sequence starts with `_` or a character with the `XID_Start` property and continues with
+    /// characters with the `XID_Continue` property, except for `·` which is always a multiplication. `∞` is also a
+    /// sequence of characters on its own.
+    ///
     /// The sequence of characters may be one of the following:
     /// - Magic values (Euler's number, Pi, infinity)
     /// - The name of a function (ln (natural log), sin, cos, square root).
@@ -105,11 +197,27 @@ pub enum TokenKind {
     /// A number
     Number(NumberKind),
 
+    /// A string literal quoted by `"` or `'`
+    ///
+    /// A backslash escapes the character after it, including the quote. Whether the escape sequences are valid is
+    /// checked later in the parsing process.
+    Str {
+        /// Whether the string literal has a closing quote.
+        ///
+        /// An unterminated string literal continues until the end of the input.
+        terminated: bool,
+    },
+
     /// Whitespace character(s)
     ///
     /// See [`char::is_ascii_whitespace`].
     Ws,
 
+    /// A comment from `#` to the end of the line, not including the line break
+    ///
+    /// Only produced with [`LexerConfig::hash_comments`].
+    Comment,
+
     /// An invalid token
     ///
     /// This kind of token is handled for error checking purposes.
@@ -128,9 +236,12 @@ pub enum TokenKind {
 /// - `>`
 /// - `<`
 /// - `&`
+/// - `|`
 /// - `^`
 /// - `!`
 /// - `,`
+/// - `;`
+/// - `:`
 ///
 /// Note that [`TokenKind::Brace`] cannot be created using this macro. Use the [`Brace`] macro instead to generate a
 /// [`TokenKind`] for a brace.
@@ -152,9 +263,12 @@ macro_rules! Kind {
     [>] => { $crate::TokenKind::Ge };
     [<] => { $crate::TokenKind::Le };
     [&] => { $crate::TokenKind::Amp };
+    [|] => { $crate::TokenKind::Pipe };
     [^] => { $crate::TokenKind::Caret };
     [!] => { $crate::TokenKind::Exclamation };
     [,] => { $crate::TokenKind::Comma };
+    [;] => { $crate::TokenKind::Semicolon };
+    [:] => { $crate::TokenKind::Colon };
 }
 
 /// A type-macro which expands to the [`TokenKind`] representation of a type of brace.
@@ -188,6 +302,7 @@ macro_rules! Brace {
 /// A single token in an expression.
 #[cfg_attr(feature = "fmt", derive(Debug))]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
     /// The token kind
     pub kind: TokenKind,
@@ -201,9 +316,38 @@ pub struct Token {
 #[derive(Clone)]
 pub struct Tokenizer<'a> {
     iter: Chars<'a>,
+    config: LexerConfig,
 }
 
-impl Tokenizer<'_> {
+impl<'a> Tokenizer<'a> {
+    /// Creates a tokenizer from a string, lexing the dialect described by `config`.
+    ///
+    /// ```
+    /// use equation_lexer::{LexerConfig, Token, TokenKind, Tokenizer};
+    ///
+    /// let config = LexerConfig {
+    ///     dotted_identifiers: true,
+    ///     ..LexerConfig::default()
+    /// };
+    /// let mut parser = Tokenizer::with_config("order.total", config);
+    ///
+    /// assert_eq!(parser.next(), Some(Token {
+    ///     kind: TokenKind::Chars,
+    ///     len: 11,
+    /// }));
+    /// assert_eq!(parser.next(), None);
+    /// ```
+    pub fn with_config(str: &'a str, config: LexerConfig) -> Self {
+        Self {
+            iter: str.chars(),
+            config,
+        }
+    }
+
+    /// The dialect lexed by the tokenizer.
+    pub fn config(&self) -> LexerConfig {
+        self.config
+    }
     /// Peek by a specified amount for the next [`Token`].
     ///
     /// This does not advance the iterator and repeated calls return the same token.
@@ -219,19 +363,13 @@ impl Tokenizer<'_> {
             count = count.checked_sub(1)?;
 
             let mut chars = str.chars();
-            let (kind, mut len) = kind_with_iter(&mut chars)?;
+            let (kind, mut len) = kind_with_iter(&mut chars, &self.config)?;
 
             // Determine the length of an invalid token
             if kind == TokenKind::Invalid {
-                loop {
-                    // Consume the next character in the iterator until the end or invalid token.
-                    match kind_with_iter(&mut chars) {
-                        Some((kind, _)) if kind == TokenKind::Invalid => {
-                            len += 1;
-                        }
-
-                        _ => break,
-                    }
+                // Consume the next character in the iterator until the end or invalid token.
+                while let Some((TokenKind::Invalid, _)) = kind_with_iter(&mut chars, &self.config) {
+                    len += 1;
                 }
             }
 
@@ -239,9 +377,12 @@ impl Tokenizer<'_> {
                 break Some(Token { kind, len });
             }
 
-            // Take remainder of the str borrowed from the true iterator to peek at the next token.
-            // TODO: Use advance_by when stabilized.
-            str = chars.as_str().get((len - 1)..)?;
+            // Take remainder of the str after the token to peek at the next token.
+            let offset = str
+                .char_indices()
+                .nth(len)
+                .map_or(str.len(), |(offset, _)| offset);
+            str = str.get(offset..)?;
         }
     }
 }
@@ -277,7 +418,7 @@ impl<'a> From<&'a str> for Tokenizer<'a> {
     /// assert_eq!(parser.next(), None);
     /// ```
     fn from(str: &'a str) -> Self {
-        Self { iter: str.chars() }
+        Self::with_config(str, LexerConfig::default())
     }
 }
 
@@ -299,20 +440,27 @@ macro_rules! tok1 {
     }};
 }
 
-fn kind_with_iter(chars: &mut Chars) -> Option<(TokenKind, usize)> {
+fn kind_with_iter(chars: &mut Chars, config: &LexerConfig) -> Option<(TokenKind, usize)> {
     let (kind, len) = match chars.next()? {
         '+' => tok1!(Kind![+]),
         '-' => tok1!(Kind![-]),
+        '*' if config.double_star_power && chars.clone().next() == Some('*') => (Kind![^], 2),
         '*' => tok1!(Kind![*]),
+        '/' if config.double_slash_division && chars.clone().next() == Some('/') => {
+            (TokenKind::DoubleSlash, 2)
+        }
         '/' => tok1!(Kind![/]),
         '%' => tok1!(Kind![%]),
         '=' => tok1!(Kind![=]),
         '>' => tok1!(Kind![>]),
         '<' => tok1!(Kind![<]),
         '&' => tok1!(Kind![&]),
+        '|' => tok1!(Kind![|]),
         '^' => tok1!(Kind![^]),
         '!' => tok1!(Kind![!]),
         ',' => tok1!(Kind![,]),
+        ';' => tok1!(Kind![;]),
+        ':' => tok1!(Kind![:]),
         '(' => tok1!(Brace![Round, Open]),
         ')' => tok1!(Brace![Round, Close]),
         '[' => tok1!(Brace![Square, Open]),
@@ -321,13 +469,29 @@ fn kind_with_iter(chars: &mut Chars) -> Option<(TokenKind, usize)> {
         '}' => tok1!(Brace![Curly, Close]),
 
         c => match c {
+            '"' | '\'' => {
+                let (terminated, len) = string(c, chars);
+                (TokenKind::Str { terminated }, len)
+            }
+
             '0'..='9' => {
                 let (kind, len) = number(c, chars);
                 (TokenKind::Number(kind), len)
             }
 
-            c if c.is_ascii_alphabetic() => (TokenKind::Chars, chr(chars) + 1),
+            c if c.is_ascii_alphabetic() || c == '_' => (TokenKind::Chars, chr(chars, config) + 1),
             c if c.is_ascii_whitespace() => (TokenKind::Ws, ws(chars) + 1),
+
+            '#' if config.hash_comments => (TokenKind::Comment, comment(chars) + 1),
+            '$' if config.dollar_identifiers && chars.clone().next().is_some_and(starts) => {
+                let _ = chars.next();
+                (TokenKind::Chars, chr(chars, config) + 2)
+            }
+
+            #[cfg(feature = "unicode")]
+            c => unicode(c, chars, config),
+
+            #[cfg(not(feature = "unicode"))]
             _ => (TokenKind::Invalid, 1),
         },
     };
@@ -335,14 +499,132 @@ fn kind_with_iter(chars: &mut Chars) -> Option<(TokenKind, usize)> {
     Some((kind, len))
 }
 
-fn chr(chars: &mut Chars) -> usize {
-    chars.take_while(char::is_ascii_alphanumeric).count()
+/// Whether the character may start a [`TokenKind::Chars`] token.
+fn starts(c: char) -> bool {
+    #[cfg(feature = "unicode")]
+    if unicode_xid::UnicodeXID::is_xid_start(c) {
+        return true;
+    }
+
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+/// Whether the character may continue a [`TokenKind::Chars`] token.
+#[cfg(not(feature = "unicode"))]
+fn continues(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// `·` is `XID_Continue`, but `a·b` is a product rather than a name, so no symbol continues a name.
+#[cfg(feature = "unicode")]
+fn continues(c: char) -> bool {
+    unicode_xid::UnicodeXID::is_xid_continue(c) && symbol(c).is_none()
+}
+
+fn chr(chars: &mut Chars, config: &LexerConfig) -> usize {
+    let mut len = 0;
+
+    loop {
+        let mut ahead = chars.clone();
+
+        len += match ahead.next() {
+            Some(c) if continues(c) => 1,
+            // A dot is only part of the sequence between two other characters.
+            Some('.') if config.dotted_identifiers && ahead.next().is_some_and(continues) => 2,
+            _ => return len,
+        };
+
+        *chars = ahead;
+    }
+}
+
+fn comment(chars: &mut Chars) -> usize {
+    chars.take_while(|c| *c != '\n').count()
+}
+
+/// The kind of a single character token for a mathematical symbol.
+#[cfg(feature = "unicode")]
+fn symbol(c: char) -> Option<TokenKind> {
+    let kind = match c {
+        '×' | '·' | '⋅' => Kind![*],
+        '÷' => Kind![/],
+        '−' => Kind![-],
+        '≤' => TokenKind::LessEqual,
+        '≥' => TokenKind::GreaterEqual,
+        '≠' => TokenKind::NotEqual,
+        '√' => TokenKind::Root,
+        '∞' => TokenKind::Chars,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// Lex a token starting with a non-ASCII character.
+#[cfg(feature = "unicode")]
+fn unicode(c: char, chars: &mut Chars, config: &LexerConfig) -> (TokenKind, usize) {
+    if let Some(kind) = symbol(c) {
+        return tok1!(kind);
+    }
+
+    match c {
+        '⁻' => (TokenKind::Superscript, superscript(chars) + 1),
+        c if superscript_digit(c).is_some() => (TokenKind::Superscript, superscript(chars) + 1),
+        c if unicode_xid::UnicodeXID::is_xid_start(c) => (TokenKind::Chars, chr(chars, config) + 1),
+        _ => tok1!(TokenKind::Invalid),
+    }
+}
+
+#[cfg(feature = "unicode")]
+fn superscript(chars: &mut Chars) -> usize {
+    chars
+        .take_while(|c| superscript_digit(*c).is_some())
+        .count()
+}
+
+/// The value of a superscript digit.
+///
+/// ```
+/// use equation_lexer::superscript_digit;
+///
+/// assert_eq!(superscript_digit('²'), Some(2));
+/// assert_eq!(superscript_digit('2'), None);
+/// ```
+pub fn superscript_digit(c: char) -> Option<u32> {
+    match c {
+        '⁰' => Some(0),
+        '¹' => Some(1),
+        '²' => Some(2),
+        '³' => Some(3),
+        '⁴'..='⁹' => Some(c as u32 - '⁴' as u32 + 4),
+        _ => None,
+    }
 }
 
 fn ws(chars: &mut Chars) -> usize {
     chars.take_while(char::is_ascii_whitespace).count()
 }
 
+fn string(quote: char, chars: &mut Chars) -> (bool, usize) {
+    // Include the opening quote.
+    let mut len = 1;
+
+    while let Some(c) = chars.next() {
+        len += 1;
+
+        if c == quote {
+            return (true, len);
+        }
+
+        // Skip the escaped character so an escaped quote does not end the string.
+        if c == '\\' && chars.next().is_some() {
+            len += 1;
+        }
+    }
+
+    (false, len)
+}
+
 fn number(first_char: char, chars: &mut Chars) -> (NumberKind, usize) {
     // 0 first character may imply multiple types of numbers
     if first_char == '0' {
@@ -364,9 +646,11 @@ fn number(first_char: char, chars: &mut Chars) -> (NumberKind, usize) {
 }
 
 fn hexadecimal(chars: &mut Chars) -> usize {
-    chars.take_while(|c| matches!(c, 'a'..='f' | 'A'..='F' | '0'..='9')).count()
+    chars.take_while(char::is_ascii_hexdigit).count()
 }
 
 fn decimal_or_complex(chars: &mut Chars) -> usize {
-    chars.take_while(|c| matches!(c, '.' | '0'..='9' | 'e' | 'E')).count()
+    chars
+        .take_while(|c| matches!(c, '.' | '0'..='9' | 'e' | 'E'))
+        .count()
 }
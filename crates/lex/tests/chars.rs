@@ -35,6 +35,14 @@ fn log2() {
     assert_eq!(reader.next(), None);
 }
 
+#[test]
+fn underscores() {
+    let mut reader = Tokenizer::from("_to_number2");
+
+    assert_eq!(reader.next(), Some(token!(TokenKind::Chars; 11)));
+    assert_eq!(reader.next(), None);
+}
+
 #[test]
 fn space_separated_words() {
     let mut reader = Tokenizer::from("foo bar");
@@ -0,0 +1,173 @@
+//! Dialects of expressions enabled by a `LexerConfig`
+
+use equation_lexer::{
+    relex_with_config, Edit, Kind, LexerConfig, NumberKind, Token, TokenKind, Tokenizer,
+};
+
+macro_rules! token {
+    ($kind: expr; $len: expr) => {{
+        Token {
+            kind: $kind,
+            len: $len,
+        }
+    }};
+}
+
+const ALL: LexerConfig = LexerConfig {
+    double_star_power: true,
+    double_slash_division: true,
+    hash_comments: true,
+    dollar_identifiers: true,
+    dotted_identifiers: true,
+};
+
+const ONE: Token = token!(TokenKind::Number(NumberKind::Decimal); 1);
+const WS: Token = token!(TokenKind::Ws; 1);
+
+/// Check the tokens of the input with every option enabled, and with the default options.
+macro_rules! generate_test {
+    ($name: ident: $input: expr, [$($expected: expr),* $(,)?], [$($default: expr),* $(,)?]) => {
+        #[test]
+        fn $name() {
+            let tokens = Tokenizer::with_config($input, ALL).collect::<Vec<_>>();
+            assert_eq!(tokens, [$($expected),*]);
+
+            let tokens = Tokenizer::from($input).collect::<Vec<_>>();
+            assert_eq!(tokens, [$($default),*]);
+        }
+    };
+}
+
+generate_test!(
+    double_star: "1**1",
+    [ONE, token!(Kind![^]; 2), ONE],
+    [ONE, token!(Kind![*]; 1), token!(Kind![*]; 1), ONE]
+);
+generate_test!(
+    triple_star: "1***1",
+    [ONE, token!(Kind![^]; 2), token!(Kind![*]; 1), ONE],
+    [ONE, token!(Kind![*]; 1), token!(Kind![*]; 1), token!(Kind![*]; 1), ONE]
+);
+generate_test!(
+    double_slash: "1//1",
+    [ONE, token!(TokenKind::DoubleSlash; 2), ONE],
+    [ONE, token!(Kind![/]; 1), token!(Kind![/]; 1), ONE]
+);
+generate_test!(
+    comment: "1 # one\n+1",
+    [ONE, WS, token!(TokenKind::Comment; 5), WS, token!(Kind![+]; 1), ONE],
+    [ONE, WS, token!(TokenKind::Invalid; 1), WS, token!(TokenKind::Chars; 3), WS, token!(Kind![+]; 1), ONE]
+);
+generate_test!(
+    comment_to_end: "# 1 + 2",
+    [token!(TokenKind::Comment; 7)],
+    [
+        token!(TokenKind::Invalid; 1),
+        WS,
+        ONE,
+        WS,
+        token!(Kind![+]; 1),
+        WS,
+        token!(TokenKind::Number(NumberKind::Decimal); 1),
+    ]
+);
+generate_test!(
+    dollar: "$price*1",
+    [token!(TokenKind::Chars; 6), token!(Kind![*]; 1), ONE],
+    [token!(TokenKind::Invalid; 1), token!(TokenKind::Chars; 5), token!(Kind![*]; 1), ONE]
+);
+generate_test!(
+    lone_dollar: "$ 1",
+    [token!(TokenKind::Invalid; 1), WS, ONE],
+    [token!(TokenKind::Invalid; 1), WS, ONE]
+);
+generate_test!(
+    dollar_digit: "$1",
+    [token!(TokenKind::Invalid; 1), ONE],
+    [token!(TokenKind::Invalid; 1), ONE]
+);
+generate_test!(
+    dotted: "order.total",
+    [token!(TokenKind::Chars; 11)],
+    [token!(TokenKind::Chars; 5), token!(TokenKind::Invalid; 1), token!(TokenKind::Chars; 5)]
+);
+generate_test!(
+    dotted_dollar: "$order.items.0",
+    [token!(TokenKind::Chars; 14)],
+    [
+        token!(TokenKind::Invalid; 1),
+        token!(TokenKind::Chars; 5),
+        token!(TokenKind::Invalid; 1),
+        token!(TokenKind::Chars; 5),
+        token!(TokenKind::Invalid; 1),
+        ONE,
+    ]
+);
+generate_test!(
+    trailing_dot: "x.",
+    [token!(TokenKind::Chars; 1), token!(TokenKind::Invalid; 1)],
+    [token!(TokenKind::Chars; 1), token!(TokenKind::Invalid; 1)]
+);
+generate_test!(
+    double_dot: "x..y",
+    [token!(TokenKind::Chars; 1), token!(TokenKind::Invalid; 2), token!(TokenKind::Chars; 1)],
+    [token!(TokenKind::Chars; 1), token!(TokenKind::Invalid; 2), token!(TokenKind::Chars; 1)]
+);
+generate_test!(
+    decimal_unchanged: "1.5",
+    [token!(TokenKind::Number(NumberKind::Decimal); 3)],
+    [token!(TokenKind::Number(NumberKind::Decimal); 3)]
+);
+
+#[test]
+fn default_is_standard() {
+    let input = "2 * (x + 1) / 3 ^ 2 % 4";
+
+    assert!(Tokenizer::from(input).eq(Tokenizer::with_config(input, LexerConfig::default())));
+    assert_eq!(Tokenizer::from(input).config(), LexerConfig::default());
+}
+
+#[test]
+fn peek_with_config() {
+    let tokenizer = Tokenizer::with_config("a.b ** 2", ALL);
+
+    assert_eq!(tokenizer.peek(1), Some(token!(TokenKind::Chars; 3)));
+    assert_eq!(tokenizer.peek(3), Some(token!(Kind![^]; 2)));
+}
+
+/// Relex an edit and check the tokens match lexing the edited text.
+fn relex(before: &str, range: std::ops::Range<usize>, text: &str) {
+    let after = format!("{}{}{}", &before[..range.start], text, &before[range.end..]);
+    let mut tokens = Tokenizer::with_config(before, ALL).collect::<Vec<_>>();
+
+    relex_with_config(&mut tokens, &after, &Edit { range, text }, ALL);
+    assert_eq!(
+        tokens,
+        Tokenizer::with_config(&after, ALL).collect::<Vec<_>>(),
+        "{:?} -> {:?}",
+        before,
+        after
+    );
+}
+
+#[test]
+fn relex_joins_dotted() {
+    // Removing the space after the dot makes `a.b` a single name.
+    relex("a. b", 2..3, "");
+}
+
+#[test]
+fn relex_splits_dotted() {
+    relex("a.b + 1", 2..2, " ");
+}
+
+#[test]
+fn relex_double_star() {
+    relex("2 * 3", 3..4, "*");
+}
+
+#[test]
+fn relex_comment() {
+    relex("1 + 2\n+ 3", 2..3, "#");
+    relex("1 # 2\n+ 3", 5..6, " ");
+}
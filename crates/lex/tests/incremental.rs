@@ -0,0 +1,117 @@
+use equation_lexer::{relex, Change, Edit, Token, Tokenizer};
+
+fn tokens(str: &str) -> Vec<Token> {
+    Tokenizer::from(str).collect()
+}
+
+/// Apply an edit to `before`, check the incremental tokens match lexing the edited text and return the change.
+fn check(before: &str, range: std::ops::Range<usize>, text: &str) -> Change {
+    let after = format!("{}{}{}", &before[..range.start], text, &before[range.end..]);
+    let old = tokens(before);
+    let mut updated = old.clone();
+
+    let change = relex(&mut updated, &after, &Edit { range, text });
+    assert_eq!(updated, tokens(&after), "{:?} -> {:?}", before, after);
+
+    // Tokens outside of the change are the same as before.
+    let kept = old.len() - change.removed;
+    assert_eq!(updated.len(), kept + change.tokens.len());
+    assert_eq!(updated[..change.tokens.start], old[..change.tokens.start]);
+    assert_eq!(
+        updated[change.tokens.end..],
+        old[change.tokens.start + change.removed..]
+    );
+
+    change
+}
+
+macro_rules! generate_test {
+    ($name: ident: $before: expr, $range: expr, $text: expr, $tokens: expr, $removed: expr) => {
+        #[test]
+        fn $name() {
+            let change = check($before, $range, $text);
+            assert_eq!(change.tokens, $tokens);
+            assert_eq!(change.removed, $removed);
+        }
+    };
+}
+
+generate_test!(replace_number: "1 + 2 * 3", 4..5, "42", 4..5, 1);
+generate_test!(insert_operator: "1 + 2", 5..5, " * 3", 5..9, 0);
+generate_test!(extend_name: "sin(x) + co", 11..11, "s", 7..8, 1);
+generate_test!(join_names: "ab cd", 2..3, "", 0..1, 3);
+generate_test!(split_name: "abcd + 1", 2..2, " ", 0..3, 1);
+generate_test!(delete_all: "1 + 2", 0..5, "", 0..0, 5);
+generate_test!(insert_into_empty: "", 0..0, "1 + 2", 0..5, 0);
+generate_test!(prepend: "+ 2", 0..0, "1 ", 0..2, 0);
+generate_test!(open_string: "1 + x + \"a\"", 4..4, "\"", 4..7, 5);
+generate_test!(close_string: "\"abc + 1", 4..4, "\"", 0..5, 1);
+generate_test!(multi_byte: "é + 1 + π", 3..4, "✓✓", 2..3, 1);
+generate_test!(invalid_run: "1 $$ 2", 3..3, "$", 2..3, 1);
+generate_test!(number_suffix: "0 + 1", 1..1, "x1f", 0..1, 1);
+
+/// Every range of each text replaced by each replacement.
+#[test]
+fn all_edits() {
+    let texts = [
+        "1 + 2 * 3",
+        "sin(x) ^ 2 != 0x1F",
+        "\"a\\\"b\" + 'c' + \"d",
+        "é, π; [1.5e3]",
+        "$$ a_b  \n 0b101",
+    ];
+    let replacements = ["", " ", "1", "ab", "\"", "'x", "$", "0x", "(", "é", "+ 3 *"];
+
+    for text in texts {
+        let boundaries = (0..=text.len())
+            .filter(|&i| text.is_char_boundary(i))
+            .collect::<Vec<_>>();
+
+        for (i, &start) in boundaries.iter().enumerate() {
+            for &end in &boundaries[i..] {
+                for replacement in replacements {
+                    check(text, start..end, replacement);
+                }
+            }
+        }
+    }
+}
+
+/// A sequence of edits, as if typed in an editor.
+#[test]
+fn typing() {
+    let target = "max(1, 2.5) * \"total\" + x_1";
+    let mut text = String::new();
+    let mut updated = Vec::new();
+
+    for c in target.chars() {
+        let mut buf = [0; 4];
+        let inserted = c.encode_utf8(&mut buf);
+        let edit = Edit {
+            range: text.len()..text.len(),
+            text: inserted,
+        };
+
+        text.push(c);
+        relex(&mut updated, &text, &edit);
+        assert_eq!(updated, tokens(&text));
+    }
+}
+
+#[test]
+fn invalid_edit() {
+    let mut updated = tokens("1 + 2");
+
+    // The range is past the end of the text, so all of the text is lexed again.
+    let change = relex(
+        &mut updated,
+        "3",
+        &Edit {
+            range: 9..12,
+            text: "",
+        },
+    );
+    assert_eq!(updated, tokens("3"));
+    assert_eq!(change.tokens, 0..1);
+    assert_eq!(change.removed, 5);
+}
@@ -35,22 +35,10 @@ fn sentence() {
     let mut tokenizer = Tokenizer::from("I'd just like to interject for a moment.");
 
     assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Invalid; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 4)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 4)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 2)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 9)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 3)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Ws; 1)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 6)));
-    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Invalid; 1)));
+    // The apostrophe starts a string literal which is never closed.
+    assert_eq!(
+        tokenizer.next(),
+        Some(token!(TokenKind::Str { terminated: false }; 39))
+    );
     assert_eq!(tokenizer.next(), None);
 }
@@ -1,4 +1,4 @@
-use equation_lexer::{Kind, NumberKind, Token, TokenKind, Tokenizer};
+use equation_lexer::{Brace, Kind, NumberKind, Token, TokenKind, Tokenizer};
 
 macro_rules! token {
     ($kind: expr; $len: expr) => {{
@@ -29,3 +29,16 @@ fn peek_two() {
     assert_eq!(tokenizer.peek(usize::MAX), None);
     assert_eq!(tokenizer.next(), None);
 }
+
+#[test]
+fn peek_past_multiple_characters() {
+    let tokenizer = Tokenizer::from("log2(\"ab\")");
+    assert_eq!(tokenizer.peek(1), Some(token!(TokenKind::Chars; 4)));
+    assert_eq!(tokenizer.peek(2), Some(token!(Brace![Round, Open]; 1)));
+    assert_eq!(
+        tokenizer.peek(3),
+        Some(token!(TokenKind::Str { terminated: true }; 4))
+    );
+    assert_eq!(tokenizer.peek(4), Some(token!(Brace![Round, Close]; 1)));
+    assert_eq!(tokenizer.peek(5), None);
+}
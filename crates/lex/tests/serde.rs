@@ -0,0 +1,57 @@
+//! Serializing tokens with the `serde` feature.
+
+#![cfg(feature = "serde")]
+
+use equation_lexer::{Brace, Kind, NumberKind, Token, TokenKind, Tokenizer};
+use serde_json::json;
+
+macro_rules! generate_test {
+    ($name: ident: $token: expr, $json: expr) => {
+        #[test]
+        fn $name() {
+            let token: Token = $token;
+            let json = serde_json::to_value(token).unwrap();
+
+            assert_eq!(json, $json);
+            assert_eq!(serde_json::from_value::<Token>(json).unwrap(), token);
+        }
+    };
+}
+
+macro_rules! token {
+    ($kind: expr; $len: expr) => {{
+        Token {
+            kind: $kind,
+            len: $len,
+        }
+    }};
+}
+
+generate_test!(plus: token!(Kind![+]; 1), json!({ "kind": "plus", "len": 1 }));
+generate_test!(
+    brace: token!(Brace![Square, Close]; 1),
+    json!({ "kind": { "brace": { "kind": "square", "open": false } }, "len": 1 })
+);
+generate_test!(
+    number: token!(TokenKind::Number(NumberKind::Hexadecimal); 4),
+    json!({ "kind": { "number": "hexadecimal" }, "len": 4 })
+);
+generate_test!(
+    string: token!(TokenKind::Str { terminated: false }; 3),
+    json!({ "kind": { "str": { "terminated": false } }, "len": 3 })
+);
+generate_test!(chars: token!(TokenKind::Chars; 3), json!({ "kind": "chars", "len": 3 }));
+generate_test!(ws: token!(TokenKind::Ws; 2), json!({ "kind": "ws", "len": 2 }));
+
+#[test]
+fn expression() {
+    let tokens = Tokenizer::from("sin(0x1f) + 'a'").collect::<Vec<_>>();
+    let json = serde_json::to_string(&tokens).unwrap();
+
+    assert_eq!(serde_json::from_str::<Vec<Token>>(&json).unwrap(), tokens);
+}
+
+#[test]
+fn unknown_kind() {
+    assert!(serde_json::from_value::<Token>(json!({ "kind": "arrow", "len": 2 })).is_err());
+}
@@ -42,9 +42,12 @@ generate_test!(eq: "=", token!(Kind![=]; 1));
 generate_test!(ge: ">", token!(Kind![>]; 1));
 generate_test!(le: "<", token!(Kind![<]; 1));
 generate_test!(amp: "&", token!(Kind![&]; 1));
+generate_test!(pipe: "|", token!(Kind![|]; 1));
 generate_test!(caret: "^", token!(Kind![^]; 1));
 generate_test!(exclamation: "!", token!(Kind![!]; 1));
 generate_test!(comma: ",", token!(Kind![,]; 1));
+generate_test!(semicolon: ";", token!(Kind![;]; 1));
+generate_test!(colon: ":", token!(Kind![:]; 1));
 generate_test!(open_round_brace: "(", token!(Brace![Round, Open]; 1));
 generate_test!(close_round_brace: ")", token!(Brace![Round, Close]; 1));
 generate_test!(open_square_brace: "[", token!(Brace![Square, Open]; 1));
@@ -0,0 +1,75 @@
+//! String literal tokens
+
+use equation_lexer::{Brace, Kind, Token, TokenKind, Tokenizer};
+
+macro_rules! token {
+    ($kind: expr; $len: expr) => {{
+        Token {
+            kind: $kind,
+            len: $len,
+        }
+    }};
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            const INPUT: &str = $input;
+
+            let mut parser = Tokenizer::from(INPUT);
+            assert_eq!(parser.next(), Some($expected));
+            // All tests use a single token, so the next fetch should return None.
+            assert_eq!(parser.next(), None);
+        }
+    };
+}
+
+generate_test!(empty_double: r#""""#, token!(TokenKind::Str { terminated: true }; 2));
+generate_test!(empty_single: "''", token!(TokenKind::Str { terminated: true }; 2));
+generate_test!(word: r#""Total""#, token!(TokenKind::Str { terminated: true }; 7));
+generate_test!(with_ws: r#""a b""#, token!(TokenKind::Str { terminated: true }; 5));
+generate_test!(other_quote: r#""it's""#, token!(TokenKind::Str { terminated: true }; 6));
+generate_test!(escaped_quote: r#""a\"b""#, token!(TokenKind::Str { terminated: true }; 6));
+generate_test!(escaped_backslash: r#""\\""#, token!(TokenKind::Str { terminated: true }; 4));
+generate_test!(multibyte: "'カニ'", token!(TokenKind::Str { terminated: true }; 4));
+generate_test!(unterminated: r#""abc"#, token!(TokenKind::Str { terminated: false }; 4));
+generate_test!(unterminated_escape: r#""\"#, token!(TokenKind::Str { terminated: false }; 2));
+generate_test!(escaped_end_quote: r#""\""#, token!(TokenKind::Str { terminated: false }; 3));
+
+#[test]
+fn consecutive() {
+    let mut tokenizer = Tokenizer::from("'''''''");
+    assert_eq!(
+        tokenizer.next(),
+        Some(token!(TokenKind::Str { terminated: true }; 2))
+    );
+    assert_eq!(
+        tokenizer.next(),
+        Some(token!(TokenKind::Str { terminated: true }; 2))
+    );
+    assert_eq!(
+        tokenizer.next(),
+        Some(token!(TokenKind::Str { terminated: true }; 2))
+    );
+    assert_eq!(
+        tokenizer.next(),
+        Some(token!(TokenKind::Str { terminated: false }; 1))
+    );
+    assert_eq!(tokenizer.next(), None);
+}
+
+#[test]
+fn concat_call() {
+    let mut tokenizer = Tokenizer::from(r#"concat("Total: ",x)"#);
+    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 6)));
+    assert_eq!(tokenizer.next(), Some(token!(Brace![Round, Open]; 1)));
+    assert_eq!(
+        tokenizer.next(),
+        Some(token!(TokenKind::Str { terminated: true }; 9))
+    );
+    assert_eq!(tokenizer.next(), Some(token!(Kind![,]; 1)));
+    assert_eq!(tokenizer.next(), Some(token!(TokenKind::Chars; 1)));
+    assert_eq!(tokenizer.next(), Some(token!(Brace![Round, Close]; 1)));
+    assert_eq!(tokenizer.next(), None);
+}
@@ -0,0 +1,91 @@
+//! Unicode operators and identifiers with the `unicode` feature.
+
+#![cfg(feature = "unicode")]
+
+use equation_lexer::{Kind, NumberKind, Token, TokenKind, Tokenizer};
+
+macro_rules! token {
+    ($kind: expr; $len: expr) => {{
+        Token {
+            kind: $kind,
+            len: $len,
+        }
+    }};
+}
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, [$($expected: expr),* $(,)?]) => {
+        #[test]
+        fn $name() {
+            let tokens = Tokenizer::from($input).collect::<Vec<_>>();
+            assert_eq!(tokens, [$($expected),*]);
+        }
+    };
+}
+
+const ONE: Token = token!(TokenKind::Number(NumberKind::Decimal); 1);
+
+generate_test!(times: "1×1", [ONE, token!(Kind![*]; 1), ONE]);
+generate_test!(middle_dot: "1·1", [ONE, token!(Kind![*]; 1), ONE]);
+generate_test!(dot_operator: "1⋅1", [ONE, token!(Kind![*]; 1), ONE]);
+generate_test!(divide: "1÷1", [ONE, token!(Kind![/]; 1), ONE]);
+generate_test!(minus: "−1", [token!(Kind![-]; 1), ONE]);
+generate_test!(less_equal: "1≤1", [ONE, token!(TokenKind::LessEqual; 1), ONE]);
+generate_test!(greater_equal: "1≥1", [ONE, token!(TokenKind::GreaterEqual; 1), ONE]);
+generate_test!(not_equal: "1≠1", [ONE, token!(TokenKind::NotEqual; 1), ONE]);
+generate_test!(root: "√1", [token!(TokenKind::Root; 1), ONE]);
+generate_test!(pi: "π", [token!(TokenKind::Chars; 1)]);
+generate_test!(infinity: "−∞", [token!(Kind![-]; 1), token!(TokenKind::Chars; 1)]);
+generate_test!(greek: "θ_0 + α", [
+    token!(TokenKind::Chars; 3),
+    token!(TokenKind::Ws; 1),
+    token!(Kind![+]; 1),
+    token!(TokenKind::Ws; 1),
+    token!(TokenKind::Chars; 1),
+]);
+// カニ is crab in Japanese
+generate_test!(kani: "カニ", [token!(TokenKind::Chars; 2)]);
+generate_test!(mixed_name: "größe_2", [token!(TokenKind::Chars; 7)]);
+generate_test!(square: "x²", [token!(TokenKind::Chars; 1), token!(TokenKind::Superscript; 1)]);
+generate_test!(superscripts: "10¹²³⁴⁵⁶⁷⁸⁹⁰", [
+    token!(TokenKind::Number(NumberKind::Decimal); 2),
+    token!(TokenKind::Superscript; 10),
+]);
+generate_test!(negative_superscript: "x⁻¹", [token!(TokenKind::Chars; 1), token!(TokenKind::Superscript; 2)]);
+generate_test!(lone_superscript_minus: "x⁻", [token!(TokenKind::Chars; 1), token!(TokenKind::Superscript; 1)]);
+generate_test!(split_superscripts: "x²⁻¹", [
+    token!(TokenKind::Chars; 1),
+    token!(TokenKind::Superscript; 1),
+    token!(TokenKind::Superscript; 2),
+]);
+generate_test!(middle_dot_between_names: "a·b", [
+    token!(TokenKind::Chars; 1),
+    token!(Kind![*]; 1),
+    token!(TokenKind::Chars; 1),
+]);
+generate_test!(symbols_after_name: "x×y⋅z÷w−v≠u", [
+    token!(TokenKind::Chars; 1),
+    token!(Kind![*]; 1),
+    token!(TokenKind::Chars; 1),
+    token!(Kind![*]; 1),
+    token!(TokenKind::Chars; 1),
+    token!(Kind![/]; 1),
+    token!(TokenKind::Chars; 1),
+    token!(Kind![-]; 1),
+    token!(TokenKind::Chars; 1),
+    token!(TokenKind::NotEqual; 1),
+    token!(TokenKind::Chars; 1),
+]);
+generate_test!(infinity_after_name: "x∞", [token!(TokenKind::Chars; 1), token!(TokenKind::Chars; 1)]);
+generate_test!(emoji: "🙃🙃", [token!(TokenKind::Invalid; 2)]);
+generate_test!(subscript: "x₀", [token!(TokenKind::Chars; 1), token!(TokenKind::Invalid; 1)]);
+
+#[test]
+fn peek_multibyte() {
+    let tokenizer = Tokenizer::from("α² ≤ √β");
+
+    assert_eq!(tokenizer.peek(2), Some(token!(TokenKind::Superscript; 1)));
+    assert_eq!(tokenizer.peek(4), Some(token!(TokenKind::LessEqual; 1)));
+    assert_eq!(tokenizer.peek(7), Some(token!(TokenKind::Chars; 1)));
+    assert_eq!(tokenizer.peek(8), None);
+}
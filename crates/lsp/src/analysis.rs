@@ -0,0 +1,386 @@
+//! Analysis of the text of a document.
+//!
+//! Each function takes the full text of the document and, where names are resolved, the [`Resolver`] used to
+//! evaluate it.
+
+use equation_eval::{
+    eval_with_limits,
+    highlight::{self, Class},
+    limits::EvalLimits,
+    node::{Node, Span, Spanned},
+    resolve::Resolver,
+};
+use equation_lexer::{LexerConfig, Token, TokenKind, Tokenizer};
+use equation_math::{self as math, Signature};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, Documentation, Hover,
+    HoverContents, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position,
+    SemanticToken, SemanticTokenType, SemanticTokensLegend, SignatureHelp, SignatureInformation,
+};
+
+use crate::{
+    builtins,
+    position::{self, advance},
+};
+
+/// The token types of the [`semantic_tokens`], in the order of their index.
+pub const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::COMMENT,
+];
+
+const NUMBER: u32 = 0;
+const STRING: u32 = 1;
+const OPERATOR: u32 = 2;
+const FUNCTION: u32 = 3;
+const VARIABLE: u32 = 4;
+const KEYWORD: u32 = 5;
+const PARAMETER: u32 = 6;
+const COMMENT: u32 = 7;
+
+/// The legend describing the [`semantic_tokens`].
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: Vec::new(),
+    }
+}
+
+/// The errors of an expression.
+///
+/// If the expression parses, it is evaluated and the error of evaluation is reported. Errors for unknown names
+/// suggest similar names. Documents are untrusted, so they are parsed and evaluated within the default
+/// [`EvalLimits`].
+pub fn diagnostics(resolver: &dyn Resolver, text: &str) -> Vec<Diagnostic> {
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let limits = EvalLimits::default();
+    let (_, mut errors) = Node::parse_recovering_with_limits(text, &limits);
+
+    if errors.is_empty() {
+        errors.extend(eval_with_limits(resolver, text, &limits).err());
+    }
+
+    errors
+        .iter()
+        .map(|error| {
+            let message = match error.help() {
+                Some(help) => format!("{}, {}", error.kind, help),
+                None => error.kind.to_string(),
+            };
+
+            Diagnostic {
+                range: position::range(text, error.span),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("equation".into()),
+                message,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// The semantic tokens of the text, relative to each other as described by the protocol.
+///
+/// Tokens are classified by [`highlight::semantic`], which parses the document within the default [`EvalLimits`].
+/// Whitespace, braces, separators and invalid tokens are not included.
+pub fn semantic_tokens(resolver: &dyn Resolver, text: &str) -> Vec<SemanticToken> {
+    semantic_tokens_with_config(resolver, text, LexerConfig::default())
+}
+
+/// The semantic tokens of text in a dialect, such as one with `#` comments which are [`COMMENT`] tokens.
+///
+/// [`COMMENT`]: SemanticTokenType::COMMENT
+pub fn semantic_tokens_with_config(
+    resolver: &dyn Resolver,
+    text: &str,
+    config: LexerConfig,
+) -> Vec<SemanticToken> {
+    let mut semantic = Vec::new();
+    let mut position = Position::new(0, 0);
+    let mut previous = Position::new(0, 0);
+
+    let limits = EvalLimits::default();
+
+    for highlight in highlight::semantic_with_config(resolver, text, config, &limits) {
+        let start = position;
+
+        text[highlight.span.start..highlight.span.end]
+            .chars()
+            .for_each(|c| advance(&mut position, c));
+
+        let token_type = match highlight.class {
+            Class::Number => NUMBER,
+            Class::String => STRING,
+            Class::Operator => OPERATOR,
+            Class::Function => FUNCTION,
+            Class::Constant | Class::Name => VARIABLE,
+            Class::Keyword => KEYWORD,
+            Class::Variable => PARAMETER,
+            Class::Comment => COMMENT,
+            Class::Whitespace | Class::Brace | Class::Separator | Class::Invalid => continue,
+        };
+
+        // Tokens may not span multiple lines.
+        if start.line != position.line {
+            continue;
+        }
+
+        let delta_start = if start.line == previous.line {
+            start.character - previous.character
+        } else {
+            start.character
+        };
+
+        semantic.push(SemanticToken {
+            delta_line: start.line - previous.line,
+            delta_start,
+            length: position.character - start.character,
+            token_type,
+            token_modifiers_bitset: 0,
+        });
+        previous = start;
+    }
+
+    semantic
+}
+
+/// The value of the smallest sub-expression at a position, and the description of the constant or function under it.
+///
+/// The document is parsed and the sub-expression evaluated within the default [`EvalLimits`].
+pub fn hover(resolver: &dyn Resolver, text: &str, position: Position) -> Option<Hover> {
+    let offset = position::offset(text, position);
+    let mut sections = Vec::new();
+    let mut span = None;
+
+    let limits = EvalLimits::default();
+
+    if let Ok(nodes) = Node::parse_with_limits(text, &limits) {
+        let smallest = nodes
+            .filter(|node| !matches!(node, Node::Jump(_) | Node::Error(_)))
+            .map(|node| node.span())
+            .filter(|span| span.start <= offset && offset < span.end)
+            .min_by_key(|span| span.end - span.start);
+
+        if let Some(smallest) = smallest {
+            let expr = &text[smallest.start..smallest.end];
+
+            if let Ok(value) = eval_with_limits(resolver, expr, &limits) {
+                sections.push(format!("```\n{} = {}\n```", expr, value));
+                span = Some(smallest);
+            }
+        }
+    }
+
+    let name = tokens(text).into_iter().find(|(token, span)| {
+        token.kind == TokenKind::Chars && span.start <= offset && offset < span.end
+    });
+
+    if let Some((_, name_span)) = name {
+        if let Some(signature) = signature(resolver, &text[name_span.start..name_span.end]) {
+            sections.push(format!("```\n{}\n```\n{}", label(signature), signature.doc));
+            span = span.or(Some(name_span));
+        }
+    }
+
+    if sections.is_empty() {
+        return None;
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: sections.join("\n\n"),
+        }),
+        range: span.map(|span| position::range(text, span)),
+    })
+}
+
+/// The names starting with the word before a position.
+///
+/// Names are the constants and functions of the resolver, the built-in functions and the boolean literals.
+pub fn completion(resolver: &dyn Resolver, text: &str, position: Position) -> Vec<CompletionItem> {
+    let offset = position::offset(text, position);
+    let prefix = word_before(text, offset);
+
+    if prefix.starts_with(|c: char| c.is_ascii_digit()) {
+        return Vec::new();
+    }
+
+    let constants = resolver
+        .const_symbols()
+        .into_iter()
+        .map(|name| (name, CompletionItemKind::CONSTANT));
+    let functions = resolver
+        .functions()
+        .into_iter()
+        .chain(builtins::FUNCTIONS.iter().map(|signature| signature.name))
+        .map(|name| (name, CompletionItemKind::FUNCTION));
+    let keywords = ["true", "false"]
+        .into_iter()
+        .map(|name| (name, CompletionItemKind::KEYWORD));
+
+    let mut items: Vec<CompletionItem> = Vec::new();
+
+    for (name, kind) in constants.chain(functions).chain(keywords) {
+        if !name.starts_with(prefix) || items.iter().any(|item| item.label == name) {
+            continue;
+        }
+
+        let signature = signature(resolver, name);
+
+        items.push(CompletionItem {
+            label: name.into(),
+            kind: Some(kind),
+            detail: signature.map(label),
+            documentation: signature.map(|signature| Documentation::String(signature.doc.into())),
+            ..Default::default()
+        });
+    }
+
+    items
+}
+
+/// The signature of the innermost call around a position and the parameter the position is in.
+pub fn signature_help(
+    resolver: &dyn Resolver,
+    text: &str,
+    position: Position,
+) -> Option<SignatureHelp> {
+    let offset = position::offset(text, position);
+
+    // The braces which are open at the position, with the name of the function for calls and the number of commas.
+    let mut open: Vec<(Option<&str>, u32)> = Vec::new();
+    let mut name = None;
+
+    for (token, span) in tokens(text) {
+        if span.start >= offset {
+            break;
+        }
+
+        match token.kind {
+            TokenKind::Ws => continue,
+            TokenKind::Brace { open: true, kind } => {
+                let call = name.filter(|_| kind == equation_lexer::BraceKind::Round);
+                open.push((call, 0));
+            }
+            TokenKind::Brace { open: false, .. } => {
+                open.pop();
+            }
+            TokenKind::Comma => {
+                if let Some((_, commas)) = open.last_mut() {
+                    *commas += 1;
+                }
+            }
+            _ => {}
+        }
+
+        name = (token.kind == TokenKind::Chars).then(|| &text[span.start..span.end]);
+    }
+
+    let (Some(name), commas) = *open.last()? else {
+        return None;
+    };
+    let signature = signature(resolver, name)?;
+
+    // Further arguments of a function taking any number of arguments are the last parameter.
+    let variadic = signature
+        .params
+        .last()
+        .is_some_and(|param| param.ends_with("..."));
+    let active = match variadic {
+        true => commas.min(signature.params.len() as u32 - 1),
+        false => commas,
+    };
+
+    let label = label(signature);
+    let mut start = signature.name.len() + 1;
+    let parameters = signature
+        .params
+        .iter()
+        .map(|param| {
+            let offsets = [start as u32, (start + param.len()) as u32];
+            start += param.len() + 2;
+
+            ParameterInformation {
+                label: ParameterLabel::LabelOffsets(offsets),
+                documentation: None,
+            }
+        })
+        .collect();
+
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: Some(Documentation::String(signature.doc.into())),
+            parameters: Some(parameters),
+            active_parameter: Some(active),
+        }],
+        active_signature: Some(0),
+        active_parameter: Some(active),
+    })
+}
+
+/// The signature of a built-in function, or of a constant or function of [`Math`](math::Math) which the resolver
+/// provides.
+fn signature(resolver: &dyn Resolver, name: &str) -> Option<&'static Signature> {
+    let provided = resolver.has_function(name) || resolver.resolve_const_symbol(name).is_some();
+
+    builtins::FUNCTIONS
+        .iter()
+        .find(|signature| signature.name == name)
+        .or_else(|| {
+            math::CONSTANTS
+                .iter()
+                .chain(math::FUNCTIONS)
+                .find(|signature| provided && signature.name == name)
+        })
+}
+
+/// `name` for a constant and `name(params...)` for a function.
+fn label(signature: &Signature) -> String {
+    if signature.params.is_empty() {
+        return signature.name.into();
+    }
+
+    format!("{}({})", signature.name, signature.params.join(", "))
+}
+
+/// The name characters directly before an offset.
+fn word_before(text: &str, offset: usize) -> &str {
+    let before = &text[..offset];
+    let start = before
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+        .last()
+        .map_or(offset, |(i, _)| i);
+
+    &before[start..]
+}
+
+/// The tokens of the text with their byte spans.
+fn tokens(text: &str) -> Vec<(Token, Span)> {
+    let mut start = 0;
+
+    Tokenizer::from(text)
+        .map(|token| {
+            let end = text[start..]
+                .char_indices()
+                .nth(token.len)
+                .map_or(text.len(), |(len, _)| start + len);
+            let span = Span { start, end };
+
+            start = end;
+            (token, span)
+        })
+        .collect()
+}
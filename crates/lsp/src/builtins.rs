@@ -0,0 +1,98 @@
+//! Signatures of the functions built into the evaluator.
+
+use equation_math::Signature;
+
+/// The built-in functions.
+///
+/// A parameter ending in `...` takes any number of arguments.
+pub(crate) const FUNCTIONS: &[Signature] = &[
+    function(
+        "if",
+        &["condition", "then", "else"],
+        "The second argument if the condition is true, otherwise the third.",
+    ),
+    function(
+        "concat",
+        &["values..."],
+        "Join the textual representation of each value.",
+    ),
+    function(
+        "len",
+        &["values..."],
+        "The number of characters in a string, otherwise the number of values.",
+    ),
+    function("upper", &["str"], "The string in upper case."),
+    function("lower", &["str"], "The string in lower case."),
+    function(
+        "substr",
+        &["str", "start", "count"],
+        "A range of characters of a string.",
+    ),
+    function(
+        "contains",
+        &["str", "pattern"],
+        "Whether the string contains the pattern.",
+    ),
+    function("to_number", &["str"], "Parse a string as a decimal number."),
+    function(
+        "format",
+        &["template", "values..."],
+        "Replace each `{}` in the template with the next value.",
+    ),
+    function("sum", &["values..."], "The sum of the values."),
+    function("avg", &["values..."], "The arithmetic mean."),
+    function("min", &["values..."], "The smallest value."),
+    function("max", &["values..."], "The largest value."),
+    function(
+        "median",
+        &["values..."],
+        "The middle value, or the mean of the two middle values.",
+    ),
+    function(
+        "stddev",
+        &["values..."],
+        "The population standard deviation.",
+    ),
+    function("transpose", &["matrix"], "The transpose of a matrix."),
+    function("det", &["matrix"], "The determinant of a square matrix."),
+    function("inverse", &["matrix"], "The inverse of a square matrix."),
+    function("identity", &["n"], "The n by n identity matrix."),
+    function(
+        "map",
+        &["list", "f"],
+        "The value of the closure for each element.",
+    ),
+    function(
+        "filter",
+        &["list", "f"],
+        "The elements for which the closure is true.",
+    ),
+    function(
+        "reduce",
+        &["list", "f", "initial"],
+        "Combine the elements from first to last.",
+    ),
+    function(
+        "prod",
+        &["k", "from", "to", "body"],
+        "The product of the body for each integer k from `from` to `to`.",
+    ),
+    function(
+        "integrate",
+        &["expr", "x", "from", "to"],
+        "The definite integral of the expression over x.",
+    ),
+    function(
+        "root",
+        &["expr", "x", "guess"],
+        "A value of x near the guess where the expression is zero.",
+    ),
+];
+
+const fn function(
+    name: &'static str,
+    params: &'static [&'static str],
+    doc: &'static str,
+) -> Signature {
+    Signature { name, params, doc }
+}
@@ -0,0 +1,10 @@
+//! A language server for expressions.
+//!
+//! [`analysis`] answers questions about the text of a document, such as the diagnostics of an expression or the value
+//! of a sub-expression, and [`server`] answers the requests of a client over a [`Connection`](lsp_server::Connection).
+//! The server resolves names with [`Math`](equation_math::Math), which the command line tools share.
+
+pub mod analysis;
+mod builtins;
+pub mod position;
+pub mod server;
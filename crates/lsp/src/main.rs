@@ -0,0 +1,16 @@
+use std::error::Error;
+
+use equation_lsp::server::{self, Server};
+use equation_math::Math;
+use lsp_server::Connection;
+
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+
+    connection.initialize(serde_json::to_value(server::capabilities())?)?;
+    Server::new(&Math).run(&connection)?;
+
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
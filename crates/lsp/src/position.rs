@@ -0,0 +1,59 @@
+//! Conversion between byte offsets and positions.
+//!
+//! The protocol addresses text by line and character, where characters are counted in UTF-16 code units.
+
+use equation_eval::node::Span;
+use lsp_types::{Position, Range};
+
+/// The position of a byte offset in the text.
+///
+/// Offsets past the end of the text are clamped to the end.
+pub fn position(text: &str, offset: usize) -> Position {
+    let mut position = Position::new(0, 0);
+
+    for (i, c) in text.char_indices() {
+        if i >= offset {
+            break;
+        }
+
+        advance(&mut position, c);
+    }
+
+    position
+}
+
+/// The byte offset of a position in the text.
+///
+/// A position past the end of a line is the end of the line and a line past the end of the text is the end of the
+/// text.
+pub fn offset(text: &str, position: Position) -> usize {
+    let mut current = Position::new(0, 0);
+
+    for (i, c) in text.char_indices() {
+        if current.line > position.line
+            || (current.line == position.line && current.character >= position.character)
+            || (current.line == position.line && c == '\n')
+        {
+            return i;
+        }
+
+        advance(&mut current, c);
+    }
+
+    text.len()
+}
+
+/// The range of a span in the text.
+pub fn range(text: &str, span: Span) -> Range {
+    Range::new(position(text, span.start), position(text, span.end))
+}
+
+/// Move a position past a character.
+pub(crate) fn advance(position: &mut Position, c: char) {
+    if c == '\n' {
+        position.line += 1;
+        position.character = 0;
+    } else {
+        position.character += c.len_utf16() as u32;
+    }
+}
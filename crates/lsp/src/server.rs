@@ -0,0 +1,239 @@
+//! Answering the requests of a client.
+//!
+//! Documents are synchronised in full, each change replaces the text of the document and publishes its diagnostics.
+
+use std::{collections::HashMap, error::Error};
+
+use equation_eval::resolve::Resolver;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{
+        Completion, HoverRequest, Request as _, SemanticTokensFullRequest, SignatureHelpRequest,
+    },
+    CompletionOptions, CompletionResponse, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, HoverProviderCapability, PublishDiagnosticsParams, SemanticTokens,
+    SemanticTokensFullOptions, SemanticTokensOptions, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelpOptions,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::de::DeserializeOwned;
+
+use crate::analysis;
+
+/// The capabilities of the server, sent in response to the `initialize` request.
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            ..Default::default()
+        }),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: analysis::legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+/// The state of the server, which is the text of each open document.
+pub struct Server<'a> {
+    resolver: &'a dyn Resolver,
+    documents: HashMap<Url, String>,
+}
+
+impl<'a> Server<'a> {
+    /// Create a server resolving names with `resolver`.
+    pub fn new(resolver: &'a dyn Resolver) -> Self {
+        Self {
+            resolver,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Handle messages from an initialized connection until the client shuts the server down.
+    pub fn run(&mut self, connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+
+                    let response = self.request(request);
+                    connection.sender.send(Message::Response(response))?;
+                }
+
+                Message::Notification(notification) => {
+                    if let Some(notification) = self.notification(notification) {
+                        connection
+                            .sender
+                            .send(Message::Notification(notification))?;
+                    }
+                }
+
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Answer a request.
+    pub fn request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => self.respond::<HoverRequest>(request, |text, params| {
+                let position = params.text_document_position_params.position;
+                analysis::hover(self.resolver, text, position)
+            }),
+
+            Completion::METHOD => self.respond::<Completion>(request, |text, params| {
+                let position = params.text_document_position.position;
+                let items = analysis::completion(self.resolver, text, position);
+                Some(CompletionResponse::Array(items))
+            }),
+
+            SignatureHelpRequest::METHOD => {
+                self.respond::<SignatureHelpRequest>(request, |text, params| {
+                    let position = params.text_document_position_params.position;
+                    analysis::signature_help(self.resolver, text, position)
+                })
+            }
+
+            SemanticTokensFullRequest::METHOD => {
+                self.respond::<SemanticTokensFullRequest>(request, |text, _| {
+                    Some(SemanticTokensResult::Tokens(SemanticTokens {
+                        result_id: None,
+                        data: analysis::semantic_tokens(self.resolver, text),
+                    }))
+                })
+            }
+
+            method => Err((
+                ErrorCode::MethodNotFound,
+                format!("unknown request `{}`", method),
+            )),
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    /// Handle a notification, returning the diagnostics to publish if a document changed.
+    pub fn notification(&mut self, notification: Notification) -> Option<Notification> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = params(notification.params).ok()?;
+                let document = params.text_document;
+
+                self.documents.insert(document.uri.clone(), document.text);
+                document.uri
+            }
+
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = params(notification.params).ok()?;
+                let uri = params.text_document.uri;
+                let text = params.content_changes.into_iter().last()?.text;
+
+                self.documents.insert(uri.clone(), text);
+                uri
+            }
+
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = params(notification.params).ok()?;
+                let uri = params.text_document.uri;
+
+                // Clear the diagnostics of the closed document.
+                self.documents.remove(&uri);
+                uri
+            }
+
+            _ => return None,
+        };
+
+        let diagnostics = self
+            .documents
+            .get(&uri)
+            .map(|text| analysis::diagnostics(self.resolver, text))
+            .unwrap_or_default();
+
+        Some(Notification::new(
+            PublishDiagnostics::METHOD.into(),
+            PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            },
+        ))
+    }
+
+    /// Answer a request about a document with the result of `f` for the text of the document.
+    fn respond<R>(
+        &self,
+        request: Request,
+        f: impl FnOnce(&str, R::Params) -> R::Result,
+    ) -> Result<serde_json::Value, (ErrorCode, String)>
+    where
+        R: lsp_types::request::Request,
+        R::Params: Document,
+    {
+        let params: R::Params = params(request.params)
+            .map_err(|error| (ErrorCode::InvalidParams, error.to_string()))?;
+        let text = self.documents.get(params.uri()).ok_or_else(|| {
+            (
+                ErrorCode::InvalidParams,
+                format!("unknown document `{}`", params.uri()),
+            )
+        })?;
+
+        serde_json::to_value(f(text, params))
+            .map_err(|error| (ErrorCode::InternalError, error.to_string()))
+    }
+}
+
+/// The parameters of a request or notification about a document.
+trait Document {
+    fn uri(&self) -> &Url;
+}
+
+impl Document for lsp_types::HoverParams {
+    fn uri(&self) -> &Url {
+        &self.text_document_position_params.text_document.uri
+    }
+}
+
+impl Document for lsp_types::CompletionParams {
+    fn uri(&self) -> &Url {
+        &self.text_document_position.text_document.uri
+    }
+}
+
+impl Document for lsp_types::SignatureHelpParams {
+    fn uri(&self) -> &Url {
+        &self.text_document_position_params.text_document.uri
+    }
+}
+
+impl Document for lsp_types::SemanticTokensParams {
+    fn uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+/// Deserialize the parameters of a request or notification.
+fn params<P: DeserializeOwned>(params: serde_json::Value) -> serde_json::Result<P> {
+    serde_json::from_value(params)
+}
@@ -0,0 +1,284 @@
+//! Analysis of documents
+
+use equation_lexer::LexerConfig;
+use equation_lsp::{
+    analysis::{
+        completion, diagnostics, hover, semantic_tokens, semantic_tokens_with_config,
+        signature_help, TOKEN_TYPES,
+    },
+    position::{offset, position},
+};
+use equation_math::Math;
+use lsp_types::{HoverContents, Position, Range, SemanticTokenType};
+
+/// The position at the end of a single line.
+fn end(text: &str) -> Position {
+    Position::new(0, text.len() as u32)
+}
+
+fn hover_text(text: &str, character: u32) -> Option<String> {
+    match hover(&Math, text, Position::new(0, character))?.contents {
+        HoverContents::Markup(markup) => Some(markup.value),
+        _ => None,
+    }
+}
+
+fn completions(text: &str) -> Vec<String> {
+    completion(&Math, text, end(text))
+        .into_iter()
+        .map(|item| item.label)
+        .collect()
+}
+
+/// The active parameter and label of the signature at the end of the text.
+fn signature(text: &str) -> Option<(u32, String)> {
+    let help = signature_help(&Math, text, end(text))?;
+    let signature = help.signatures.into_iter().next()?;
+
+    Some((help.active_parameter?, signature.label))
+}
+
+macro_rules! generate_diagnostics {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let messages = diagnostics(&Math, $input)
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>();
+            let expected: &[&str] = &$expected;
+            assert_eq!(messages, expected);
+        }
+    };
+}
+
+generate_diagnostics!(valid: "sqrt(16) + pi", []);
+generate_diagnostics!(empty: "  ", []);
+generate_diagnostics!(unexpected_end: "1 +", ["unexpected end of expression"]);
+generate_diagnostics!(every_parse_error: "1 + ) * (2 +", ["unexpected token", "unexpected end of expression"]);
+generate_diagnostics!(eval_error: "1 / 0", ["division by zero"]);
+generate_diagnostics!(suggestion: "sqr(2)", ["unknown function, did you mean `sqrt`?"]);
+generate_diagnostics!(arity: "round(1)", ["expected 2 arguments, found 1"]);
+generate_diagnostics!(limits: "len(identity(1000))", ["too many elements"]);
+
+#[test]
+fn diagnostic_range() {
+    let diagnostics = diagnostics(&Math, "1 +\n  ln(0)");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].range,
+        Range::new(Position::new(1, 2), Position::new(1, 7))
+    );
+}
+
+#[test]
+fn semantic() {
+    let tokens = semantic_tokens(&Math, "sin(x) + map([pi],\n  y -> y)")
+        .into_iter()
+        .map(|token| {
+            (
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                &TOKEN_TYPES[token.token_type as usize],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        tokens,
+        [
+            (0, 0, 3, &SemanticTokenType::FUNCTION),
+            (0, 4, 1, &SemanticTokenType::VARIABLE),
+            (0, 3, 1, &SemanticTokenType::OPERATOR),
+            (0, 2, 3, &SemanticTokenType::FUNCTION),
+            (0, 5, 2, &SemanticTokenType::VARIABLE),
+            (1, 2, 1, &SemanticTokenType::PARAMETER),
+            (0, 2, 1, &SemanticTokenType::OPERATOR),
+            (0, 1, 1, &SemanticTokenType::OPERATOR),
+            (0, 2, 1, &SemanticTokenType::PARAMETER),
+        ]
+    );
+}
+
+#[test]
+fn semantic_comment() {
+    let config = LexerConfig {
+        hash_comments: true,
+        ..LexerConfig::default()
+    };
+    let tokens = semantic_tokens_with_config(&Math, "x + 1 # one", config)
+        .into_iter()
+        .map(|token| {
+            (
+                token.delta_start,
+                token.length,
+                &TOKEN_TYPES[token.token_type as usize],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(tokens.last(), Some(&(2, 5, &SemanticTokenType::COMMENT)));
+    // Without the configuration, `#` is an invalid token and not highlighted.
+    assert!(semantic_tokens(&Math, "x + 1 # one")
+        .iter()
+        .all(|token| TOKEN_TYPES[token.token_type as usize] != SemanticTokenType::COMMENT));
+}
+
+#[test]
+fn semantic_utf16() {
+    // The string is 3 UTF-16 code units long.
+    let tokens = semantic_tokens(&Math, "'😀' + 1");
+
+    assert_eq!(tokens[0].length, 4);
+    assert_eq!(tokens[1].delta_start, 5);
+}
+
+#[test]
+fn hover_sub_expression() {
+    let text = hover_text("1 + 2 * 3", 6).unwrap();
+    assert!(text.contains("2 * 3 = 6"), "{}", text);
+
+    let range = hover(&Math, "1 + 2 * 3", Position::new(0, 6))
+        .unwrap()
+        .range
+        .unwrap();
+    assert_eq!(range, Range::new(Position::new(0, 4), Position::new(0, 9)));
+}
+
+#[test]
+fn parse_limits() {
+    let messages = |text: &str| {
+        diagnostics(&Math, text)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect::<Vec<_>>()
+    };
+
+    let text = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+    assert_eq!(messages(&text), ["input is too long"]);
+
+    let text = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+    assert_eq!(messages(&text), ["braces are nested too deeply"]);
+    assert!(hover(&Math, &text, Position::new(0, 100)).is_none());
+
+    let text = "inverse(identity(250))";
+    assert_eq!(messages(text), ["evaluation step limit reached"]);
+    assert!(hover(&Math, text, Position::new(0, 1)).is_some());
+}
+
+#[test]
+fn semantic_limits() {
+    let types = |depth: usize| {
+        let text = format!("{}map([1], x -> x){}", "(".repeat(depth), ")".repeat(depth));
+
+        semantic_tokens(&Math, &text)
+            .into_iter()
+            .map(|token| TOKEN_TYPES[token.token_type as usize].clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(types(2).contains(&SemanticTokenType::PARAMETER));
+
+    // Too deeply nested to parse, so the parameter is not known.
+    let types = types(100_000);
+    assert!(types.contains(&SemanticTokenType::FUNCTION));
+    assert!(!types.contains(&SemanticTokenType::PARAMETER));
+}
+
+#[test]
+fn hover_function() {
+    let text = hover_text("sqrt(16)", 1).unwrap();
+
+    assert!(text.contains("sqrt(16) = 4"), "{}", text);
+    assert!(text.contains("sqrt(x)"), "{}", text);
+    assert!(text.contains("The square root of x."), "{}", text);
+}
+
+#[test]
+fn hover_nothing() {
+    assert_eq!(hover_text("  1", 0), None);
+    assert_eq!(hover_text("1 +", 0), None);
+}
+
+#[test]
+fn complete_prefix() {
+    let labels = completions("1 + s");
+
+    for name in ["sqrt", "sin", "substr", "sum", "stddev"] {
+        assert!(labels.iter().any(|label| label == name), "{}", name);
+    }
+
+    assert!(labels.iter().all(|label| label.starts_with('s')));
+}
+
+#[test]
+fn complete_everything() {
+    let labels = completions("");
+
+    for name in ["pi", "e", "round", "if", "map", "true"] {
+        assert!(labels.iter().any(|label| label == name), "{}", name);
+    }
+}
+
+#[test]
+fn complete_number() {
+    assert!(completions("1").is_empty());
+}
+
+#[test]
+fn complete_documentation() {
+    let items = completion(&Math, "rou", end("rou"));
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].detail.as_deref(), Some("round(x, digits)"));
+}
+
+#[test]
+fn signature_first() {
+    assert_eq!(signature("round("), Some((0, "round(x, digits)".into())));
+}
+
+#[test]
+fn signature_second() {
+    assert_eq!(signature("round(1, "), Some((1, "round(x, digits)".into())));
+}
+
+#[test]
+fn signature_nested() {
+    assert_eq!(signature("round(sqrt("), Some((0, "sqrt(x)".into())));
+    assert_eq!(
+        signature("round(sqrt(2), "),
+        Some((1, "round(x, digits)".into()))
+    );
+    assert_eq!(
+        signature("round([1, 2, "),
+        None,
+        "commas in a list are not arguments"
+    );
+}
+
+#[test]
+fn signature_variadic() {
+    assert_eq!(signature("max(1, 2, 3"), Some((0, "max(values...)".into())));
+}
+
+#[test]
+fn signature_outside() {
+    assert_eq!(signature("round(1, 2)"), None);
+    assert_eq!(signature("(1, 2"), None);
+    assert_eq!(signature("unknown(1"), None);
+}
+
+#[test]
+fn positions() {
+    let text = "é\n😀x";
+    let x = text.find('x').unwrap();
+
+    assert_eq!(position(text, x), Position::new(1, 2));
+    assert_eq!(offset(text, Position::new(1, 2)), x);
+    assert_eq!(offset(text, Position::new(0, 10)), 2);
+    assert_eq!(offset(text, Position::new(5, 0)), text.len());
+    assert_eq!(position(text, 100), Position::new(1, 3));
+}
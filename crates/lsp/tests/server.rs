@@ -0,0 +1,135 @@
+//! Requests and notifications over a connection
+
+use std::thread;
+
+use equation_lsp::server::{capabilities, Server};
+use equation_math::Math;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{HoverRequest, Initialize, Request as _, Shutdown},
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, Hover, HoverContents, HoverParams,
+    InitializeParams, InitializedParams, Position, PublishDiagnosticsParams,
+    TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+    TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+use serde::de::DeserializeOwned;
+
+fn uri() -> Url {
+    Url::parse("file:///expression.eq").unwrap()
+}
+
+fn request<P: serde::Serialize>(client: &Connection, id: i32, method: &str, params: P) -> Response {
+    let request = Request::new(RequestId::from(id), method.into(), params);
+    client.sender.send(Message::Request(request)).unwrap();
+
+    match client.receiver.recv().unwrap() {
+        Message::Response(response) => response,
+        message => panic!("expected a response, found {:?}", message),
+    }
+}
+
+fn notify<P: serde::Serialize>(client: &Connection, method: &str, params: P) {
+    let notification = Notification::new(method.into(), params);
+    client
+        .sender
+        .send(Message::Notification(notification))
+        .unwrap();
+}
+
+fn receive<P: DeserializeOwned>(client: &Connection, method: &str) -> P {
+    match client.receiver.recv().unwrap() {
+        Message::Notification(notification) if notification.method == method => {
+            serde_json::from_value(notification.params).unwrap()
+        }
+        message => panic!("expected {}, found {:?}", method, message),
+    }
+}
+
+#[test]
+fn session() {
+    let (client, server) = Connection::memory();
+
+    let thread = thread::spawn(move || {
+        server
+            .initialize(serde_json::to_value(capabilities()).unwrap())
+            .unwrap();
+        Server::new(&Math).run(&server).unwrap();
+    });
+
+    #[allow(deprecated)]
+    let initialize = InitializeParams::default();
+    let response = request(&client, 1, Initialize::METHOD, initialize);
+    assert!(response.error.is_none());
+    notify(&client, Initialized::METHOD, InitializedParams {});
+
+    // Opening a document publishes its diagnostics.
+    notify(
+        &client,
+        DidOpenTextDocument::METHOD,
+        DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri(), "equation".into(), 1, "sqr(4)".into()),
+        },
+    );
+    let published: PublishDiagnosticsParams = receive(&client, PublishDiagnostics::METHOD);
+    assert_eq!(published.uri, uri());
+    assert_eq!(published.diagnostics.len(), 1);
+
+    // Fixing the document clears them.
+    notify(
+        &client,
+        DidChangeTextDocument::METHOD,
+        DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier::new(uri(), 2),
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "sqrt(4) + 1".into(),
+            }],
+        },
+    );
+    let published: PublishDiagnosticsParams = receive(&client, PublishDiagnostics::METHOD);
+    assert!(published.diagnostics.is_empty());
+
+    let response = request(
+        &client,
+        2,
+        HoverRequest::METHOD,
+        HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(uri()),
+                Position::new(0, 2),
+            ),
+            work_done_progress_params: Default::default(),
+        },
+    );
+    let hover: Hover = serde_json::from_value(response.result.unwrap()).unwrap();
+    let HoverContents::Markup(markup) = hover.contents else {
+        panic!("expected markup");
+    };
+    assert!(markup.value.contains("sqrt(4) = 2"), "{}", markup.value);
+
+    // A document which was never opened is an error.
+    let response = request(
+        &client,
+        3,
+        HoverRequest::METHOD,
+        HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(Url::parse("file:///other.eq").unwrap()),
+                Position::new(0, 0),
+            ),
+            work_done_progress_params: Default::default(),
+        },
+    );
+    assert!(response.error.is_some());
+
+    let response = request(&client, 4, Shutdown::METHOD, ());
+    assert!(response.error.is_none());
+    notify(&client, Exit::METHOD, ());
+
+    thread.join().unwrap();
+}
@@ -0,0 +1,156 @@
+//! A resolver for common mathematical constants and functions.
+//!
+//! [`Math`] may be used on its own or by another resolver for the names it does not provide itself.
+
+use equation_eval::{resolve::Resolver, ErrorKind, Evaluated, Type};
+use rust_decimal::{prelude::ToPrimitive, Decimal, MathematicalOps, RoundingStrategy};
+
+/// The name and description of a constant or function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub name: &'static str,
+
+    /// The names of the parameters, which is empty for a constant.
+    pub params: &'static [&'static str],
+
+    /// A single sentence describing the constant or function.
+    pub doc: &'static str,
+}
+
+/// The constants provided by [`Math`].
+pub const CONSTANTS: &[Signature] = &[
+    constant(
+        "pi",
+        "The ratio of a circle's circumference to its diameter.",
+    ),
+    constant(
+        "tau",
+        "The ratio of a circle's circumference to its radius, 2π.",
+    ),
+    constant("e", "Euler's number, the base of the natural logarithm."),
+];
+
+/// The functions provided by [`Math`].
+pub const FUNCTIONS: &[Signature] = &[
+    function("abs", &["x"], "The absolute value of x."),
+    function("sqrt", &["x"], "The square root of x."),
+    function("exp", &["x"], "e raised to the power of x."),
+    function("ln", &["x"], "The natural logarithm of x."),
+    function("log", &["x"], "The base 10 logarithm of x."),
+    function("sin", &["x"], "The sine of x radians."),
+    function("cos", &["x"], "The cosine of x radians."),
+    function("tan", &["x"], "The tangent of x radians."),
+    function(
+        "floor",
+        &["x"],
+        "The largest integer less than or equal to x.",
+    ),
+    function(
+        "ceil",
+        &["x"],
+        "The smallest integer greater than or equal to x.",
+    ),
+    function(
+        "round",
+        &["x", "digits"],
+        "x rounded to a number of decimal places, half away from zero.",
+    ),
+];
+
+const fn constant(name: &'static str, doc: &'static str) -> Signature {
+    Signature {
+        name,
+        params: &[],
+        doc,
+    }
+}
+
+const fn function(
+    name: &'static str,
+    params: &'static [&'static str],
+    doc: &'static str,
+) -> Signature {
+    Signature { name, params, doc }
+}
+
+/// A resolver for the [`CONSTANTS`] and [`FUNCTIONS`].
+pub struct Math;
+
+impl Resolver for Math {
+    fn resolve_const_symbol(&self, name: &str) -> Option<Evaluated> {
+        let value = match name {
+            "pi" => Decimal::PI,
+            "tau" => Decimal::TWO_PI,
+            "e" => Decimal::E,
+            _ => return None,
+        };
+
+        Some(Evaluated::Decimal(value))
+    }
+
+    fn resolve_function(&self, name: &str, args: &[Evaluated]) -> Result<Evaluated, ErrorKind> {
+        let signature = FUNCTIONS
+            .iter()
+            .find(|signature| signature.name == name)
+            .ok_or(ErrorKind::UnknownFunction)?;
+
+        if args.len() != signature.params.len() {
+            return Err(ErrorKind::ArgumentCount {
+                expected: signature.params.len(),
+                found: args.len(),
+            });
+        }
+
+        let mut numbers = args.iter().map(|arg| match arg {
+            Evaluated::Decimal(decimal) => Ok(*decimal),
+            value => Err(ErrorKind::TypeMismatch {
+                expected: Type::Decimal,
+                found: value.ty(),
+            }),
+        });
+
+        let x = numbers.next().ok_or(ErrorKind::MissingOperand)??;
+
+        let value = match name {
+            "abs" => Some(x.abs()),
+            "sqrt" if x.is_sign_negative() && !x.is_zero() => return Err(ErrorKind::Undefined),
+            "sqrt" => x.sqrt(),
+            "exp" => x.checked_exp(),
+            "ln" | "log" if x <= Decimal::ZERO => return Err(ErrorKind::Undefined),
+            "ln" => x.checked_ln(),
+            "log" => x.checked_log10(),
+            "sin" => x.checked_sin(),
+            "cos" => x.checked_cos(),
+            "tan" => x.checked_tan(),
+            "floor" => Some(x.floor()),
+            "ceil" => Some(x.ceil()),
+            "round" => {
+                let digits = numbers.next().ok_or(ErrorKind::MissingOperand)??;
+                let digits = digits
+                    .is_integer()
+                    .then(|| digits.to_u32())
+                    .flatten()
+                    .ok_or(ErrorKind::InvalidArgument)?;
+
+                Some(x.round_dp_with_strategy(digits, RoundingStrategy::MidpointAwayFromZero))
+            }
+            _ => return Err(ErrorKind::UnknownFunction),
+        };
+
+        value
+            .map(|value| Evaluated::Decimal(value.normalize()))
+            .ok_or(ErrorKind::Overflow)
+    }
+
+    fn has_function(&self, name: &str) -> bool {
+        FUNCTIONS.iter().any(|signature| signature.name == name)
+    }
+
+    fn const_symbols(&self) -> Vec<&str> {
+        CONSTANTS.iter().map(|signature| signature.name).collect()
+    }
+
+    fn functions(&self) -> Vec<&str> {
+        FUNCTIONS.iter().map(|signature| signature.name).collect()
+    }
+}
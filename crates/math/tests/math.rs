@@ -0,0 +1,65 @@
+//! The constants and functions of the `Math` resolver
+
+use equation_eval::{eval, ErrorKind, Evaluated};
+use equation_math::Math;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+macro_rules! generate_test {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let value = eval(&Math, $input).unwrap();
+            assert_eq!(
+                value,
+                Evaluated::Decimal(Decimal::from_str($expected).unwrap())
+            );
+        }
+    };
+}
+
+macro_rules! generate_error {
+    ($name: ident: $input: expr, $expected: expr) => {
+        #[test]
+        fn $name() {
+            let err = eval(&Math, $input).unwrap_err();
+            assert_eq!(err.kind, $expected);
+        }
+    };
+}
+
+generate_test!(pi: "pi", "3.1415926535897932384626433833");
+generate_test!(tau_is_two_pi: "tau - 2 * pi", "0");
+generate_test!(abs: "abs(-2.5)", "2.5");
+generate_test!(sqrt: "sqrt(16)", "4");
+generate_test!(sqrt_zero: "sqrt(0)", "0");
+generate_test!(ln_e: "round(ln(e), 10)", "1");
+generate_test!(log: "round(log(1000), 10)", "3");
+generate_test!(exp_zero: "exp(0)", "1");
+generate_test!(sin_zero: "sin(0)", "0");
+generate_test!(cos_pi: "round(cos(pi), 10)", "-1");
+generate_test!(floor: "floor(-1.5)", "-2");
+generate_test!(ceil: "ceil(1.2)", "2");
+generate_test!(round_half_away: "round(2.5, 0) + round(-2.5, 0)", "0");
+generate_test!(round_digits: "round(1.23456, 2)", "1.23");
+generate_test!(nested: "sqrt(abs(-9)) * 2", "6");
+
+generate_error!(sqrt_negative: "sqrt(-1)", ErrorKind::Undefined);
+generate_error!(ln_zero: "ln(0)", ErrorKind::Undefined);
+generate_error!(log_negative: "log(-10)", ErrorKind::Undefined);
+generate_error!(round_fraction: "round(1.5, 0.5)", ErrorKind::InvalidArgument);
+generate_error!(unknown: "sinh(1)", ErrorKind::UnknownFunction);
+generate_error!(
+    arity: "sqrt(1, 2)",
+    ErrorKind::ArgumentCount {
+        expected: 1,
+        found: 2
+    }
+);
+generate_error!(
+    not_a_number: "abs(\"x\")",
+    ErrorKind::TypeMismatch {
+        expected: equation_eval::Type::Decimal,
+        found: equation_eval::Type::Str
+    }
+);